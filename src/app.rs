@@ -0,0 +1,53 @@
+//! App extensions for persistent resources.
+
+use crate::prelude::*;
+
+/// App extensions for persistent resources.
+pub trait PersistentAppExt {
+    /// Initializes a persistent resource in a startup system.
+    ///
+    /// The builder of the resource is created with access to the world,
+    /// so the resource can be configured using other resources
+    /// (e.g., its path can come from a resource computed at startup).
+    ///
+    /// # Panics
+    ///
+    /// The startup system panics if the resource cannot be built.
+    fn init_persistent<R: Resource + Serialize + DeserializeOwned>(
+        &mut self,
+        builder: impl Fn(&mut World) -> PersistentBuilder<R> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Registers a persistent resource to `PersistentRegistry`
+    /// (e.g., to list it in a debugging UI).
+    ///
+    /// Persistent resources initialized with `init_persistent` are registered automatically.
+    fn register_persistent<R: Resource + Serialize + DeserializeOwned>(&mut self) -> &mut Self;
+}
+
+impl PersistentAppExt for App {
+    fn init_persistent<R: Resource + Serialize + DeserializeOwned>(
+        &mut self,
+        builder: impl Fn(&mut World) -> PersistentBuilder<R> + Send + Sync + 'static,
+    ) -> &mut App {
+        self.register_persistent::<R>();
+        self.add_systems(Startup, move |world: &mut World| {
+            let builder = builder(world);
+            let name = builder.name.clone().unwrap_or_default();
+            match builder.build() {
+                Ok(persistent) => {
+                    world.insert_resource(persistent);
+                },
+                Err(error) => {
+                    panic!("failed to initialize {}: {}", name, error);
+                },
+            }
+        })
+    }
+
+    fn register_persistent<R: Resource + Serialize + DeserializeOwned>(&mut self) -> &mut App {
+        self.init_resource::<PersistentRegistry>();
+        self.world_mut().resource_mut::<PersistentRegistry>().register::<R>();
+        self
+    }
+}
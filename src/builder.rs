@@ -1,26 +1,61 @@
 //! A builder for a persistent resource.
 
 use crate::prelude::*;
+use std::collections::HashMap;
 
 /// A builder for a persistent resource.
 pub struct PersistentBuilder<R: Resource + Serialize + DeserializeOwned> {
-    pub(crate) name: Option<String>,
+    pub(crate) name: Option<Cow<'static, str>>,
     pub(crate) format: Option<StorageFormat>,
     pub(crate) path: Option<PathBuf>,
+    pub(crate) path_template: Option<(String, HashMap<String, String>)>,
+    pub(crate) fallback_path: Option<PathBuf>,
+    #[cfg(target_family = "wasm")]
+    pub(crate) key_prefix: Option<String>,
     pub(crate) loaded: bool,
     pub(crate) default: Option<R>,
     pub(crate) revertible: bool,
+    pub(crate) retain_default: bool,
     pub(crate) revert_to_default_on_deserialization_errors: bool,
+    pub(crate) quiet: bool,
+    pub(crate) create_if_missing: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) create_dirs: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) relative_to_exe: bool,
+    pub(crate) deny_unknown_fields: bool,
+    pub(crate) fsync: bool,
+    pub(crate) persist_on_drop: bool,
+    pub(crate) skip_redundant_writes: bool,
+    pub(crate) history: usize,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) check_free_space: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) max_read_bytes: Option<u64>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) follow_symlinks: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) temp_dir: Option<PathBuf>,
+    pub(crate) format_options: FormatOptions,
+    pub(crate) hooks: Hooks<R>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) merge: Option<fn(&mut R, R)>,
+    #[cfg(target_family = "wasm")]
+    pub(crate) cache: Option<fn(&R) -> R>,
 }
 
 impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
     /// Sets the name of the resource.
-    pub fn name(mut self, name: impl ToString) -> PersistentBuilder<R> {
-        self.name = Some(name.to_string());
+    ///
+    /// Static names (e.g., `"key bindings"`) are stored without allocating.
+    pub fn name(mut self, name: impl Into<Cow<'static, str>>) -> PersistentBuilder<R> {
+        self.name = Some(name.into());
         self
     }
 
     /// Sets the storage format of the resource.
+    ///
+    /// If it's not set, it's inferred from the extension of the path (e.g., `settings.toml`).
     pub fn format(mut self, format: StorageFormat) -> PersistentBuilder<R> {
         self.format = Some(format);
         self
@@ -29,6 +64,59 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
     /// Sets the path of the resource.
     pub fn path(mut self, path: impl Into<PathBuf>) -> PersistentBuilder<R> {
         self.path = Some(path.into());
+        self.path_template = None;
+        self
+    }
+
+    /// Sets the path of the resource from a template.
+    ///
+    /// Placeholders in the template (e.g., `{profile}` in `profiles/{profile}/settings.toml`)
+    /// are expanded using the context when the resource is built, and building fails
+    /// with `PersistenceError::MissingPathPlaceholder` if a placeholder is not in the context.
+    pub fn path_template(
+        mut self,
+        template: &str,
+        context: &HashMap<String, String>,
+    ) -> PersistentBuilder<R> {
+        self.path = None;
+        self.path_template = Some((template.to_owned(), context.clone()));
+        self
+    }
+
+    /// Sets the fallback path of the resource.
+    ///
+    /// If the resource doesn't exist in its path but exists in its fallback path,
+    /// it's loaded from the fallback path and saved to its path (e.g., to migrate
+    /// the resource from a legacy location).
+    pub fn fallback_path(mut self, fallback_path: impl Into<PathBuf>) -> PersistentBuilder<R> {
+        self.fallback_path = Some(fallback_path.into());
+        self
+    }
+
+    /// Sets the prefix of the browser storage key of the resource
+    /// (e.g., to avoid clashes between multiple games on the same origin).
+    ///
+    /// Key is prefixed with the prefix followed by `::` (e.g., `mygame::settings.toml`).
+    /// Fallback path is not prefixed, so resources can be migrated from unprefixed keys.
+    #[cfg(target_family = "wasm")]
+    pub fn key_prefix(mut self, key_prefix: &str) -> PersistentBuilder<R> {
+        self.key_prefix = Some(key_prefix.to_owned());
+        self
+    }
+
+    /// Sets whether the resource should be cached in memory to make reloading it cheap
+    /// (e.g., to reload a frequently reloaded resource without parsing the browser storage).
+    ///
+    /// Cache is invalidated when the browser storage is written or removed by this crate,
+    /// but changes made to it externally (e.g., by JavaScript) are not detected, so reloading
+    /// after such changes returns a stale resource. Cached resources are not passed through
+    /// the load hook or the validator again when they are reloaded.
+    #[cfg(target_family = "wasm")]
+    pub fn cache(mut self, cache: bool) -> PersistentBuilder<R>
+    where
+        R: Clone,
+    {
+        self.cache = if cache { Some(R::clone) } else { None };
         self
     }
 
@@ -56,6 +144,15 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
         self
     }
 
+    /// Sets whether the default value of the resource should be retained
+    /// even if the resource is not revertible (e.g., to show it with `Persistent::default`).
+    ///
+    /// Defaults of revertible resources are always retained.
+    pub fn retain_default(mut self, retain_default: bool) -> PersistentBuilder<R> {
+        self.retain_default = retain_default;
+        self
+    }
+
     /// Sets whether the the resource should be reverted to default on deserialization errors.
     pub fn revert_to_default_on_deserialization_errors(
         mut self,
@@ -65,6 +162,279 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
             revert_to_default_on_deserialization_errors;
         self
     }
+
+    /// Sets whether routine logs of the resource (e.g., saves and loads) should be suppressed.
+    ///
+    /// Errors are logged regardless of this setting.
+    pub fn quiet(mut self, quiet: bool) -> PersistentBuilder<R> {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Sets whether the default value of the resource should be saved
+    /// to its underlying storage when the storage is missing.
+    ///
+    /// If set to `false`, the default value is only used in memory,
+    /// and the storage is created the first time the resource is persisted.
+    /// Resources which are not loaded keep their default value to load it later.
+    pub fn create_if_missing(mut self, create_if_missing: bool) -> PersistentBuilder<R> {
+        self.create_if_missing = create_if_missing;
+        self
+    }
+
+    /// Sets whether missing parent directories of the underlying storage should be created
+    /// (e.g., to make sure saves only land in directories that are set up beforehand).
+    ///
+    /// If set to `false`, writing the resource (e.g., initializing, persisting or snapshotting it,
+    /// or committing a transaction with it) fails with `PersistenceError::MissingDirectory`
+    /// when the parent directory of the storage it's written to doesn't exist.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn create_dirs(mut self, create_dirs: bool) -> PersistentBuilder<R> {
+        self.create_dirs = create_dirs;
+        self
+    }
+
+    /// Sets whether unknown fields should be denied when loading the resource.
+    ///
+    /// Unknown fields are detected by comparing the loaded document with the serialized form
+    /// of the loaded resource, so they can be denied without `#[serde(deny_unknown_fields)]`.
+    /// Fields that are skipped during serialization are reported as unknown as well.
+    ///
+    /// Only JSON and TOML storage formats support denying unknown fields,
+    /// and this setting is ignored for other storage formats.
+    pub fn deny_unknown_fields(mut self, deny_unknown_fields: bool) -> PersistentBuilder<R> {
+        self.deny_unknown_fields = deny_unknown_fields;
+        self
+    }
+
+    /// Sets whether writes of the resource should be flushed to the underlying device
+    /// before returning (e.g., to avoid losing saves on power failures).
+    ///
+    /// Syncing is slow, so it's disabled by default.
+    /// It has no effect on browser storages.
+    pub fn fsync(mut self, fsync: bool) -> PersistentBuilder<R> {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Sets whether the resource should be written to its underlying storage when it's dropped.
+    ///
+    /// Unloaded resources are not written, and failing to write the resource is only logged.
+    /// It's not carried over to builders created with `Persistent::to_builder`.
+    pub fn persist_on_drop(mut self, persist_on_drop: bool) -> PersistentBuilder<R> {
+        self.persist_on_drop = persist_on_drop;
+        self
+    }
+
+    /// Sets whether writing the resource should be skipped if the underlying storage
+    /// already has the same content (e.g., to avoid touching configs committed to git).
+    ///
+    /// Underlying storage is read and compared to the serialized resource before each write,
+    /// so it's disabled by default. Modification time of the underlying storage is not changed
+    /// when a write is skipped. It has no effect in append mode.
+    pub fn skip_redundant_writes(mut self, skip_redundant_writes: bool) -> PersistentBuilder<R> {
+        self.skip_redundant_writes = skip_redundant_writes;
+        self
+    }
+
+    /// Sets the maximum number of previous values of the resource to keep to undo changes
+    /// (see `Persistent::undo`).
+    ///
+    /// Values are remembered when they are loaded or persisted, and they are kept
+    /// in memory only, so history is lost when the application is restarted.
+    /// History is disabled (i.e., set to zero) by default.
+    pub fn history(mut self, history: usize) -> PersistentBuilder<R> {
+        self.history = history;
+        self
+    }
+
+    /// Sets whether free space of the underlying device should be checked
+    /// before writing the resource (e.g., to avoid partially written saves on full disks).
+    ///
+    /// If there isn't enough free space, writing fails with
+    /// `PersistenceError::InsufficientSpace` without touching the underlying storage.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn check_free_space(mut self, check_free_space: bool) -> PersistentBuilder<R> {
+        self.check_free_space = check_free_space;
+        self
+    }
+
+    /// Sets the maximum size of the underlying storage in bytes to read the resource from
+    /// (e.g., to avoid running out of memory while reading huge or corrupted files).
+    ///
+    /// Underlying storage is read up to the limit, and if it's larger than the limit,
+    /// reading fails with `PersistenceError::TooLarge`. There is no limit by default.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn max_read_bytes(mut self, max_read_bytes: u64) -> PersistentBuilder<R> {
+        self.max_read_bytes = Some(max_read_bytes);
+        self
+    }
+
+    /// Sets whether the resource should be written through symbolic links
+    /// (e.g., to refuse overwriting arbitrary files through planted symbolic links).
+    ///
+    /// Symbolic links are followed by default. If they are not, writing to an underlying storage
+    /// which is a symbolic link fails with `PersistenceError::Symlink` without touching it,
+    /// and symbolic links created after the check are replaced rather than written through.
+    /// Only the underlying storage itself is checked (i.e., its parent directories can still be
+    /// symbolic links), and reading through symbolic links is still allowed.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> PersistentBuilder<R> {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Sets the directory to create temporary files in while writing the resource
+    /// (e.g., when the directory of the resource is read-only but the resource itself is not).
+    ///
+    /// Resources are written into temporary files first, which are renamed to the underlying
+    /// storage afterwards, so errors don't leave it half written. Temporary files are created
+    /// next to the underlying storage by default, which keeps them on the same filesystem.
+    /// If the directory is on another filesystem, temporary files are copied into other temporary
+    /// files next to the underlying storage before renaming them, and a warning is logged.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn temp_dir(mut self, temp_dir: Option<PathBuf>) -> PersistentBuilder<R> {
+        self.temp_dir = temp_dir;
+        self
+    }
+
+    /// Sets whether relative paths should be resolved against the directory of the executable
+    /// instead of the current working directory (e.g., for portable builds which keep their
+    /// configuration next to the executable).
+    ///
+    /// Paths are resolved when the resource is built, and the fallback path is resolved
+    /// as well. If the path of the executable cannot be determined, a warning is logged
+    /// and paths are resolved against the current working directory.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn relative_to_exe(mut self, relative_to_exe: bool) -> PersistentBuilder<R> {
+        self.relative_to_exe = relative_to_exe;
+        self
+    }
+
+    /// Sets the hook to run after the resource is loaded from its underlying storage
+    /// (e.g., to validate or normalize the loaded resource).
+    ///
+    /// The hook runs after the resource is deserialized, including reloads,
+    /// and the resource in memory is the one modified by the hook.
+    pub fn on_load(
+        mut self,
+        on_load: impl Fn(&mut R) + Send + Sync + 'static,
+    ) -> PersistentBuilder<R> {
+        self.hooks.on_load = Some(Box::new(on_load));
+        self
+    }
+
+    /// Sets the hook to run before the resource is saved to its underlying storage.
+    pub fn on_save(mut self, on_save: impl Fn(&R) + Send + Sync + 'static) -> PersistentBuilder<R> {
+        self.hooks.on_save = Some(Box::new(on_save));
+        self
+    }
+
+    /// Sets the validator to run after the resource is loaded from its underlying storage.
+    ///
+    /// The validator runs after the load hook, and if it returns an error,
+    /// loading fails with `PersistenceError::Validation`, which is treated as
+    /// a deserialization error (e.g., the resource is reverted to default if
+    /// `revert_to_default_on_deserialization_errors` is set).
+    pub fn validator(
+        mut self,
+        validator: impl Fn(&R) -> Result<(), String> + Send + Sync + 'static,
+    ) -> PersistentBuilder<R> {
+        self.hooks.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Sets the line ending of text storage formats
+    /// (e.g., to avoid diff noise in version controlled configuration files).
+    ///
+    /// Line endings are normalized when the resource is written, and binary storage formats
+    /// are not affected. Line breaks in multi-line strings are normalized as well.
+    ///
+    /// If it's not set, line endings are kept as storage formats serialize them
+    /// (e.g., INI uses `\r\n` while the others use `\n`).
+    pub fn line_ending(mut self, line_ending: LineEnding) -> PersistentBuilder<R> {
+        self.format_options.line_ending = Some(line_ending);
+        self
+    }
+
+    /// Sets the header comment of the underlying storage
+    /// (e.g., to warn users that their edits may be overwritten).
+    ///
+    /// The header is written as line comments at the beginning of the underlying storage
+    /// in text storage formats which support comments (i.e., INI, RON, TOML and YAML),
+    /// and it's ignored when the resource is read as it's a comment.
+    ///
+    /// JSON doesn't support comments and binary storage formats are not meant to be edited,
+    /// so the header is skipped for them. It's skipped in append mode as well.
+    pub fn header(mut self, header: impl Into<String>) -> PersistentBuilder<R> {
+        self.format_options.header = Some(header.into());
+        self
+    }
+
+    /// Sets the options of INI storage formats.
+    ///
+    /// INI only supports flat resources (see `StorageFormat::Ini`), and shapes of resources
+    /// are checked before they are serialized by default to report it clearly.
+    #[cfg(feature = "ini")]
+    pub fn ini_options(mut self, ini_options: IniOptions) -> PersistentBuilder<R> {
+        self.format_options.ini = ini_options;
+        self
+    }
+
+    /// Sets the configuration of the layout of TOML storage formats
+    /// (e.g., to format small tables as inline tables and sort keys for stable diffs).
+    #[cfg(feature = "toml-edit")]
+    pub fn toml_config(mut self, toml_config: TomlConfig) -> PersistentBuilder<R> {
+        self.format_options.toml = toml_config;
+        self
+    }
+
+    /// Sets the options of RON storage formats.
+    ///
+    /// Options are used both for serialization and deserialization
+    /// (e.g., `ron::extensions::Extensions::IMPLICIT_SOME` can be enabled
+    /// to serialize `Some(value)` as `value`).
+    #[cfg(feature = "ron")]
+    pub fn ron_options(mut self, ron_options: ron::Options) -> PersistentBuilder<R> {
+        self.format_options.ron = ron_options;
+        self
+    }
+
+    /// Sets the maximum number of aliases in YAML storage formats
+    /// (e.g., to guard against alias bombs in user provided files).
+    ///
+    /// Aliases can expand to exponentially large values (i.e., "billion laughs"),
+    /// so documents with more aliases than the limit are rejected before they are parsed
+    /// with `PersistenceError::YamlAliasLimitExceeded`. There is no limit by default.
+    #[cfg(feature = "yaml")]
+    pub fn yaml_alias_limit(mut self, yaml_alias_limit: usize) -> PersistentBuilder<R> {
+        self.format_options.yaml_alias_limit = Some(yaml_alias_limit);
+        self
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned + Merge> PersistentBuilder<R> {
+    /// Sets whether the resource should be stored as an append-only log of records.
+    ///
+    /// See `Merge` for the semantics of append mode.
+    ///
+    /// Records are newline-delimited, so only storage formats which serialize resources
+    /// into a single line (i.e., `StorageFormat::Json` and `StorageFormat::Ron`) are supported.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn append(mut self, append: bool) -> PersistentBuilder<R> {
+        self.merge = if append { Some(R::merge) } else { None };
+        self
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned + Default> Default for PersistentBuilder<R> {
+    /// Creates a persistent resource builder with the default value of the resource.
+    ///
+    /// The inherent [`PersistentBuilder::default`] setter takes precedence in method resolution,
+    /// so call this through the trait, e.g., `<PersistentBuilder<R> as Default>::default()`.
+    fn default() -> PersistentBuilder<R> {
+        Persistent::builder().default(R::default())
+    }
 }
 
 impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
@@ -72,11 +442,19 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
     ///
     /// # Panics
     ///
-    /// Panics if `name`, `path`, `format` or `default` is not set.
+    /// Panics if `name`, `path` (or `path_template`) or `default` is not set,
+    /// or if append mode is set for a storage format which doesn't support it.
+    ///
+    /// If `format` is not set, it's inferred from the extension of the path
+    /// (see `StorageFormat::from_extension`), and building fails with
+    /// `PersistenceError::UninferredStorageFormat` if the extension is not recognized.
     #[cfg(any(
         feature = "bincode",
+        feature = "bson",
         feature = "ini",
         feature = "json",
+        feature = "pickle",
+        feature = "protobuf",
         feature = "ron",
         feature = "toml",
         feature = "yaml",
@@ -85,10 +463,7 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
         if self.name.is_none() {
             panic!("persistent resource name is not set");
         }
-        if self.format.is_none() {
-            panic!("persistent resource format is not set");
-        }
-        if self.path.is_none() {
+        if self.path.is_none() && self.path_template.is_none() {
             panic!("persistent resource path is not set");
         }
         if self.default.is_none() {
@@ -96,54 +471,124 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
         }
 
         let name = self.name.unwrap();
-        let format = self.format.unwrap();
-        let path = self.path.unwrap();
+        let path = match self.path_template {
+            Some((template, context)) => {
+                PersistentBuilder::<R>::expand_path_template(&template, &context)?
+            },
+            None => self.path.unwrap(),
+        };
+        let format = match self.format {
+            Some(format) => format,
+            None => {
+                path.extension()
+                    .and_then(|extension| extension.to_str())
+                    .and_then(StorageFormat::from_extension)
+                    .ok_or_else(|| PersistenceError::UninferredStorageFormat(path.clone()))?
+            },
+        };
+        let fallback_path = self.fallback_path;
+        #[cfg(not(target_family = "wasm"))]
+        let (path, fallback_path) = if self.relative_to_exe {
+            (
+                PersistentBuilder::<R>::resolve_relative_to_exe(&name, path),
+                fallback_path
+                    .map(|path| PersistentBuilder::<R>::resolve_relative_to_exe(&name, path)),
+            )
+        } else {
+            (path, fallback_path)
+        };
+        #[cfg(target_family = "wasm")]
+        let key_prefix = self.key_prefix;
         let loaded = self.loaded;
         let default = self.default.unwrap();
         let revertible = self.revertible;
+        let retain_default = self.retain_default;
         let revert_to_default_on_deserialization_errors =
             self.revert_to_default_on_deserialization_errors;
+        let quiet = self.quiet;
+        let create_if_missing = self.create_if_missing;
+        #[cfg(not(target_family = "wasm"))]
+        let create_dirs = self.create_dirs;
+        let deny_unknown_fields = self.deny_unknown_fields;
+        let fsync = self.fsync;
+        let persist_on_drop = self.persist_on_drop;
+        let skip_redundant_writes = self.skip_redundant_writes;
+        let history = self.history;
+        #[cfg(not(target_family = "wasm"))]
+        let check_free_space = self.check_free_space;
+        #[cfg(not(target_family = "wasm"))]
+        let max_read_bytes = self.max_read_bytes;
+        #[cfg(not(target_family = "wasm"))]
+        let follow_symlinks = self.follow_symlinks;
+        #[cfg(not(target_family = "wasm"))]
+        let temp_dir = self.temp_dir;
+        let format_options = self.format_options;
+        let hooks = self.hooks;
+        #[cfg(not(target_family = "wasm"))]
+        let merge = self.merge;
+        #[cfg(target_family = "wasm")]
+        let cache = self.cache;
 
-        let storage = {
-            #[cfg(not(target_family = "wasm"))]
-            {
-                Storage::Filesystem { path: path.canonicalize().unwrap_or(path) }
-            }
-            #[cfg(target_family = "wasm")]
-            {
-                let separator = std::path::MAIN_SEPARATOR_STR;
-                let path = path.strip_prefix(separator).unwrap_or(&path);
-
-                if let Ok(Some(key)) = path.strip_prefix("local").map(|p| p.to_str()) {
-                    Storage::LocalStorage { key: key.to_owned() }
-                } else if let Ok(Some(key)) = path.strip_prefix("session").map(|p| p.to_str()) {
-                    Storage::SessionStorage { key: key.to_owned() }
-                } else {
-                    panic!(
-                        "persistent resource path should start with \
-                        \"local\" or \"session\" and be UTF-8 encoded \
-                        in WebAssembly but it's {:?}",
-                        path,
-                    );
-                }
-            }
+        #[cfg(not(target_family = "wasm"))]
+        if merge.is_some() && !format.is_single_line() {
+            panic!("append mode is not supported for {} storage format", format.as_str());
+        }
+
+        #[cfg(not(target_family = "wasm"))]
+        let storage = if follow_symlinks {
+            PersistentBuilder::<R>::storage(path)
+        } else {
+            PersistentBuilder::<R>::storage_without_following_symlinks(path)
+        };
+        #[cfg(target_family = "wasm")]
+        let storage = PersistentBuilder::<R>::storage(path);
+        #[cfg(target_family = "wasm")]
+        let storage = match key_prefix {
+            Some(key_prefix) => storage.with_key_prefix(&key_prefix),
+            None => storage,
         };
+        let fallback_storage = fallback_path.map(PersistentBuilder::<R>::storage);
 
-        Persistent::new(
-            name,
-            format,
-            storage,
-            loaded,
-            default,
-            revertible,
-            revert_to_default_on_deserialization_errors,
-        )
+        let mut persistent = Persistent::<R>::uninitialized(name, format, storage);
+        persistent.revert_to_default_on_deserialization_errors =
+            revert_to_default_on_deserialization_errors;
+        persistent.fallback_storage = fallback_storage;
+        persistent.retain_default = retain_default;
+        persistent.quiet = quiet;
+        persistent.create_if_missing = create_if_missing;
+        persistent.deny_unknown_fields = deny_unknown_fields;
+        persistent.fsync = fsync;
+        persistent.skip_redundant_writes = skip_redundant_writes;
+        persistent.history_limit = history;
+        persistent.format_options = format_options;
+        persistent.hooks = hooks;
+        #[cfg(not(target_family = "wasm"))]
+        {
+            persistent.create_dirs = create_dirs;
+            persistent.check_free_space = check_free_space;
+            persistent.max_read_bytes = max_read_bytes;
+            persistent.follow_symlinks = follow_symlinks;
+            persistent.temp_dir = temp_dir;
+            persistent.merge = merge;
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            persistent.cache = cache;
+        }
+        persistent.initialize(loaded, default, revertible).map(|mut persistent| {
+            // resources that fail to initialize shouldn't be written when they are dropped
+            persistent.persist_on_drop = persist_on_drop;
+            persistent
+        })
     }
 
     #[cfg(not(any(
         feature = "bincode",
+        feature = "bson",
         feature = "ini",
         feature = "json",
+        feature = "pickle",
+        feature = "protobuf",
         feature = "ron",
         feature = "toml",
         feature = "yaml",
@@ -151,4 +596,148 @@ impl<R: Resource + Serialize + DeserializeOwned> PersistentBuilder<R> {
     pub fn build(self) -> Result<Persistent<R>, PersistenceError> {
         unreachable!()
     }
+
+    /// Expands the placeholders of a path template using a context.
+    #[cfg(any(
+        feature = "bincode",
+        feature = "bson",
+        feature = "ini",
+        feature = "json",
+        feature = "pickle",
+        feature = "protobuf",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml",
+    ))]
+    fn expand_path_template(
+        template: &str,
+        context: &HashMap<String, String>,
+    ) -> Result<PathBuf, PersistenceError> {
+        let mut expanded = String::with_capacity(template.len());
+
+        let mut remaining = template;
+        while let Some(start) = remaining.find('{') {
+            let Some(length) = remaining[start + 1..].find('}') else {
+                break;
+            };
+
+            let placeholder = &remaining[start + 1..start + 1 + length];
+            let value = context
+                .get(placeholder)
+                .ok_or_else(|| PersistenceError::MissingPathPlaceholder(placeholder.to_owned()))?;
+
+            expanded.push_str(&remaining[..start]);
+            expanded.push_str(value);
+
+            remaining = &remaining[start + 1 + length + 1..];
+        }
+        expanded.push_str(remaining);
+
+        Ok(PathBuf::from(expanded))
+    }
+
+    /// Resolves a relative path against the directory of the executable.
+    #[cfg(all(
+        not(target_family = "wasm"),
+        any(
+            feature = "bincode",
+            feature = "bson",
+            feature = "ini",
+            feature = "json",
+            feature = "pickle",
+            feature = "protobuf",
+            feature = "ron",
+            feature = "toml",
+            feature = "yaml",
+        ),
+    ))]
+    fn resolve_relative_to_exe(name: &str, path: PathBuf) -> PathBuf {
+        if path.is_absolute() {
+            return path;
+        }
+        match std::env::current_exe() {
+            Ok(executable) => {
+                match executable.parent() {
+                    Some(directory) => directory.join(path),
+                    None => path,
+                }
+            },
+            Err(error) => {
+                log::warn!(
+                    "failed to get the path of the executable ({}), \
+                    so path of {} is resolved against the current working directory",
+                    error,
+                    name,
+                );
+                path
+            },
+        }
+    }
+
+    /// Gets the storage of a path.
+    #[cfg(any(
+        feature = "bincode",
+        feature = "bson",
+        feature = "ini",
+        feature = "json",
+        feature = "pickle",
+        feature = "protobuf",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml",
+    ))]
+    fn storage(path: PathBuf) -> Storage {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            Storage::Filesystem { path: path.canonicalize().unwrap_or(path) }
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            let separator = std::path::MAIN_SEPARATOR_STR;
+            let path = path.strip_prefix(separator).unwrap_or(&path);
+
+            if let Ok(Some(key)) = path.strip_prefix("local").map(|p| p.to_str()) {
+                Storage::LocalStorage { key: key.to_owned() }
+            } else if let Ok(Some(key)) = path.strip_prefix("session").map(|p| p.to_str()) {
+                Storage::SessionStorage { key: key.to_owned() }
+            } else {
+                panic!(
+                    "persistent resource path should start with \
+                    \"local\" or \"session\" and be UTF-8 encoded \
+                    in WebAssembly but it's {:?}",
+                    path,
+                );
+            }
+        }
+    }
+
+    /// Gets the storage of a path without resolving the path if it's a symbolic link.
+    ///
+    /// Only the parent directory of the path is canonicalized, so the storage can be checked
+    /// for being a symbolic link when it's written.
+    #[cfg(all(
+        not(target_family = "wasm"),
+        any(
+            feature = "bincode",
+            feature = "bson",
+            feature = "ini",
+            feature = "json",
+            feature = "pickle",
+            feature = "protobuf",
+            feature = "ron",
+            feature = "toml",
+            feature = "yaml",
+        ),
+    ))]
+    fn storage_without_following_symlinks(path: PathBuf) -> Storage {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => std::path::Path::new("."),
+        };
+        let path = match (parent.canonicalize(), path.file_name()) {
+            (Ok(parent), Some(file_name)) => parent.join(file_name),
+            _ => path,
+        };
+        Storage::Filesystem { path }
+    }
 }
@@ -13,6 +13,15 @@ pub enum PersistenceError {
         std::io::Error,
     ),
 
+    #[cfg(not(target_family = "wasm"))]
+    #[error("{source} ({name} at {storage})")]
+    Io {
+        name: String,
+        storage: String,
+        #[source]
+        source: std::io::Error,
+    },
+
     #[cfg(target_family = "wasm")]
     #[error("{0}")]
     Browser(
@@ -21,19 +30,110 @@ pub enum PersistenceError {
         gloo_storage::errors::StorageError,
     ),
 
+    #[error("{0}")]
+    Encoding(
+        #[from]
+        #[source]
+        std::str::Utf8Error,
+    ),
+
+    #[cfg(not(target_family = "wasm"))]
+    #[error("insufficient space on the device ({needed} bytes needed, {available} available)")]
+    InsufficientSpace { needed: u64, available: u64 },
+    #[cfg(not(target_family = "wasm"))]
+    #[error("directory {} does not exist", .0.display())]
+    MissingDirectory(PathBuf),
+    #[cfg(not(target_family = "wasm"))]
+    #[error("storage is too large to read ({size} bytes, limit is {limit} bytes)")]
+    TooLarge { size: u64, limit: u64 },
+    #[cfg(not(target_family = "wasm"))]
+    #[error("file manager is not available")]
+    FileManagerUnavailable,
+    #[cfg(not(target_family = "wasm"))]
+    #[error("storage {} is a symbolic link which is not followed", .0.display())]
+    Symlink(PathBuf),
+    #[cfg(not(target_family = "wasm"))]
+    #[error("writing didn't finish in {} milliseconds", .0.as_millis())]
+    Timeout(std::time::Duration),
+    #[cfg(not(target_family = "wasm"))]
+    #[error("writing thread panicked before finishing writing")]
+    WriterPanicked,
+
+    #[cfg(target_family = "wasm")]
+    #[error(
+        "browser storage quota is exceeded ({needed} needed, approximately {available} available)"
+    )]
+    QuotaExceeded { needed: usize, available: usize },
+
+    #[error("unknown storage format {0:?}")]
+    UnknownStorageFormat(String),
+    #[error("storage format {0:?} is not enabled")]
+    DisabledStorageFormat(String),
+    #[error("storage format {0} is binary so it cannot be represented as a string")]
+    BinaryStorageFormat(StorageFormat),
+    #[error(
+        "storage format is not set and it cannot be inferred from the extension of {}",
+        .0.display()
+    )]
+    UninferredStorageFormat(PathBuf),
     #[cfg(any(
         feature = "ini",
         feature = "json",
         feature = "ron",
         feature = "toml",
-        feature = "yaml"
+        feature = "yaml",
     ))]
-    #[error("{0}")]
-    Encoding(
-        #[from]
+    #[error("storage format couldn't be detected from the content of the storage")]
+    UndetectedStorageFormat,
+    #[cfg(any(
+        feature = "ini",
+        feature = "json",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml",
+    ))]
+    #[error("storage format is ambiguous as it's not detected from an occupied storage")]
+    AmbiguousStorageFormat,
+
+    #[error("path template placeholder {{{0}}} is not in the context")]
+    MissingPathPlaceholder(String),
+
+    #[error("validation failed: {0}")]
+    Validation(String),
+
+    #[error("default value is not retained")]
+    DefaultNotRetained,
+
+    #[cfg(feature = "json")]
+    #[error("JSON pointer {0:?} doesn't point to a value")]
+    InvalidJsonPointer(String),
+
+    #[cfg(feature = "path-to-error")]
+    #[error("{path}: {source}")]
+    AtPath {
+        path: String,
         #[source]
-        std::str::Utf8Error,
-    ),
+        source: Box<PersistenceError>,
+    },
+
+    #[error("{primary} (recovery failed: {during_recovery})")]
+    RecoveryFailed {
+        #[source]
+        primary: Box<PersistenceError>,
+        during_recovery: Box<PersistenceError>,
+    },
+
+    #[cfg(any(feature = "json", feature = "toml"))]
+    #[error("unknown fields {}", .0.join(", "))]
+    UnknownFields(Vec<String>),
+
+    #[cfg(feature = "yaml")]
+    #[error("YAML document has {aliases} aliases which is more than the limit of {limit}")]
+    YamlAliasLimitExceeded { aliases: usize, limit: usize },
+
+    #[cfg(feature = "ini")]
+    #[error("INI only supports flat resources but {0}")]
+    UnsupportedIniShape(String),
 
     #[cfg(feature = "bincode")]
     #[error("{0}")]
@@ -42,6 +142,13 @@ pub enum PersistenceError {
     #[error("{0}")]
     BincodeSerialization(#[source] bincode::Error),
 
+    #[cfg(feature = "bson")]
+    #[error("{0}")]
+    BsonDeserialization(#[source] bson::de::Error),
+    #[cfg(feature = "bson")]
+    #[error("{0}")]
+    BsonSerialization(#[source] bson::ser::Error),
+
     #[cfg(feature = "ini")]
     #[error("{0}")]
     IniDeserialization(#[source] serde_ini::de::Error),
@@ -56,6 +163,20 @@ pub enum PersistenceError {
     #[error("{0}")]
     JsonSerialization(#[source] serde_json::Error),
 
+    #[cfg(feature = "pickle")]
+    #[error("{0}")]
+    PickleDeserialization(#[source] serde_pickle::Error),
+    #[cfg(feature = "pickle")]
+    #[error("{0}")]
+    PickleSerialization(#[source] serde_pickle::Error),
+
+    #[cfg(feature = "protobuf")]
+    #[error("{0}")]
+    ProtobufDeserialization(#[source] crate::protobuf::Error),
+    #[cfg(feature = "protobuf")]
+    #[error("{0}")]
+    ProtobufSerialization(#[source] crate::protobuf::Error),
+
     #[cfg(feature = "ron")]
     #[error("{0}")]
     RonDeserialization(#[source] ron::Error),
@@ -79,19 +200,87 @@ pub enum PersistenceError {
 }
 
 impl PersistenceError {
+    /// Wraps filesystem errors with the name and the storage of a persistent resource.
+    #[cfg_attr(target_family = "wasm", allow(unused_variables))]
+    pub(crate) fn with_context(self, name: &str, storage: &Storage) -> PersistenceError {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::Filesystem(source) => {
+                PersistenceError::Io { name: name.to_owned(), storage: storage.to_string(), source }
+            },
+            error => error,
+        }
+    }
+
     pub fn is_serde(&self) -> bool {
         match self {
             #[cfg(not(target_family = "wasm"))]
             PersistenceError::Filesystem(_) => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::Io { .. } => false,
             #[cfg(target_family = "wasm")]
             PersistenceError::Browser(error) => {
                 matches!(error, gloo_storage::errors::StorageError::SerdeError(_))
             },
 
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::InsufficientSpace { .. } => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::MissingDirectory(_) => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::TooLarge { .. } => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::FileManagerUnavailable => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::Symlink(_) => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::Timeout(_) => false,
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::WriterPanicked => false,
+            #[cfg(target_family = "wasm")]
+            PersistenceError::QuotaExceeded { .. } => false,
+
+            PersistenceError::UnknownStorageFormat(_) => false,
+            PersistenceError::DisabledStorageFormat(_) => false,
+            PersistenceError::BinaryStorageFormat(_) => false,
+            PersistenceError::UninferredStorageFormat(_) => false,
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            PersistenceError::UndetectedStorageFormat => true,
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            PersistenceError::AmbiguousStorageFormat => false,
+
+            PersistenceError::MissingPathPlaceholder(_) => false,
+            PersistenceError::DefaultNotRetained => false,
+
+            PersistenceError::Encoding(_) => true,
+            PersistenceError::Validation(_) => true,
+
+            #[cfg(feature = "json")]
+            PersistenceError::InvalidJsonPointer(_) => false,
+
+            PersistenceError::RecoveryFailed { primary, .. } => primary.is_serde(),
+            #[cfg(feature = "path-to-error")]
+            PersistenceError::AtPath { source, .. } => source.is_serde(),
+
             #[cfg(any(
                 feature = "bincode",
+                feature = "bson",
                 feature = "ini",
                 feature = "json",
+                feature = "pickle",
+                feature = "protobuf",
                 feature = "ron",
                 feature = "toml",
                 feature = "yaml",
@@ -99,4 +288,214 @@ impl PersistenceError {
             _ => true,
         }
     }
+
+    /// Gets if the error occurred during serialization.
+    pub fn is_serialization(&self) -> bool {
+        match self {
+            PersistenceError::RecoveryFailed { primary, .. } => primary.is_serialization(),
+            #[cfg(feature = "path-to-error")]
+            PersistenceError::AtPath { source, .. } => source.is_serialization(),
+
+            #[cfg(feature = "bincode")]
+            PersistenceError::BincodeSerialization(_) => true,
+            #[cfg(feature = "bson")]
+            PersistenceError::BsonSerialization(_) => true,
+            #[cfg(feature = "ini")]
+            PersistenceError::IniSerialization(_) => true,
+            #[cfg(feature = "ini")]
+            PersistenceError::UnsupportedIniShape(_) => true,
+            #[cfg(feature = "json")]
+            PersistenceError::JsonSerialization(_) => true,
+            #[cfg(feature = "pickle")]
+            PersistenceError::PickleSerialization(_) => true,
+            #[cfg(feature = "protobuf")]
+            PersistenceError::ProtobufSerialization(_) => true,
+            #[cfg(feature = "ron")]
+            PersistenceError::RonSerialization(_) => true,
+            #[cfg(feature = "toml")]
+            PersistenceError::TomlSerialization(_) => true,
+            #[cfg(feature = "yaml")]
+            PersistenceError::YamlSerialization(_) => true,
+
+            _ => false,
+        }
+    }
+
+    /// Gets if the error occurred during deserialization.
+    ///
+    /// Decoding errors of text based storage formats are considered deserialization errors.
+    pub fn is_deserialization(&self) -> bool {
+        match self {
+            PersistenceError::RecoveryFailed { primary, .. } => primary.is_deserialization(),
+            #[cfg(feature = "path-to-error")]
+            PersistenceError::AtPath { source, .. } => source.is_deserialization(),
+
+            PersistenceError::Encoding(_) => true,
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            PersistenceError::UndetectedStorageFormat => true,
+            #[cfg(any(feature = "json", feature = "toml"))]
+            PersistenceError::UnknownFields(_) => true,
+            #[cfg(feature = "yaml")]
+            PersistenceError::YamlAliasLimitExceeded { .. } => true,
+            PersistenceError::Validation(_) => true,
+
+            #[cfg(feature = "bincode")]
+            PersistenceError::BincodeDeserialization(_) => true,
+            #[cfg(feature = "bson")]
+            PersistenceError::BsonDeserialization(_) => true,
+            #[cfg(feature = "ini")]
+            PersistenceError::IniDeserialization(_) => true,
+            #[cfg(feature = "json")]
+            PersistenceError::JsonDeserialization(_) => true,
+            #[cfg(feature = "pickle")]
+            PersistenceError::PickleDeserialization(_) => true,
+            #[cfg(feature = "protobuf")]
+            PersistenceError::ProtobufDeserialization(_) => true,
+            #[cfg(feature = "ron")]
+            PersistenceError::RonDeserialization(_) => true,
+            #[cfg(feature = "toml")]
+            PersistenceError::TomlDeserialization(_) => true,
+            #[cfg(feature = "yaml")]
+            PersistenceError::YamlDeserialization(_) => true,
+
+            _ => false,
+        }
+    }
+
+    /// Gets if the error occurred because the storage ran out of space
+    /// (e.g., the quota of the browser storage is exceeded).
+    pub fn is_quota(&self) -> bool {
+        match self {
+            #[cfg(target_family = "wasm")]
+            PersistenceError::Browser(gloo_storage::errors::StorageError::JsError(error)) => {
+                // name of the error is different in older versions of firefox
+                matches!(error.name.as_str(), "QuotaExceededError" | "NS_ERROR_DOM_QUOTA_REACHED")
+            },
+
+            #[cfg(not(target_family = "wasm"))]
+            PersistenceError::InsufficientSpace { .. } => true,
+            #[cfg(target_family = "wasm")]
+            PersistenceError::QuotaExceeded { .. } => true,
+
+            PersistenceError::RecoveryFailed { primary, .. } => primary.is_quota(),
+            #[cfg(feature = "path-to-error")]
+            PersistenceError::AtPath { source, .. } => source.is_quota(),
+
+            _ => false,
+        }
+    }
+
+    /// Gets the storage format the error is associated with.
+    ///
+    /// Errors of pretty storage formats are associated with their base storage formats
+    /// (e.g., errors of `StorageFormat::JsonPretty` are associated with `StorageFormat::Json`).
+    pub fn format(&self) -> Option<StorageFormat> {
+        match self {
+            PersistenceError::RecoveryFailed { primary, .. } => primary.format(),
+            #[cfg(feature = "path-to-error")]
+            PersistenceError::AtPath { source, .. } => source.format(),
+
+            #[cfg(feature = "bincode")]
+            PersistenceError::BincodeDeserialization(_)
+            | PersistenceError::BincodeSerialization(_) => Some(StorageFormat::Bincode),
+            #[cfg(feature = "bson")]
+            PersistenceError::BsonDeserialization(_) | PersistenceError::BsonSerialization(_) => {
+                Some(StorageFormat::Bson)
+            },
+            #[cfg(feature = "ini")]
+            PersistenceError::IniDeserialization(_) | PersistenceError::IniSerialization(_) => {
+                Some(StorageFormat::Ini)
+            },
+            #[cfg(feature = "json")]
+            PersistenceError::JsonDeserialization(_) | PersistenceError::JsonSerialization(_) => {
+                Some(StorageFormat::Json)
+            },
+            #[cfg(feature = "pickle")]
+            PersistenceError::PickleDeserialization(_)
+            | PersistenceError::PickleSerialization(_) => Some(StorageFormat::Pickle),
+            #[cfg(feature = "protobuf")]
+            PersistenceError::ProtobufDeserialization(_)
+            | PersistenceError::ProtobufSerialization(_) => Some(StorageFormat::Protobuf),
+            #[cfg(feature = "ron")]
+            PersistenceError::RonDeserialization(_) | PersistenceError::RonSerialization(_) => {
+                Some(StorageFormat::Ron)
+            },
+            #[cfg(feature = "toml")]
+            PersistenceError::TomlDeserialization(_) | PersistenceError::TomlSerialization(_) => {
+                Some(StorageFormat::Toml)
+            },
+            #[cfg(feature = "yaml")]
+            PersistenceError::YamlDeserialization(_) | PersistenceError::YamlSerialization(_) => {
+                Some(StorageFormat::Yaml)
+            },
+            #[cfg(feature = "yaml")]
+            PersistenceError::YamlAliasLimitExceeded { .. } => Some(StorageFormat::Yaml),
+            #[cfg(feature = "ini")]
+            PersistenceError::UnsupportedIniShape(_) => Some(StorageFormat::Ini),
+
+            _ => None,
+        }
+    }
+
+    /// Gets the underlying error of the storage format.
+    ///
+    /// It can be downcasted to the error type of the storage format
+    /// (e.g., `toml::de::Error`) to extract format specific information such as spans.
+    pub fn source_serde(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistenceError::RecoveryFailed { primary, .. } => primary.source_serde(),
+            #[cfg(feature = "path-to-error")]
+            PersistenceError::AtPath { source, .. } => source.source_serde(),
+
+            #[cfg(feature = "bincode")]
+            PersistenceError::BincodeDeserialization(error)
+            | PersistenceError::BincodeSerialization(error) => Some(&**error),
+            #[cfg(feature = "bson")]
+            PersistenceError::BsonDeserialization(error) => Some(error),
+            #[cfg(feature = "bson")]
+            PersistenceError::BsonSerialization(error) => Some(error),
+            #[cfg(feature = "ini")]
+            PersistenceError::IniDeserialization(error) => Some(error),
+            #[cfg(feature = "ini")]
+            PersistenceError::IniSerialization(error) => Some(error),
+            #[cfg(feature = "json")]
+            PersistenceError::JsonDeserialization(error)
+            | PersistenceError::JsonSerialization(error) => Some(error),
+            #[cfg(feature = "pickle")]
+            PersistenceError::PickleDeserialization(error)
+            | PersistenceError::PickleSerialization(error) => Some(error),
+            #[cfg(feature = "protobuf")]
+            PersistenceError::ProtobufDeserialization(error)
+            | PersistenceError::ProtobufSerialization(error) => Some(error),
+            #[cfg(feature = "ron")]
+            PersistenceError::RonDeserialization(error)
+            | PersistenceError::RonSerialization(error) => Some(error),
+            #[cfg(feature = "toml")]
+            PersistenceError::TomlDeserialization(error) => Some(error),
+            #[cfg(feature = "toml")]
+            PersistenceError::TomlSerialization(error) => Some(error),
+            #[cfg(feature = "yaml")]
+            PersistenceError::YamlDeserialization(error)
+            | PersistenceError::YamlSerialization(error) => Some(error),
+
+            _ => None,
+        }
+    }
+
+    /// Gets the path of the field which failed to be deserialized (e.g., `audio.volume`).
+    #[cfg(feature = "path-to-error")]
+    pub fn field_path(&self) -> Option<&str> {
+        match self {
+            PersistenceError::RecoveryFailed { primary, .. } => primary.field_path(),
+            PersistenceError::AtPath { path, .. } => Some(path),
+
+            _ => None,
+        }
+    }
 }
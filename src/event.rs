@@ -0,0 +1,25 @@
+//! Events of persistent resources.
+
+use crate::prelude::*;
+
+/// An operation on a persistent resource.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operation {
+    /// Writing the resource to its underlying storage.
+    Persist,
+    /// Reading the resource from its underlying storage.
+    Reload,
+}
+
+/// An event that is sent when an operation on a persistent resource fails.
+#[derive(Clone, Debug, Event)]
+pub struct PersistenceFailed {
+    /// Name of the resource.
+    pub name: String,
+    /// Underlying storage of the resource.
+    pub storage: Storage,
+    /// Error that caused the operation to fail.
+    pub error: String,
+    /// Operation that failed.
+    pub operation: Operation,
+}
@@ -7,12 +7,37 @@ use crate::prelude::*;
 pub enum StorageFormat {
     #[cfg(feature = "bincode")]
     Bincode,
+    /// BSON.
+    ///
+    /// BSON requires a document at the top level, so resources that aren't serialized
+    /// as documents (e.g., a tuple struct or a number) fail to be serialized.
+    #[cfg(feature = "bson")]
+    Bson,
+    /// INI, which only supports flat resources
+    /// (i.e., a struct or a map of values at the top level).
+    ///
+    /// `serde_ini` doesn't support sections, so resources with nested structs, maps, sequences
+    /// or enums with data fail to be serialized with `PersistenceError::UnsupportedIniShape`.
     #[cfg(feature = "ini")]
     Ini,
     #[cfg(feature = "json")]
     Json,
     #[cfg(all(feature = "json", feature = "pretty"))]
     JsonPretty,
+    /// Pretty JSON with a custom indentation.
+    ///
+    /// Its canonical name is `json-pretty-with`, and it's displayed with its indentation
+    /// (e.g., `json-pretty-with-tabs` or `json-pretty-with-4-spaces`) to be parsed back.
+    #[cfg(all(feature = "json", feature = "pretty"))]
+    JsonPrettyWith(JsonIndent),
+    /// Pickle (protocol 3), which can be loaded by `pickle.load` of CPython 3.
+    #[cfg(feature = "pickle")]
+    Pickle,
+    /// Protocol buffers, which only supports `Protobuf<M>` resources.
+    ///
+    /// See `protobuf` module for details.
+    #[cfg(feature = "protobuf")]
+    Protobuf,
     #[cfg(feature = "ron")]
     Ron,
     #[cfg(all(feature = "ron", feature = "pretty"))]
@@ -25,12 +50,710 @@ pub enum StorageFormat {
     TomlPretty,
     #[cfg(feature = "yaml")]
     Yaml,
+    /// YAML with an explicit document start marker (i.e., `---`).
+    ///
+    /// Indentation is not configurable as it's not supported by `serde_yaml`.
+    #[cfg(all(feature = "yaml", feature = "pretty"))]
+    YamlPretty,
+    /// Storage format detected from the content of the storage when it's read.
+    ///
+    /// Enabled text storage formats are tried in the order of JSON, TOML, RON, YAML and INI,
+    /// and the first one that deserializes the resource successfully is used. Content which is
+    /// valid in multiple storage formats (e.g., JSON is valid YAML as well) is detected as the
+    /// first one in this order, and a resource with few required fields may be deserialized
+    /// from content in an unexpected storage format, especially in lenient ones like INI.
+    ///
+    /// Resources cannot be written in this storage format as it's ambiguous, so persistent
+    /// resources using it detect their actual storage format from their underlying storages
+    /// when they are built (see `Persistent::format`), and building them fails if their
+    /// underlying storages are not occupied.
+    #[cfg(any(
+        feature = "ini",
+        feature = "json",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml",
+    ))]
+    Auto,
+}
+
+/// Options of storage formats.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FormatOptions {
+    /// Line ending of text storage formats, or `None` to keep line endings of serializers.
+    pub(crate) line_ending: Option<LineEnding>,
+    /// Header comment of text storage formats which support comments.
+    pub(crate) header: Option<String>,
+    /// Options of INI storage formats.
+    #[cfg(feature = "ini")]
+    pub(crate) ini: IniOptions,
+    /// Options of RON storage formats.
+    #[cfg(feature = "ron")]
+    pub(crate) ron: ron::Options,
+    /// Configuration of TOML storage formats.
+    #[cfg(feature = "toml-edit")]
+    pub(crate) toml: TomlConfig,
+    /// Maximum number of aliases in YAML storage formats.
+    #[cfg(feature = "yaml")]
+    pub(crate) yaml_alias_limit: Option<usize>,
+}
+
+/// A line ending of text storage formats.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Reflect)]
+pub enum LineEnding {
+    /// Line feed (i.e., `\n`).
+    #[default]
+    Lf,
+    /// Carriage return followed by line feed (i.e., `\r\n`).
+    CrLf,
+    /// Line ending of the target platform (i.e., `CrLf` on Windows and `Lf` elsewhere).
+    Native,
+}
+
+impl LineEnding {
+    /// Normalizes line endings of text to the line ending.
+    pub(crate) fn normalize(self, text: Vec<u8>) -> Vec<u8> {
+        let crlf = self.is_crlf();
+        if !crlf && !text.contains(&b'\r') {
+            return text;
+        }
+
+        let mut normalized = Vec::with_capacity(text.len());
+        let mut bytes = text.into_iter().peekable();
+        while let Some(byte) = bytes.next() {
+            if byte == b'\r' && bytes.peek() == Some(&b'\n') {
+                continue;
+            }
+            if byte == b'\n' && crlf {
+                normalized.push(b'\r');
+            }
+            normalized.push(byte);
+        }
+        normalized
+    }
+
+    /// Gets if line feeds are preceded by carriage returns in the line ending.
+    fn is_crlf(self) -> bool {
+        match self {
+            LineEnding::Lf => false,
+            LineEnding::CrLf => true,
+            LineEnding::Native => cfg!(windows),
+        }
+    }
+}
+
+/// A writer which normalizes line endings of text on the fly
+/// and counts the number of bytes written to the underlying writer.
+///
+/// Normalization is the same as `LineEnding::normalize`, but across writes.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) struct NormalizingWriter<W: std::io::Write> {
+    inner: W,
+    /// Whether line feeds are preceded by carriage returns, or `None` to write bytes as is.
+    crlf: Option<bool>,
+    /// Whether the last byte written is a carriage return which is not written yet.
+    pending_carriage_return: bool,
+    written: usize,
+    /// The error of the underlying writer, to tell it apart from serialization errors.
+    error: Option<std::io::Error>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl<W: std::io::Write> NormalizingWriter<W> {
+    /// Creates a writer which normalizes line endings to the line ending, if there is one.
+    pub(crate) fn new(inner: W, line_ending: Option<LineEnding>) -> NormalizingWriter<W> {
+        NormalizingWriter {
+            inner,
+            crlf: line_ending.map(LineEnding::is_crlf),
+            pending_carriage_return: false,
+            written: 0,
+            error: None,
+        }
+    }
+
+    /// Takes the error of the underlying writer, if writing failed because of it.
+    pub(crate) fn take_error(&mut self) -> Option<PersistenceError> {
+        self.error.take().map(PersistenceError::from)
+    }
+
+    /// Writes the remaining bytes and gets the number of bytes written to the underlying writer.
+    pub(crate) fn finish(mut self) -> Result<usize, PersistenceError> {
+        if std::mem::take(&mut self.pending_carriage_return) {
+            self.write_through(b"\r")?;
+        }
+        self.inner.flush()?;
+        Ok(self.written)
+    }
+
+    /// Writes bytes to the underlying writer and remembers its error.
+    fn write_through(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        match self.inner.write_all(bytes) {
+            Ok(()) => {
+                self.written += bytes.len();
+                Ok(())
+            },
+            Err(error) => {
+                let kind = error.kind();
+                self.error = Some(error);
+                Err(kind.into())
+            },
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl<W: std::io::Write> std::io::Write for NormalizingWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        let crlf = match self.crlf {
+            Some(crlf) => crlf,
+            None => {
+                self.write_through(bytes)?;
+                return Ok(bytes.len());
+            },
+        };
+
+        let mut normalized = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            if std::mem::take(&mut self.pending_carriage_return) && byte != b'\n' {
+                normalized.push(b'\r');
+            }
+            if byte == b'\r' {
+                self.pending_carriage_return = true;
+                continue;
+            }
+            if byte == b'\n' && crlf {
+                normalized.push(b'\r');
+            }
+            normalized.push(byte);
+        }
+        self.write_through(&normalized)?;
+
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Options of INI storage formats.
+#[cfg(feature = "ini")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Reflect)]
+pub struct IniOptions {
+    /// Whether the shape of the resource is checked before it's serialized
+    /// to fail with `PersistenceError::UnsupportedIniShape` if it's not flat.
+    ///
+    /// It's enabled by default. Resources which are not flat still fail to be serialized
+    /// without the check, but with the less clear errors of `serde_ini`.
+    pub check_shape: bool,
+}
+
+#[cfg(feature = "ini")]
+impl Default for IniOptions {
+    fn default() -> IniOptions {
+        IniOptions { check_shape: true }
+    }
+}
+
+/// A configuration of the layout of TOML storage formats.
+///
+/// Layout is applied to the serialized resource, so it doesn't affect deserialization.
+#[cfg(feature = "toml-edit")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Reflect)]
+pub struct TomlConfig {
+    /// Maximum number of keys of tables to be formatted as inline tables
+    /// (e.g., `audio = { volume = 0.5, muted = false }`).
+    ///
+    /// Only tables without nested tables are formatted as inline tables,
+    /// and `0` disables inline tables.
+    pub inline_table_max_keys: usize,
+    /// Whether keys should be sorted (e.g., to have a deterministic output for maps).
+    pub sort_keys: bool,
+}
+
+#[cfg(feature = "toml-edit")]
+impl TomlConfig {
+    /// Applies the configuration to a serialized resource.
+    pub(crate) fn apply(&self, serialized_resource: String) -> String {
+        if *self == TomlConfig::default() {
+            return serialized_resource;
+        }
+        match serialized_resource.parse::<toml_edit::DocumentMut>() {
+            Ok(mut document) => {
+                self.apply_to_table(document.as_table_mut(), &mut 0);
+                document.to_string()
+            },
+            Err(_) => serialized_resource,
+        }
+    }
+
+    /// Applies the configuration to a table recursively.
+    ///
+    /// When keys are sorted, positions of tables in the document are reassigned
+    /// in the sorted order, as tables are laid out by their positions.
+    fn apply_to_table(&self, table: &mut toml_edit::Table, position: &mut usize) {
+        use toml_edit::{
+            Item,
+            Value,
+        };
+
+        if self.sort_keys {
+            table.sort_values();
+        }
+        for (mut key, item) in table.iter_mut() {
+            if let Some(nested_table) = item.as_table_mut() {
+                if self.sort_keys {
+                    *position += 1;
+                    nested_table.set_position(*position);
+                }
+                self.apply_to_table(nested_table, position);
+
+                let inline = !nested_table.is_empty()
+                    && nested_table.len() <= self.inline_table_max_keys
+                    && nested_table.iter().all(|(_, item)| item.is_value());
+                if inline {
+                    let nested_table = std::mem::take(nested_table);
+                    *item = Item::Value(Value::InlineTable(nested_table.into_inline_table()));
+                    // decor of the key is for table headers (e.g., `[audio]`)
+                    key.leaf_decor_mut().clear();
+                }
+            } else if let Some(array_of_tables) = item.as_array_of_tables_mut() {
+                for nested_table in array_of_tables.iter_mut() {
+                    if self.sort_keys {
+                        *position += 1;
+                        nested_table.set_position(*position);
+                    }
+                    self.apply_to_table(nested_table, position);
+                }
+            } else if let Some(inline_table) = item.as_inline_table_mut() {
+                if self.sort_keys {
+                    inline_table.sort_values();
+                }
+            }
+        }
+    }
+}
+
+/// An indentation of pretty JSON.
+#[cfg(all(feature = "json", feature = "pretty"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Reflect)]
+pub enum JsonIndent {
+    /// Indentation with a single tab.
+    Tabs,
+    /// Indentation with the specified number of spaces.
+    Spaces(u8),
+}
+
+#[cfg(all(feature = "json", feature = "pretty"))]
+impl Display for JsonIndent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonIndent::Tabs => write!(f, "\t"),
+            JsonIndent::Spaces(count) => write!(f, "{}", " ".repeat(*count as usize)),
+        }
+    }
+}
+
+impl StorageFormat {
+    /// Gets if the storage format is binary.
+    pub fn is_binary(self) -> bool {
+        match self {
+            #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => true,
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => true,
+            #[cfg(feature = "ini")]
+            StorageFormat::Ini => false,
+            #[cfg(feature = "json")]
+            StorageFormat::Json => false,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty => false,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(_) => false,
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => true,
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => true,
+            #[cfg(feature = "ron")]
+            StorageFormat::Ron => false,
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPretty => false,
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPrettyWithStructNames => false,
+            #[cfg(feature = "toml")]
+            StorageFormat::Toml => false,
+            #[cfg(all(feature = "toml", feature = "pretty"))]
+            StorageFormat::TomlPretty => false,
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => false,
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => false,
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => false,
+        }
+    }
+
+    /// Gets if the storage format is human readable.
+    pub fn is_human_readable(self) -> bool {
+        !self.is_binary()
+    }
+
+    /// Gets if the storage format serializes resources into a single line,
+    /// so they can be stored as newline-delimited records.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn is_single_line(self) -> bool {
+        match self {
+            #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => false,
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => false,
+            #[cfg(feature = "ini")]
+            StorageFormat::Ini => false,
+            #[cfg(feature = "json")]
+            StorageFormat::Json => true,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty => false,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(_) => false,
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => false,
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => false,
+            #[cfg(feature = "ron")]
+            StorageFormat::Ron => true,
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPretty => false,
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPrettyWithStructNames => false,
+            #[cfg(feature = "toml")]
+            StorageFormat::Toml => false,
+            #[cfg(all(feature = "toml", feature = "pretty"))]
+            StorageFormat::TomlPretty => false,
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => false,
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => false,
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => false,
+        }
+    }
+
+    /// Gets the prefix of line comments in the storage format, if it supports comments.
+    pub(crate) fn comment_prefix(self) -> Option<&'static str> {
+        match self {
+            #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => None,
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => None,
+            #[cfg(feature = "ini")]
+            StorageFormat::Ini => Some(";"),
+            #[cfg(feature = "json")]
+            StorageFormat::Json => None,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty => None,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(_) => None,
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => None,
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => None,
+            #[cfg(feature = "ron")]
+            StorageFormat::Ron => Some("//"),
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPretty => Some("//"),
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPrettyWithStructNames => Some("//"),
+            #[cfg(feature = "toml")]
+            StorageFormat::Toml => Some("#"),
+            #[cfg(all(feature = "toml", feature = "pretty"))]
+            StorageFormat::TomlPretty => Some("#"),
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => Some("#"),
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => Some("#"),
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => None,
+        }
+    }
+
+    /// Gets if the storage format is a JSON variant.
+    #[cfg(feature = "json")]
+    pub(crate) fn is_json(self) -> bool {
+        match self {
+            StorageFormat::Json => true,
+            #[cfg(feature = "pretty")]
+            StorageFormat::JsonPretty => true,
+            #[cfg(feature = "pretty")]
+            StorageFormat::JsonPrettyWith(_) => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Gets if the storage format is a TOML variant.
+    #[cfg(feature = "toml")]
+    pub(crate) fn is_toml(self) -> bool {
+        match self {
+            StorageFormat::Toml => true,
+            #[cfg(feature = "pretty")]
+            StorageFormat::TomlPretty => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl StorageFormat {
+    /// Gets the canonical name of the storage format.
+    ///
+    /// It doesn't include the indentation of `StorageFormat::JsonPrettyWith`,
+    /// use `Display` implementation to get a name which can be parsed back.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => "bincode",
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => "bson",
+            #[cfg(feature = "ini")]
+            StorageFormat::Ini => "ini",
+            #[cfg(feature = "json")]
+            StorageFormat::Json => "json",
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty => "json-pretty",
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(_) => "json-pretty-with",
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => "pickle",
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => "protobuf",
+            #[cfg(feature = "ron")]
+            StorageFormat::Ron => "ron",
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPretty => "ron-pretty",
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPrettyWithStructNames => "ron-pretty-with-struct-names",
+            #[cfg(feature = "toml")]
+            StorageFormat::Toml => "toml",
+            #[cfg(all(feature = "toml", feature = "pretty"))]
+            StorageFormat::TomlPretty => "toml-pretty",
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => "yaml",
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => "yaml-pretty",
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => "auto",
+        }
+    }
+}
+
+impl StorageFormat {
+    /// Gets the conventional file extension of the storage format.
+    ///
+    /// It's empty for `StorageFormat::Auto` as it doesn't correspond to a single storage format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => "bin",
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => "bson",
+            #[cfg(feature = "ini")]
+            StorageFormat::Ini => "ini",
+            #[cfg(feature = "json")]
+            StorageFormat::Json => "json",
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty => "json",
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(_) => "json",
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => "pkl",
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => "pb",
+            #[cfg(feature = "ron")]
+            StorageFormat::Ron => "ron",
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPretty => "ron",
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            StorageFormat::RonPrettyWithStructNames => "ron",
+            #[cfg(feature = "toml")]
+            StorageFormat::Toml => "toml",
+            #[cfg(all(feature = "toml", feature = "pretty"))]
+            StorageFormat::TomlPretty => "toml",
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => "yaml",
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => "yaml",
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => "",
+        }
+    }
+
+    /// Gets the storage format of a file extension (e.g., `StorageFormat::Toml` for `toml`).
+    ///
+    /// It's the inverse of `StorageFormat::extension` (e.g., `json` is `StorageFormat::Json`
+    /// rather than `StorageFormat::JsonPretty`), and it also recognizes `yml` as YAML.
+    /// Extensions are case insensitive, and extensions of disabled storage formats are `None`.
+    pub fn from_extension(extension: &str) -> Option<StorageFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            #[cfg(feature = "bincode")]
+            "bin" => Some(StorageFormat::Bincode),
+            #[cfg(feature = "bson")]
+            "bson" => Some(StorageFormat::Bson),
+            #[cfg(feature = "ini")]
+            "ini" => Some(StorageFormat::Ini),
+            #[cfg(feature = "json")]
+            "json" => Some(StorageFormat::Json),
+            #[cfg(feature = "pickle")]
+            "pkl" => Some(StorageFormat::Pickle),
+            #[cfg(feature = "protobuf")]
+            "pb" => Some(StorageFormat::Protobuf),
+            #[cfg(feature = "ron")]
+            "ron" => Some(StorageFormat::Ron),
+            #[cfg(feature = "toml")]
+            "toml" => Some(StorageFormat::Toml),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(StorageFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+impl Display for StorageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(JsonIndent::Tabs) => write!(f, "json-pretty-with-tabs"),
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(JsonIndent::Spaces(count)) => {
+                write!(f, "json-pretty-with-{}-spaces", count)
+            },
+            _ => write!(f, "{}", self.as_str()),
+        }
+    }
+}
+
+impl FromStr for StorageFormat {
+    type Err = PersistenceError;
+
+    fn from_str(name: &str) -> Result<StorageFormat, PersistenceError> {
+        match name {
+            #[cfg(feature = "bincode")]
+            "bincode" => Ok(StorageFormat::Bincode),
+            #[cfg(feature = "bson")]
+            "bson" => Ok(StorageFormat::Bson),
+            #[cfg(feature = "ini")]
+            "ini" => Ok(StorageFormat::Ini),
+            #[cfg(feature = "json")]
+            "json" => Ok(StorageFormat::Json),
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            "json-pretty" => Ok(StorageFormat::JsonPretty),
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            "json-pretty-with-tabs" => Ok(StorageFormat::JsonPrettyWith(JsonIndent::Tabs)),
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            _ if name.starts_with("json-pretty-with-") => {
+                name.strip_prefix("json-pretty-with-")
+                    .and_then(|indent| indent.strip_suffix("-spaces"))
+                    .and_then(|count| count.parse::<u8>().ok())
+                    .map(|count| StorageFormat::JsonPrettyWith(JsonIndent::Spaces(count)))
+                    .ok_or_else(|| PersistenceError::UnknownStorageFormat(name.to_owned()))
+            },
+            #[cfg(feature = "pickle")]
+            "pickle" => Ok(StorageFormat::Pickle),
+            #[cfg(feature = "protobuf")]
+            "protobuf" => Ok(StorageFormat::Protobuf),
+            #[cfg(feature = "ron")]
+            "ron" => Ok(StorageFormat::Ron),
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            "ron-pretty" => Ok(StorageFormat::RonPretty),
+            #[cfg(all(feature = "ron", feature = "pretty"))]
+            "ron-pretty-with-struct-names" => Ok(StorageFormat::RonPrettyWithStructNames),
+            #[cfg(feature = "toml")]
+            "toml" => Ok(StorageFormat::Toml),
+            #[cfg(all(feature = "toml", feature = "pretty"))]
+            "toml-pretty" => Ok(StorageFormat::TomlPretty),
+            #[cfg(feature = "yaml")]
+            "yaml" => Ok(StorageFormat::Yaml),
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            "yaml-pretty" => Ok(StorageFormat::YamlPretty),
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            "auto" => Ok(StorageFormat::Auto),
+
+            #[allow(unreachable_patterns)]
+            "auto"
+            | "bincode"
+            | "bson"
+            | "ini"
+            | "json"
+            | "json-pretty"
+            | "pickle"
+            | "protobuf"
+            | "ron"
+            | "ron-pretty"
+            | "ron-pretty-with-struct-names"
+            | "toml"
+            | "toml-pretty"
+            | "yaml"
+            | "yaml-pretty" => Err(PersistenceError::DisabledStorageFormat(name.to_owned())),
+            #[cfg(not(all(feature = "json", feature = "pretty")))]
+            _ if name.starts_with("json-pretty-with-") => {
+                Err(PersistenceError::DisabledStorageFormat(name.to_owned()))
+            },
+
+            _ => Err(PersistenceError::UnknownStorageFormat(name.to_owned())),
+        }
+    }
+}
+
+impl TryFrom<&str> for StorageFormat {
+    type Error = PersistenceError;
+
+    fn try_from(name: &str) -> Result<StorageFormat, PersistenceError> {
+        name.parse()
+    }
 }
 
 #[cfg(any(
     feature = "bincode",
+    feature = "bson",
     feature = "ini",
     feature = "json",
+    feature = "pickle",
+    feature = "protobuf",
     feature = "ron",
     feature = "toml",
     feature = "yaml",
@@ -42,16 +765,212 @@ impl StorageFormat {
         name: &str,
         resource: &R,
     ) -> Result<Vec<u8>, PersistenceError> {
+        self.serialize_with(name, resource, &FormatOptions::default())
+    }
+
+    /// Deserializes a resource from bytes.
+    pub fn deserialize<R: Serialize + DeserializeOwned>(
+        self,
+        name: &str,
+        serialized_resource: &[u8],
+    ) -> Result<R, PersistenceError> {
+        self.deserialize_with(name, serialized_resource, &FormatOptions::default())
+    }
+
+    /// Serializes a resource into bytes with format options.
+    ///
+    /// Line endings of text storage formats are normalized to the line ending in the options.
+    pub(crate) fn serialize_with<R: Serialize + DeserializeOwned>(
+        self,
+        name: &str,
+        resource: &R,
+        options: &FormatOptions,
+    ) -> Result<Vec<u8>, PersistenceError> {
+        #[cfg(not(target_family = "wasm"))]
+        if self.is_streamable() {
+            let mut serialized_resource = Vec::new();
+            self.serialize_into(name, resource, options, &mut serialized_resource)?;
+            return Ok(serialized_resource);
+        }
+
+        let mut serialized_resource =
+            self.serialize_without_normalization(name, resource, options)?;
+        if self.is_binary() {
+            return Ok(serialized_resource);
+        }
+
+        if let (Some(header), Some(prefix)) = (&options.header, self.comment_prefix()) {
+            serialized_resource.splice(0..0, comment(header, prefix).into_bytes());
+        }
+
+        Ok(match options.line_ending {
+            Some(line_ending) => line_ending.normalize(serialized_resource),
+            None => serialized_resource,
+        })
+    }
+
+    /// Gets if the storage format can serialize resources into writers directly
+    /// (i.e., without serializing them into bytes in memory first).
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn is_streamable(self) -> bool {
+        match self {
+            #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => true,
+            #[cfg(feature = "json")]
+            StorageFormat::Json => true,
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty | StorageFormat::JsonPrettyWith(_) => true,
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => true,
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Serializes a resource into a writer with format options
+    /// and gets the number of bytes written.
+    ///
+    /// `serialize_with` is implemented in terms of it for streamable storage formats,
+    /// so written bytes are the same as the bytes `serialize_with` would return.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the storage format is not streamable.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn serialize_into<R: Serialize + DeserializeOwned, W: std::io::Write>(
+        self,
+        name: &str,
+        resource: &R,
+        options: &FormatOptions,
+        writer: W,
+    ) -> Result<usize, PersistenceError> {
+        use std::io::Write;
+
+        let line_ending = if self.is_binary() { None } else { options.line_ending };
+        let mut writer = NormalizingWriter::new(writer, line_ending);
+
+        if let (Some(header), Some(prefix)) = (&options.header, self.comment_prefix()) {
+            writer.write_all(comment(header, prefix).as_bytes())?;
+        }
+
         match self {
             #[cfg(feature = "bincode")]
+            StorageFormat::Bincode => {
+                bincode::serialize_into(&mut writer, resource).map_err(|error| {
+                    writer.take_error().unwrap_or_else(|| {
+                        log::error!("failed to serialize {} to Bincode\n\n{}", name, error);
+                        PersistenceError::BincodeSerialization(error)
+                    })
+                })?;
+            },
+            #[cfg(feature = "json")]
+            StorageFormat::Json => {
+                serde_json::to_writer(&mut writer, resource).map_err(|error| {
+                    writer.take_error().unwrap_or_else(|| {
+                        log::error!("failed to serialize {} to JSON\n\n{}", name, error);
+                        PersistenceError::JsonSerialization(error)
+                    })
+                })?;
+            },
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPretty => {
+                serde_json::to_writer_pretty(&mut writer, resource).map_err(|error| {
+                    writer.take_error().unwrap_or_else(|| {
+                        log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
+                        PersistenceError::JsonSerialization(error)
+                    })
+                })?;
+            },
+            #[cfg(all(feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(indent) => {
+                let indent = indent.to_string();
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+
+                let result = resource
+                    .serialize(&mut serde_json::Serializer::with_formatter(&mut writer, formatter));
+
+                result.map_err(|error| {
+                    writer.take_error().unwrap_or_else(|| {
+                        log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
+                        PersistenceError::JsonSerialization(error)
+                    })
+                })?;
+            },
+            #[cfg(feature = "yaml")]
+            StorageFormat::Yaml => {
+                serde_yaml::to_writer(&mut writer, resource).map_err(|error| {
+                    writer.take_error().unwrap_or_else(|| {
+                        log::error!("failed to serialize {} to YAML\n\n{}", name, error);
+                        PersistenceError::YamlSerialization(error)
+                    })
+                })?;
+            },
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => {
+                writer.write_all(b"---\n")?;
+                serde_yaml::to_writer(&mut writer, resource).map_err(|error| {
+                    writer.take_error().unwrap_or_else(|| {
+                        log::error!("failed to serialize {} to pretty YAML\n\n{}", name, error);
+                        PersistenceError::YamlSerialization(error)
+                    })
+                })?;
+            },
+            #[allow(unreachable_patterns)]
+            _ => panic!("{} storage format is not streamable", self.as_str()),
+        }
+
+        writer.finish()
+    }
+
+    /// Serializes a resource into bytes with format options as is.
+    #[cfg_attr(
+        not(any(feature = "ini", feature = "ron", feature = "toml-edit")),
+        allow(unused_variables)
+    )]
+    fn serialize_without_normalization<R: Serialize + DeserializeOwned>(
+        self,
+        name: &str,
+        resource: &R,
+        options: &FormatOptions,
+    ) -> Result<Vec<u8>, PersistenceError> {
+        match self {
+            // streamable storage formats are serialized into bytes in memory in browsers
+            #[cfg(all(target_family = "wasm", feature = "bincode"))]
             StorageFormat::Bincode => {
                 bincode::serialize(resource).map_err(|error| {
                     log::error!("failed to serialize {} to Bincode\n\n{}", name, error);
                     PersistenceError::BincodeSerialization(error)
                 })
             },
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => {
+                bson::to_bson(resource)
+                    .and_then(|serialized_resource| {
+                        match serialized_resource {
+                            bson::Bson::Document(document) => bson::to_vec(&document),
+                            _ => {
+                                Err(<bson::ser::Error as serde::ser::Error>::custom(format!(
+                                    "BSON requires a document at the top level \
+                                    but the resource is serialized as {:?}",
+                                    serialized_resource.element_type(),
+                                )))
+                            },
+                        }
+                    })
+                    .map_err(|error| {
+                        log::error!("failed to serialize {} to BSON\n\n{}", name, error);
+                        PersistenceError::BsonSerialization(error)
+                    })
+            },
             #[cfg(feature = "ini")]
             StorageFormat::Ini => {
+                if options.ini.check_shape {
+                    crate::ini::check_shape(resource).inspect_err(|error| {
+                        log::error!("failed to serialize {} to INI\n\n{}", name, error);
+                    })?;
+                }
                 serde_ini::to_string(resource)
                     .map(|serialized_resource| serialized_resource.into_bytes())
                     .map_err(|error| {
@@ -59,27 +978,55 @@ impl StorageFormat {
                         PersistenceError::IniSerialization(error)
                     })
             },
-            #[cfg(feature = "json")]
+            #[cfg(all(target_family = "wasm", feature = "json"))]
             StorageFormat::Json => {
-                serde_json::to_string(resource)
-                    .map(|serialized_resource| serialized_resource.into_bytes())
-                    .map_err(|error| {
-                        log::error!("failed to serialize {} to JSON\n\n{}", name, error);
-                        PersistenceError::JsonSerialization(error)
-                    })
+                serde_json::to_vec(resource).map_err(|error| {
+                    log::error!("failed to serialize {} to JSON\n\n{}", name, error);
+                    PersistenceError::JsonSerialization(error)
+                })
             },
-            #[cfg(all(feature = "json", feature = "pretty"))]
+            #[cfg(all(target_family = "wasm", feature = "json", feature = "pretty"))]
             StorageFormat::JsonPretty => {
-                serde_json::to_string_pretty(resource)
-                    .map(|serialized_resource| serialized_resource.into_bytes())
-                    .map_err(|error| {
-                        log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
-                        PersistenceError::JsonSerialization(error)
-                    })
+                serde_json::to_vec_pretty(resource).map_err(|error| {
+                    log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
+                    PersistenceError::JsonSerialization(error)
+                })
+            },
+            #[cfg(all(target_family = "wasm", feature = "json", feature = "pretty"))]
+            StorageFormat::JsonPrettyWith(indent) => {
+                let indent = indent.to_string();
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+
+                let mut serialized_resource = Vec::new();
+                let result = resource.serialize(&mut serde_json::Serializer::with_formatter(
+                    &mut serialized_resource,
+                    formatter,
+                ));
+
+                result.map(|_| serialized_resource).map_err(|error| {
+                    log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
+                    PersistenceError::JsonSerialization(error)
+                })
+            },
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => {
+                serde_pickle::to_vec(resource, serde_pickle::SerOptions::new()).map_err(|error| {
+                    log::error!("failed to serialize {} to Pickle\n\n{}", name, error);
+                    PersistenceError::PickleSerialization(error)
+                })
+            },
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => {
+                crate::protobuf::to_vec(resource).map_err(|error| {
+                    log::error!("failed to serialize {} to Protobuf\n\n{}", name, error);
+                    PersistenceError::ProtobufSerialization(error)
+                })
             },
             #[cfg(feature = "ron")]
             StorageFormat::Ron => {
-                ron::to_string(resource)
+                options
+                    .ron
+                    .to_string(resource)
                     .map(|serialized_resource| serialized_resource.into_bytes())
                     .map_err(|error| {
                         log::error!("failed to serialize {} to RON\n\n{}", name, error);
@@ -89,7 +1036,9 @@ impl StorageFormat {
             #[cfg(all(feature = "ron", feature = "pretty"))]
             StorageFormat::RonPretty => {
                 use ron::ser::PrettyConfig;
-                ron::ser::to_string_pretty(resource, PrettyConfig::new().struct_names(false))
+                options
+                    .ron
+                    .to_string_pretty(resource, PrettyConfig::new().struct_names(false))
                     .map(|serialized_resource| serialized_resource.into_bytes())
                     .map_err(|error| {
                         log::error!("failed to serialize {} to pretty RON\n\n{}", name, error);
@@ -99,7 +1048,9 @@ impl StorageFormat {
             #[cfg(all(feature = "ron", feature = "pretty"))]
             StorageFormat::RonPrettyWithStructNames => {
                 use ron::ser::PrettyConfig;
-                ron::ser::to_string_pretty(resource, PrettyConfig::new().struct_names(true))
+                options
+                    .ron
+                    .to_string_pretty(resource, PrettyConfig::new().struct_names(true))
                     .map(|serialized_resource| serialized_resource.into_bytes())
                     .map_err(|error| {
                         log::error!(
@@ -113,7 +1064,11 @@ impl StorageFormat {
             #[cfg(feature = "toml")]
             StorageFormat::Toml => {
                 toml::to_string(resource)
-                    .map(|serialized_resource| serialized_resource.into_bytes())
+                    .map(|serialized_resource| {
+                        #[cfg(feature = "toml-edit")]
+                        let serialized_resource = options.toml.apply(serialized_resource);
+                        serialized_resource.into_bytes()
+                    })
                     .map_err(|error| {
                         log::error!("failed to serialize {} to TOML\n\n{}", name, error);
                         PersistenceError::TomlSerialization(error)
@@ -122,13 +1077,17 @@ impl StorageFormat {
             #[cfg(all(feature = "toml", feature = "pretty"))]
             StorageFormat::TomlPretty => {
                 toml::to_string(resource)
-                    .map(|serialized_resource| serialized_resource.into_bytes())
+                    .map(|serialized_resource| {
+                        #[cfg(feature = "toml-edit")]
+                        let serialized_resource = options.toml.apply(serialized_resource);
+                        serialized_resource.into_bytes()
+                    })
                     .map_err(|error| {
                         log::error!("failed to serialize {} to pretty TOML\n\n{}", name, error);
                         PersistenceError::TomlSerialization(error)
                     })
             },
-            #[cfg(feature = "yaml")]
+            #[cfg(all(target_family = "wasm", feature = "yaml"))]
             StorageFormat::Yaml => {
                 serde_yaml::to_string(resource)
                     .map(|serialized_resource| serialized_resource.into_bytes())
@@ -137,14 +1096,41 @@ impl StorageFormat {
                         PersistenceError::YamlSerialization(error)
                     })
             },
+            #[cfg(all(target_family = "wasm", feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => {
+                serde_yaml::to_string(resource)
+                    .map(|serialized_resource| format!("---\n{}", serialized_resource).into_bytes())
+                    .map_err(|error| {
+                        log::error!("failed to serialize {} to pretty YAML\n\n{}", name, error);
+                        PersistenceError::YamlSerialization(error)
+                    })
+            },
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => {
+                log::error!(
+                    "failed to serialize {} as its storage format is not detected yet",
+                    name,
+                );
+                Err(PersistenceError::AmbiguousStorageFormat)
+            },
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("{} storage format is streamable", self.as_str()),
         }
     }
 
-    /// Deserializes a resource from bytes.
-    pub fn deserialize<R: Serialize + DeserializeOwned>(
+    /// Deserializes a resource from bytes with format options.
+    #[cfg_attr(not(any(feature = "ron", feature = "yaml")), allow(unused_variables))]
+    pub(crate) fn deserialize_with<R: Serialize + DeserializeOwned>(
         self,
         name: &str,
         serialized_resource: &[u8],
+        options: &FormatOptions,
     ) -> Result<R, PersistenceError> {
         #[cfg(feature = "bincode")]
         #[allow(irrefutable_let_patterns)]
@@ -155,6 +1141,37 @@ impl StorageFormat {
             });
         }
 
+        #[cfg(feature = "bson")]
+        #[allow(irrefutable_let_patterns)]
+        if let StorageFormat::Bson = self {
+            return bson::from_slice::<R>(serialized_resource).map_err(|error| {
+                log::error!("failed to parse {} as BSON\n\n{}", name, error);
+                PersistenceError::BsonDeserialization(error)
+            });
+        }
+
+        #[cfg(feature = "pickle")]
+        #[allow(irrefutable_let_patterns)]
+        if let StorageFormat::Pickle = self {
+            return serde_pickle::from_slice::<R>(
+                serialized_resource,
+                serde_pickle::DeOptions::new(),
+            )
+            .map_err(|error| {
+                log::error!("failed to parse {} as Pickle\n\n{}", name, error);
+                PersistenceError::PickleDeserialization(error)
+            });
+        }
+
+        #[cfg(feature = "protobuf")]
+        #[allow(irrefutable_let_patterns)]
+        if let StorageFormat::Protobuf = self {
+            return crate::protobuf::from_slice::<R>(serialized_resource).map_err(|error| {
+                log::error!("failed to parse {} as Protobuf\n\n{}", name, error);
+                PersistenceError::ProtobufDeserialization(error)
+            });
+        }
+
         #[cfg(any(
             feature = "ini",
             feature = "json",
@@ -171,6 +1188,23 @@ impl StorageFormat {
         match self {
             #[cfg(feature = "bincode")]
             StorageFormat::Bincode => unreachable!(),
+            #[cfg(feature = "bson")]
+            StorageFormat::Bson => unreachable!(),
+            #[cfg(feature = "pickle")]
+            StorageFormat::Pickle => unreachable!(),
+            #[cfg(feature = "protobuf")]
+            StorageFormat::Protobuf => unreachable!(),
+            #[cfg(any(
+                feature = "ini",
+                feature = "json",
+                feature = "ron",
+                feature = "toml",
+                feature = "yaml",
+            ))]
+            StorageFormat::Auto => {
+                StorageFormat::deserialize_detecting::<R>(name, serialized_resource, options)
+                    .map(|(resource, _)| resource)
+            },
             #[cfg(feature = "ini")]
             StorageFormat::Ini => {
                 serde_ini::from_str::<R>(serialized_resource_str).map_err(|error| {
@@ -180,35 +1214,33 @@ impl StorageFormat {
             },
             #[cfg(feature = "json")]
             StorageFormat::Json => {
-                serde_json::from_str::<R>(serialized_resource_str).map_err(|error| {
+                json_from_str::<R>(serialized_resource_str).inspect_err(|error| {
                     log::error!("failed to parse {} as JSON\n\n{}", name, error);
-                    PersistenceError::JsonDeserialization(error)
                 })
             },
             #[cfg(all(feature = "json", feature = "pretty"))]
-            StorageFormat::JsonPretty => {
-                serde_json::from_str::<R>(serialized_resource_str).map_err(|error| {
+            StorageFormat::JsonPretty | StorageFormat::JsonPrettyWith(_) => {
+                json_from_str::<R>(serialized_resource_str).inspect_err(|error| {
                     log::error!("failed to parse {} as pretty JSON\n\n{}", name, error);
-                    PersistenceError::JsonDeserialization(error)
                 })
             },
             #[cfg(feature = "ron")]
             StorageFormat::Ron => {
-                ron::from_str::<R>(serialized_resource_str).map_err(|error| {
+                options.ron.from_str::<R>(serialized_resource_str).map_err(|error| {
                     log::error!("failed to parse {} as RON\n\n{}", name, error);
                     PersistenceError::RonDeserialization(error.into())
                 })
             },
             #[cfg(all(feature = "ron", feature = "pretty"))]
             StorageFormat::RonPretty => {
-                ron::from_str::<R>(serialized_resource_str).map_err(|error| {
+                options.ron.from_str::<R>(serialized_resource_str).map_err(|error| {
                     log::error!("failed to parse {} as pretty RON\n\n{}", name, error);
                     PersistenceError::RonDeserialization(error.into())
                 })
             },
             #[cfg(all(feature = "ron", feature = "pretty"))]
             StorageFormat::RonPrettyWithStructNames => {
-                ron::from_str::<R>(serialized_resource_str).map_err(|error| {
+                options.ron.from_str::<R>(serialized_resource_str).map_err(|error| {
                     log::error!(
                         "failed to parse {} as pretty RON with struct names\n\n{}",
                         name,
@@ -219,33 +1251,93 @@ impl StorageFormat {
             },
             #[cfg(feature = "toml")]
             StorageFormat::Toml => {
-                toml::from_str::<R>(serialized_resource_str).map_err(|error| {
+                toml_from_str::<R>(serialized_resource_str).inspect_err(|error| {
                     log::error!("failed to parse {} as TOML\n\n{}", name, error);
-                    PersistenceError::TomlDeserialization(error)
                 })
             },
             #[cfg(all(feature = "toml", feature = "pretty"))]
             StorageFormat::TomlPretty => {
-                toml::from_str::<R>(serialized_resource_str).map_err(|error| {
+                toml_from_str::<R>(serialized_resource_str).inspect_err(|error| {
                     log::error!("failed to parse {} as pretty TOML\n\n{}", name, error);
-                    PersistenceError::TomlDeserialization(error)
                 })
             },
             #[cfg(feature = "yaml")]
             StorageFormat::Yaml => {
-                serde_yaml::from_str::<R>(serialized_resource_str).map_err(|error| {
-                    log::error!("failed to parse {} as YAML\n\n{}", name, error);
-                    PersistenceError::YamlDeserialization(error)
-                })
+                ensure_yaml_alias_limit(serialized_resource_str, options)
+                    .and_then(|_| yaml_from_str::<R>(serialized_resource_str))
+                    .inspect_err(|error| {
+                        log::error!("failed to parse {} as YAML\n\n{}", name, error);
+                    })
+            },
+            #[cfg(all(feature = "yaml", feature = "pretty"))]
+            StorageFormat::YamlPretty => {
+                ensure_yaml_alias_limit(serialized_resource_str, options)
+                    .and_then(|_| yaml_from_str::<R>(serialized_resource_str))
+                    .inspect_err(|error| {
+                        log::error!("failed to parse {} as pretty YAML\n\n{}", name, error);
+                    })
             },
         }
     }
+
+    /// Deserializes a resource from bytes in the first storage format it's detected as.
+    ///
+    /// See `StorageFormat::Auto` for the order of the storage formats which are tried.
+    #[cfg(any(
+        feature = "ini",
+        feature = "json",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml",
+    ))]
+    #[cfg_attr(not(any(feature = "ron", feature = "yaml")), allow(unused_variables))]
+    pub(crate) fn deserialize_detecting<R: Serialize + DeserializeOwned>(
+        name: &str,
+        serialized_resource: &[u8],
+        options: &FormatOptions,
+    ) -> Result<(R, StorageFormat), PersistenceError> {
+        let serialized_resource_str =
+            std::str::from_utf8(serialized_resource).map_err(|error| {
+                log::error!("failed to decode {} as UTF-8\n\n{}", name, error);
+                PersistenceError::Encoding(error)
+            })?;
+
+        // failed attempts are not logged as they are expected
+        #[cfg(feature = "json")]
+        if let Ok(resource) = serde_json::from_str::<R>(serialized_resource_str) {
+            return Ok((resource, StorageFormat::Json));
+        }
+        #[cfg(feature = "toml")]
+        if let Ok(resource) = toml::from_str::<R>(serialized_resource_str) {
+            return Ok((resource, StorageFormat::Toml));
+        }
+        #[cfg(feature = "ron")]
+        if let Ok(resource) = options.ron.from_str::<R>(serialized_resource_str) {
+            return Ok((resource, StorageFormat::Ron));
+        }
+        #[cfg(feature = "yaml")]
+        if let Ok(resource) = ensure_yaml_alias_limit(serialized_resource_str, options)
+            .and_then(|_| yaml_from_str::<R>(serialized_resource_str))
+        {
+            return Ok((resource, StorageFormat::Yaml));
+        }
+        #[cfg(feature = "ini")]
+        if let Ok(resource) = serde_ini::from_str::<R>(serialized_resource_str) {
+            return Ok((resource, StorageFormat::Ini));
+        }
+
+        log::error!("failed to detect the storage format of {}", name);
+        Err(PersistenceError::UndetectedStorageFormat)
+    }
 }
 
 #[cfg(not(any(
     feature = "bincode",
+    feature = "bson",
     feature = "ini",
     feature = "json",
+    feature = "pickle",
+    feature = "protobuf",
     feature = "ron",
     feature = "toml",
     feature = "yaml",
@@ -268,4 +1360,192 @@ impl StorageFormat {
     ) -> Result<R, PersistenceError> {
         unreachable!()
     }
+
+    /// Serializes a resource into bytes with format options.
+    pub(crate) fn serialize_with<R: Serialize + DeserializeOwned>(
+        self,
+        _name: &str,
+        _resource: &R,
+        _options: &FormatOptions,
+    ) -> Result<Vec<u8>, PersistenceError> {
+        unreachable!()
+    }
+
+    /// Deserializes a resource from bytes with format options.
+    pub(crate) fn deserialize_with<R: Serialize + DeserializeOwned>(
+        self,
+        _name: &str,
+        _serialized_resource: &[u8],
+        _options: &FormatOptions,
+    ) -> Result<R, PersistenceError> {
+        unreachable!()
+    }
+}
+
+/// Comments each line of a header with a comment prefix.
+#[cfg(any(
+    feature = "bincode",
+    feature = "bson",
+    feature = "ini",
+    feature = "json",
+    feature = "pickle",
+    feature = "protobuf",
+    feature = "ron",
+    feature = "toml",
+    feature = "yaml",
+))]
+fn comment(header: &str, prefix: &str) -> String {
+    let mut commented_header = String::new();
+    for line in header.lines() {
+        if line.is_empty() {
+            commented_header.push_str(prefix);
+        } else {
+            commented_header.push_str(&format!("{} {}", prefix, line));
+        }
+        commented_header.push('\n');
+    }
+    commented_header
+}
+
+/// Deserializes a resource from JSON.
+///
+/// Errors are wrapped with the path of the offending field if `path-to-error` feature is enabled.
+#[cfg(feature = "json")]
+fn json_from_str<R: DeserializeOwned>(text: &str) -> Result<R, PersistenceError> {
+    #[cfg(feature = "path-to-error")]
+    {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let resource = with_path(
+            serde_path_to_error::deserialize(&mut deserializer),
+            PersistenceError::JsonDeserialization,
+        )?;
+        deserializer.end().map_err(PersistenceError::JsonDeserialization)?;
+        Ok(resource)
+    }
+    #[cfg(not(feature = "path-to-error"))]
+    {
+        serde_json::from_str(text).map_err(PersistenceError::JsonDeserialization)
+    }
+}
+
+/// Deserializes a resource from TOML.
+///
+/// Errors are wrapped with the path of the offending field if `path-to-error` feature is enabled.
+#[cfg(feature = "toml")]
+fn toml_from_str<R: DeserializeOwned>(text: &str) -> Result<R, PersistenceError> {
+    #[cfg(feature = "path-to-error")]
+    {
+        with_path(
+            serde_path_to_error::deserialize(toml::Deserializer::new(text)),
+            PersistenceError::TomlDeserialization,
+        )
+    }
+    #[cfg(not(feature = "path-to-error"))]
+    {
+        toml::from_str(text).map_err(PersistenceError::TomlDeserialization)
+    }
+}
+
+/// Deserializes a resource from YAML.
+///
+/// Errors are wrapped with the path of the offending field if `path-to-error` feature is enabled.
+#[cfg(feature = "yaml")]
+fn yaml_from_str<R: DeserializeOwned>(text: &str) -> Result<R, PersistenceError> {
+    #[cfg(feature = "path-to-error")]
+    {
+        with_path(
+            serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(text)),
+            PersistenceError::YamlDeserialization,
+        )
+    }
+    #[cfg(not(feature = "path-to-error"))]
+    {
+        serde_yaml::from_str(text).map_err(PersistenceError::YamlDeserialization)
+    }
+}
+
+/// Converts the error of a path tracking deserialization,
+/// wrapping it with the path of the offending field unless it's at the top level.
+#[cfg(all(feature = "path-to-error", any(feature = "json", feature = "toml", feature = "yaml")))]
+fn with_path<R, E>(
+    result: Result<R, serde_path_to_error::Error<E>>,
+    into_error: fn(E) -> PersistenceError,
+) -> Result<R, PersistenceError> {
+    result.map_err(|error| {
+        let path = error.path().to_string();
+        let error = into_error(error.into_inner());
+        if path == "." { error } else { PersistenceError::AtPath { path, source: Box::new(error) } }
+    })
+}
+
+/// Ensures a YAML document doesn't have more aliases than the limit, if there is a limit.
+#[cfg(feature = "yaml")]
+fn ensure_yaml_alias_limit(text: &str, options: &FormatOptions) -> Result<(), PersistenceError> {
+    if let Some(limit) = options.yaml_alias_limit {
+        let aliases = count_yaml_aliases(text);
+        if aliases > limit {
+            return Err(PersistenceError::YamlAliasLimitExceeded { aliases, limit });
+        }
+    }
+    Ok(())
+}
+
+/// Counts the aliases in a YAML document (e.g., `*defaults`).
+///
+/// The document is scanned without parsing it, so aliases are counted without expanding them.
+/// Comments and quoted scalars are skipped, but words starting with asterisks in block scalars
+/// are counted as aliases as well, which errs on the side of rejecting documents.
+#[cfg(feature = "yaml")]
+fn count_yaml_aliases(text: &str) -> usize {
+    let is_separator = |character: char| character.is_whitespace() || "[{,".contains(character);
+
+    let mut aliases = 0;
+    let mut quote = None;
+    let mut previous = '\n';
+
+    let mut characters = text.chars().peekable();
+    while let Some(character) = characters.next() {
+        match quote {
+            Some(delimiter) if character == delimiter => {
+                // single quotes are escaped by repeating them in single quoted scalars
+                if delimiter == '\'' && characters.peek() == Some(&'\'') {
+                    characters.next();
+                } else {
+                    quote = None;
+                }
+            },
+            Some('"') if character == '\\' => {
+                characters.next();
+            },
+            Some(_) => {},
+            None => {
+                match character {
+                    '#' if previous.is_whitespace() => {
+                        for character in characters.by_ref() {
+                            if character == '\n' {
+                                break;
+                            }
+                        }
+                        previous = '\n';
+                        continue;
+                    },
+                    '\'' | '"' if is_separator(previous) => {
+                        quote = Some(character);
+                    },
+                    '*' if is_separator(previous) => {
+                        let is_alias = characters
+                            .peek()
+                            .is_some_and(|next| !is_separator(*next) && !"]}".contains(*next));
+                        if is_alias {
+                            aliases += 1;
+                        }
+                    },
+                    _ => {},
+                }
+            },
+        }
+        previous = character;
+    }
+
+    aliases
 }
@@ -0,0 +1,219 @@
+//! A group of persistent resources.
+
+use crate::{
+    event::Operation,
+    prelude::*,
+};
+use std::any::TypeId;
+
+/// A group of persistent resources.
+///
+/// Persistent resources are registered to the group by their types, and they are looked up
+/// from the world when the group is operated on, so they can be inserted to and removed from
+/// the world freely after being registered.
+///
+/// Operations are applied to each registered resource independently, so one of them failing
+/// doesn't stop the operation from being applied to the rest.
+#[derive(Debug, Default, Resource)]
+pub struct PersistentGroup {
+    entries: Vec<PersistentGroupEntry>,
+}
+
+/// A type-erased entry of a persistent resource group.
+#[derive(Debug)]
+struct PersistentGroupEntry {
+    type_id: TypeId,
+    persist: fn(&World) -> Option<PersistentGroupOutcome>,
+    reload: fn(&mut World) -> Option<PersistentGroupOutcome>,
+    unload: fn(&mut World) -> Option<PersistentGroupOutcome>,
+}
+
+/// An outcome of an operation on a persistent resource in a group.
+struct PersistentGroupOutcome {
+    name: String,
+    storage: Storage,
+    result: Result<(), PersistenceError>,
+}
+
+impl PersistentGroupOutcome {
+    /// Converts the outcome to the name of the resource along with the result of the operation.
+    fn into_result(self) -> (String, Result<(), PersistenceError>) {
+        (self.name, self.result)
+    }
+
+    /// Converts the outcome to a failure event, if the operation failed.
+    fn into_failure(self, operation: Operation) -> Option<PersistenceFailed> {
+        self.result.err().map(|error| {
+            PersistenceFailed {
+                name: self.name,
+                storage: self.storage,
+                error: error.to_string(),
+                operation,
+            }
+        })
+    }
+}
+
+impl PersistentGroup {
+    /// Creates an empty persistent resource group.
+    pub fn new() -> PersistentGroup {
+        PersistentGroup::default()
+    }
+
+    /// Registers a persistent resource to the group.
+    ///
+    /// Registering the same persistent resource multiple times has no effect.
+    pub fn register<R: Resource + Serialize + DeserializeOwned>(&mut self) -> &mut PersistentGroup {
+        let type_id = TypeId::of::<Persistent<R>>();
+        if !self.entries.iter().any(|entry| entry.type_id == type_id) {
+            self.entries.push(PersistentGroupEntry {
+                type_id,
+                persist: persist::<R>,
+                reload: reload::<R>,
+                unload: unload::<R>,
+            });
+        }
+        self
+    }
+
+    /// Gets the number of persistent resources in the group.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Gets if the group is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl PersistentGroup {
+    /// Writes the registered resources to their underlying storages.
+    ///
+    /// Resources that are not in the world or are unloaded are skipped.
+    ///
+    /// Returns the name of each persisted resource along with the result of persisting it.
+    pub fn persist_all(&self, world: &World) -> Vec<(String, Result<(), PersistenceError>)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| (entry.persist)(world))
+            .map(PersistentGroupOutcome::into_result)
+            .collect()
+    }
+
+    /// Reloads the registered resources from their underlying storages.
+    ///
+    /// Resources that are not in the world are skipped, and unloaded resources are loaded.
+    ///
+    /// Returns the name of each reloaded resource along with the result of reloading it.
+    pub fn reload_all(&self, world: &mut World) -> Vec<(String, Result<(), PersistenceError>)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| (entry.reload)(world))
+            .map(PersistentGroupOutcome::into_result)
+            .collect()
+    }
+
+    /// Unloads the registered resources from memory.
+    ///
+    /// Changes are synchronized with the underlying storages before unloading,
+    /// and resources that fail to persist are kept loaded.
+    ///
+    /// Resources that are not in the world or are already unloaded are skipped.
+    ///
+    /// Returns the name of each unloaded resource along with the result of unloading it.
+    pub fn unload_all(&self, world: &mut World) -> Vec<(String, Result<(), PersistenceError>)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| (entry.unload)(world))
+            .map(PersistentGroupOutcome::into_result)
+            .collect()
+    }
+}
+
+impl PersistentGroup {
+    /// Writes the resources registered to the group in the world to their underlying storages,
+    /// and sends a `PersistenceFailed` event for each resource that failed to persist.
+    ///
+    /// It's an exclusive system (e.g., `app.add_systems(Update, PersistentGroup::persist_all_system)`),
+    /// and `PersistenceFailed` event should be added to the app for failures to be reported.
+    pub fn persist_all_system(world: &mut World) {
+        let failures = match world.get_resource::<PersistentGroup>() {
+            Some(group) => {
+                group
+                    .entries
+                    .iter()
+                    .filter_map(|entry| (entry.persist)(world))
+                    .filter_map(|outcome| outcome.into_failure(Operation::Persist))
+                    .collect::<Vec<_>>()
+            },
+            None => return,
+        };
+        if !failures.is_empty() {
+            world.send_event_batch(failures);
+        }
+    }
+
+    /// Reloads the resources registered to the group in the world from their underlying storages,
+    /// and sends a `PersistenceFailed` event for each resource that failed to reload.
+    ///
+    /// It's an exclusive system (e.g., `app.add_systems(Update, PersistentGroup::reload_all_system)`),
+    /// and `PersistenceFailed` event should be added to the app for failures to be reported.
+    pub fn reload_all_system(world: &mut World) {
+        if !world.contains_resource::<PersistentGroup>() {
+            return;
+        }
+        let failures = world.resource_scope(|world, group: Mut<PersistentGroup>| {
+            group
+                .entries
+                .iter()
+                .filter_map(|entry| (entry.reload)(world))
+                .filter_map(|outcome| outcome.into_failure(Operation::Reload))
+                .collect::<Vec<_>>()
+        });
+        if !failures.is_empty() {
+            world.send_event_batch(failures);
+        }
+    }
+}
+
+fn persist<R: Resource + Serialize + DeserializeOwned>(
+    world: &World,
+) -> Option<PersistentGroupOutcome> {
+    let persistent = world.get_resource::<Persistent<R>>()?;
+    if persistent.is_unloaded() {
+        return None;
+    }
+    Some(PersistentGroupOutcome {
+        name: persistent.name().to_owned(),
+        storage: persistent.storage().clone(),
+        result: persistent.persist(),
+    })
+}
+
+fn reload<R: Resource + Serialize + DeserializeOwned>(
+    world: &mut World,
+) -> Option<PersistentGroupOutcome> {
+    let mut persistent = world.get_resource_mut::<Persistent<R>>()?;
+    let result = persistent.reload();
+    Some(PersistentGroupOutcome {
+        name: persistent.name().to_owned(),
+        storage: persistent.storage().clone(),
+        result,
+    })
+}
+
+fn unload<R: Resource + Serialize + DeserializeOwned>(
+    world: &mut World,
+) -> Option<PersistentGroupOutcome> {
+    let mut persistent = world.get_resource_mut::<Persistent<R>>()?;
+    if persistent.is_unloaded() {
+        return None;
+    }
+    let result = persistent.unload();
+    Some(PersistentGroupOutcome {
+        name: persistent.name().to_owned(),
+        storage: persistent.storage().clone(),
+        result,
+    })
+}
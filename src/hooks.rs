@@ -0,0 +1,73 @@
+//! Hooks of persistent resources.
+
+use crate::prelude::*;
+
+/// A hook that runs after a resource is loaded.
+pub(crate) type OnLoad<R> = Box<dyn Fn(&mut R) + Send + Sync>;
+
+/// A hook that runs before a resource is saved.
+pub(crate) type OnSave<R> = Box<dyn Fn(&R) + Send + Sync>;
+
+/// A callback that runs after a resource is saved successfully.
+pub(crate) type OnPersisted<R> = Box<dyn Fn(&R) + Send + Sync>;
+
+/// A validator that runs after a resource is loaded.
+pub(crate) type Validator<R> = Box<dyn Fn(&R) -> Result<(), String> + Send + Sync>;
+
+/// Hooks of a persistent resource.
+pub(crate) struct Hooks<R> {
+    pub(crate) on_load: Option<OnLoad<R>>,
+    pub(crate) on_save: Option<OnSave<R>>,
+    pub(crate) on_persisted: Vec<OnPersisted<R>>,
+    pub(crate) validator: Option<Validator<R>>,
+}
+
+impl<R> Hooks<R> {
+    /// Runs the load hook on a resource, if it's set,
+    /// and validates the resulting resource, if a validator is set.
+    pub(crate) fn loaded(&self, resource: &mut R) -> Result<(), PersistenceError> {
+        if let Some(on_load) = &self.on_load {
+            on_load(resource);
+        }
+        self.validate(resource)
+    }
+
+    /// Validates a resource, if a validator is set.
+    pub(crate) fn validate(&self, resource: &R) -> Result<(), PersistenceError> {
+        if let Some(validator) = &self.validator {
+            validator(resource).map_err(PersistenceError::Validation)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the save hook on a resource, if it's set.
+    pub(crate) fn saving(&self, resource: &R) {
+        if let Some(on_save) = &self.on_save {
+            on_save(resource);
+        }
+    }
+
+    /// Runs the callbacks of successful saves on a resource.
+    pub(crate) fn persisted(&self, resource: &R) {
+        for on_persisted in &self.on_persisted {
+            on_persisted(resource);
+        }
+    }
+}
+
+impl<R> Default for Hooks<R> {
+    fn default() -> Hooks<R> {
+        Hooks { on_load: None, on_save: None, on_persisted: Vec::new(), validator: None }
+    }
+}
+
+impl<R> fmt::Debug for Hooks<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("on_load", &self.on_load.is_some())
+            .field("on_save", &self.on_save.is_some())
+            .field("on_persisted", &self.on_persisted.len())
+            .field("validator", &self.validator.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,200 @@
+//! Shapes of INI storage formats.
+//!
+//! `serde_ini` only supports flat resources (i.e., a struct or a map of values without sections),
+//! so shapes of resources are checked before they are serialized to report a clear error.
+
+use crate::prelude::*;
+use serde::ser::{
+    self,
+    Impossible,
+    Serializer,
+};
+
+/// Checks whether a resource is flat to be serialized into INI.
+///
+/// Errors which are not about the shape of the resource (e.g., custom errors of `Serialize`
+/// implementations) are ignored, as they are reported by `serde_ini` afterwards.
+pub(crate) fn check_shape<R: Serialize + ?Sized>(resource: &R) -> Result<(), PersistenceError> {
+    match resource.serialize(ShapeChecker { top_level: true, field: None }) {
+        Err(ShapeError::Unsupported(shape)) => Err(PersistenceError::UnsupportedIniShape(shape)),
+        _ => Ok(()),
+    }
+}
+
+/// An error of checking the shape of a resource.
+#[derive(Debug)]
+enum ShapeError {
+    /// Shape of the resource is not supported.
+    Unsupported(String),
+    /// Resource failed to be serialized for another reason.
+    Other,
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::Unsupported(shape) => f.write_str(shape),
+            ShapeError::Other => f.write_str("resource failed to be serialized"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+impl ser::Error for ShapeError {
+    fn custom<T: Display>(_message: T) -> ShapeError {
+        ShapeError::Other
+    }
+}
+
+macro_rules! value {
+    ($($method:ident($($argument:ty),*) = $kind:literal;)*) => {
+        $(
+            fn $method(self, $(_: $argument),*) -> Result<(), ShapeError> {
+                if self.top_level { Err(self.unsupported($kind)) } else { Ok(()) }
+            }
+        )*
+    };
+}
+
+macro_rules! unsupported {
+    ($($method:ident($($argument:ty),*) -> $result:ty = $kind:literal;)*) => {
+        $(
+            fn $method(self, $(_: $argument),*) -> Result<$result, ShapeError> {
+                Err(self.unsupported($kind))
+            }
+        )*
+    };
+}
+
+/// A serializer which only accepts a struct or a map of values at the top level.
+struct ShapeChecker {
+    top_level: bool,
+    field: Option<&'static str>,
+}
+
+impl ShapeChecker {
+    /// Gets the error of a value which is serialized as an unsupported kind.
+    fn unsupported(&self, kind: &str) -> ShapeError {
+        ShapeError::Unsupported(match self.field {
+            _ if self.top_level => format!("the resource is serialized as {}", kind),
+            Some(field) => format!("field {} is serialized as {}", field, kind),
+            None => format!("an entry is serialized as {}", kind),
+        })
+    }
+}
+
+impl Serializer for ShapeChecker {
+    type Ok = ();
+    type Error = ShapeError;
+
+    type SerializeSeq = Impossible<(), ShapeError>;
+    type SerializeTuple = Impossible<(), ShapeError>;
+    type SerializeTupleStruct = Impossible<(), ShapeError>;
+    type SerializeTupleVariant = Impossible<(), ShapeError>;
+    type SerializeMap = FieldsChecker;
+    type SerializeStruct = FieldsChecker;
+    type SerializeStructVariant = Impossible<(), ShapeError>;
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<FieldsChecker, ShapeError> {
+        if self.top_level { Ok(FieldsChecker) } else { Err(self.unsupported("a map")) }
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<FieldsChecker, ShapeError> {
+        if self.top_level { Ok(FieldsChecker) } else { Err(self.unsupported("a struct")) }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), ShapeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), ShapeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), ShapeError> {
+        Err(self.unsupported("an enum with data"))
+    }
+
+    value! {
+        serialize_bool(bool) = "a boolean";
+        serialize_i8(i8) = "an integer";
+        serialize_i16(i16) = "an integer";
+        serialize_i32(i32) = "an integer";
+        serialize_i64(i64) = "an integer";
+        serialize_u8(u8) = "an integer";
+        serialize_u16(u16) = "an integer";
+        serialize_u32(u32) = "an integer";
+        serialize_u64(u64) = "an integer";
+        serialize_f32(f32) = "a float";
+        serialize_f64(f64) = "a float";
+        serialize_char(char) = "a character";
+        serialize_str(&str) = "a string";
+        serialize_bytes(&[u8]) = "bytes";
+        serialize_none() = "an option";
+        serialize_unit() = "a unit";
+        serialize_unit_struct(&'static str) = "a unit";
+        serialize_unit_variant(&'static str, u32, &'static str) = "an enum";
+    }
+
+    unsupported! {
+        serialize_seq(Option<usize>) -> Impossible<(), ShapeError> = "a sequence";
+        serialize_tuple(usize) -> Impossible<(), ShapeError> = "a tuple";
+        serialize_tuple_struct(&'static str, usize) -> Impossible<(), ShapeError> = "a tuple";
+        serialize_tuple_variant(&'static str, u32, &'static str, usize)
+            -> Impossible<(), ShapeError> = "an enum with data";
+        serialize_struct_variant(&'static str, u32, &'static str, usize)
+            -> Impossible<(), ShapeError> = "an enum with data";
+    }
+}
+
+/// A serializer of the fields of the top level struct or map, which only accepts values.
+struct FieldsChecker;
+
+impl ser::SerializeStruct for FieldsChecker {
+    type Ok = ();
+    type Error = ShapeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ShapeError> {
+        value.serialize(ShapeChecker { top_level: false, field: Some(key) })
+    }
+
+    fn end(self) -> Result<(), ShapeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for FieldsChecker {
+    type Ok = ();
+    type Error = ShapeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, _key: &T) -> Result<(), ShapeError> {
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ShapeError> {
+        value.serialize(ShapeChecker { top_level: false, field: None })
+    }
+
+    fn end(self) -> Result<(), ShapeError> {
+        Ok(())
+    }
+}
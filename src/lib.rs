@@ -5,8 +5,11 @@
 #[cfg(not(any(
     feature = "library",
     feature = "bincode",
+    feature = "bson",
     feature = "ini",
     feature = "json",
+    feature = "pickle",
+    feature = "protobuf",
     feature = "ron",
     feature = "toml",
     feature = "yaml",
@@ -20,17 +23,71 @@ compile_error!(concat!(r#"no storage formats are selected!
 
 "#));
 
+#[macro_use]
+mod logging;
+
+pub mod app;
 pub mod builder;
 pub mod error;
+pub mod event;
 pub mod format;
+pub mod group;
+mod hooks;
+#[cfg(feature = "ini")]
+mod ini;
+pub mod merge;
+pub mod param;
 pub mod persistent;
 pub mod prelude;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+pub mod proxy;
+pub mod registry;
+pub mod serde_helpers;
+pub mod slots;
 pub mod storage;
+#[cfg(not(target_family = "wasm"))]
+pub mod transaction;
 
 pub use crate::{
+    app::PersistentAppExt,
     builder::PersistentBuilder,
     error::PersistenceError,
-    format::StorageFormat,
-    persistent::Persistent,
+    event::{
+        Operation,
+        PersistenceFailed,
+    },
+    format::{
+        LineEnding,
+        StorageFormat,
+    },
+    group::PersistentGroup,
+    merge::Merge,
+    param::PersistMut,
+    persistent::{
+        Persistent,
+        UnconfiguredPersistent,
+    },
+    proxy::{
+        Proxied,
+        Proxy,
+    },
+    registry::PersistentRegistry,
+    slots::SaveSlots,
     storage::Storage,
 };
+
+#[cfg(feature = "ini")]
+pub use crate::format::IniOptions;
+
+#[cfg(all(feature = "json", feature = "pretty"))]
+pub use crate::format::JsonIndent;
+
+#[cfg(feature = "protobuf")]
+pub use crate::protobuf::Protobuf;
+
+#[cfg(feature = "toml-edit")]
+pub use crate::format::TomlConfig;
+
+#[cfg(not(target_family = "wasm"))]
+pub use crate::transaction::Transaction;
@@ -0,0 +1,26 @@
+//! Logging utilities of the crate.
+
+/// Logs an event about a resource.
+///
+/// When `tracing` feature is enabled, the name, the storage and the storage format
+/// of the resource are attached to the event as structured fields
+/// (i.e., `resource.name`, `storage` and `format`).
+///
+/// The resource can be specified as a persistent resource (e.g., `self`)
+/// or as a `(name, storage, format)` tuple.
+macro_rules! log_event {
+    ($level:ident, ($name:expr, $storage:expr, $format:expr $(,)?), $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        $crate::prelude::log::$level!(
+            resource.name = %$name,
+            storage = %$storage,
+            format = %$format,
+            $($arg)+
+        );
+        #[cfg(not(feature = "tracing"))]
+        $crate::prelude::log::$level!($($arg)+);
+    }};
+    ($level:ident, $persistent:expr, $($arg:tt)+) => {
+        log_event!($level, ($persistent.name, $persistent.storage, $persistent.format), $($arg)+)
+    };
+}
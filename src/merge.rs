@@ -0,0 +1,17 @@
+//! Merging of records of append-only persistent resources.
+
+/// A resource that can be stored as an append-only log of records.
+///
+/// In append mode, each save of a resource which is set (e.g., with `Persistent::set`) appends
+/// it to the underlying storage as a newline-delimited record instead of overwriting the storage,
+/// and loading the resource reconstructs it by merging all records in order into the first one.
+///
+/// So the resource in memory is the record to append when it's set,
+/// and it's the combination of all records when it's loaded
+/// (e.g., a list of analytics events which is merged by extending it).
+/// Saving the combination of all records (e.g., after updating a loaded resource) replaces
+/// all records with it as the only record instead, so records are never duplicated.
+pub trait Merge {
+    /// Merges a subsequent record into the resource.
+    fn merge(&mut self, record: Self);
+}
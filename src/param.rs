@@ -0,0 +1,52 @@
+//! System parameters for persistent resources.
+
+use crate::prelude::*;
+use bevy::ecs::system::{
+    SystemChangeTick,
+    SystemParam,
+};
+
+/// Mutable access to a persistent resource which persists it automatically.
+///
+/// It's used like `ResMut<Persistent<R>>` in systems, but the resource is written to its
+/// underlying storage when the system finishes running, if it's changed during the run
+/// (i.e., it's mutably dereferenced). So the resource cannot be forgotten to be persisted
+/// after being modified.
+///
+/// Failing to write the resource is only logged, so `Persistent::persist` should be
+/// called explicitly in systems which need to handle failures.
+///
+/// # Panics
+///
+/// Systems using it panic if the persistent resource doesn't exist,
+/// same as systems using `ResMut`.
+#[derive(SystemParam)]
+pub struct PersistMut<'w, R: Resource + Serialize + DeserializeOwned> {
+    persistent: ResMut<'w, Persistent<R>>,
+    ticks: SystemChangeTick,
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Deref for PersistMut<'_, R> {
+    type Target = Persistent<R>;
+
+    fn deref(&self) -> &Persistent<R> {
+        &self.persistent
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> DerefMut for PersistMut<'_, R> {
+    fn deref_mut(&mut self) -> &mut Persistent<R> {
+        &mut self.persistent
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Drop for PersistMut<'_, R> {
+    fn drop(&mut self) {
+        // mutable dereferences mark the resource as changed in the current run of the system
+        let changed = self.persistent.last_changed() == self.ticks.this_run();
+        if changed && self.persistent.is_loaded() {
+            // errors are logged in persist
+            self.persistent.persist().ok();
+        }
+    }
+}
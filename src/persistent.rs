@@ -1,6 +1,7 @@
 //! A persistent resource.
 
 use crate::prelude::*;
+use std::collections::VecDeque;
 
 /// A persistent resource.
 ///
@@ -12,12 +13,96 @@ use crate::prelude::*;
 /// They are synchronized with the disk from the moment of their creation.
 #[derive(Component, Debug, Resource)]
 pub struct Persistent<R: Resource + Serialize + DeserializeOwned> {
-    pub(crate) name: String,
+    pub(crate) name: Cow<'static, str>,
     pub(crate) format: StorageFormat,
     pub(crate) storage: Storage,
+    pub(crate) fallback_storage: Option<Storage>,
     pub(crate) resource: Option<R>,
     pub(crate) default: Option<Box<R>>,
+    pub(crate) revertible: bool,
+    pub(crate) retain_default: bool,
     pub(crate) revert_to_default_on_deserialization_errors: bool,
+    pub(crate) quiet: bool,
+    pub(crate) create_if_missing: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) create_dirs: bool,
+    pub(crate) deny_unknown_fields: bool,
+    pub(crate) fsync: bool,
+    pub(crate) persist_on_drop: bool,
+    pub(crate) skip_redundant_writes: bool,
+    pub(crate) history_limit: usize,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) check_free_space: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) max_read_bytes: Option<u64>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) follow_symlinks: bool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) temp_dir: Option<PathBuf>,
+    pub(crate) format_options: FormatOptions,
+    pub(crate) hooks: Hooks<R>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) merge: Option<fn(&mut R, R)>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) holds_record: std::sync::atomic::AtomicBool,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) abandoned_writer: Mutex<Option<std::thread::JoinHandle<()>>>,
+    #[cfg(target_family = "wasm")]
+    pub(crate) cache: Option<fn(&R) -> R>,
+    pub(crate) last_modified: Mutex<Option<SystemTime>>,
+    #[cfg(feature = "json")]
+    pub(crate) last_persisted: Mutex<Option<serde_json::Value>>,
+    pub(crate) history: Mutex<VecDeque<Vec<u8>>>,
+    #[cfg(target_family = "wasm")]
+    pub(crate) cached: Mutex<Option<(String, u64, R)>>,
+}
+
+/// A reflectable view of the configuration of a persistent resource
+/// (e.g., to show how a resource is persisted in an inspector).
+#[derive(Clone, Debug, Eq, PartialEq, Reflect)]
+pub struct PersistentInfo {
+    pub name: String,
+    pub format: StorageFormat,
+    pub storage: Storage,
+    pub fallback_storage: Option<Storage>,
+    pub loaded: bool,
+    pub revertible: bool,
+}
+
+/// A configuration of a persistent resource with named fields
+/// (e.g., to create a resource without positional arguments).
+///
+/// It's non-exhaustive so new options can be added, so it should be created with
+/// `PersistentConfig::new` and adjusted afterwards.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct PersistentConfig {
+    pub name: Cow<'static, str>,
+    pub format: StorageFormat,
+    pub storage: Storage,
+    pub loaded: bool,
+    pub revertible: bool,
+    pub revert_to_default_on_deserialization_errors: bool,
+}
+
+impl PersistentConfig {
+    /// Creates a configuration of a persistent resource.
+    ///
+    /// Resource is loaded and not revertible, same as the defaults of the builder.
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        format: StorageFormat,
+        storage: Storage,
+    ) -> PersistentConfig {
+        PersistentConfig {
+            name: name.into(),
+            format,
+            storage,
+            loaded: true,
+            revertible: false,
+            revert_to_default_on_deserialization_errors: false,
+        }
+    }
 }
 
 impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
@@ -27,11 +112,88 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
             name: None,
             format: None,
             path: None,
+            path_template: None,
+            fallback_path: None,
+            #[cfg(target_family = "wasm")]
+            key_prefix: None,
             loaded: true,
             default: None,
             revertible: false,
+            retain_default: false,
             revert_to_default_on_deserialization_errors: false,
+            quiet: false,
+            create_if_missing: true,
+            #[cfg(not(target_family = "wasm"))]
+            create_dirs: true,
+            #[cfg(not(target_family = "wasm"))]
+            relative_to_exe: false,
+            deny_unknown_fields: false,
+            fsync: false,
+            persist_on_drop: false,
+            skip_redundant_writes: false,
+            history: 0,
+            #[cfg(not(target_family = "wasm"))]
+            check_free_space: false,
+            #[cfg(not(target_family = "wasm"))]
+            max_read_bytes: None,
+            #[cfg(not(target_family = "wasm"))]
+            follow_symlinks: true,
+            #[cfg(not(target_family = "wasm"))]
+            temp_dir: None,
+            format_options: FormatOptions::default(),
+            hooks: Hooks::default(),
+            #[cfg(not(target_family = "wasm"))]
+            merge: None,
+            #[cfg(target_family = "wasm")]
+            cache: None,
+        }
+    }
+
+    /// Creates a persistent resource builder with the configuration of the resource
+    /// (e.g., to create a sibling resource with the same configuration in another path).
+    ///
+    /// Path of the resource is not carried over, so it needs to be set anew.
+    /// Hooks and validators are not carried over either, as they cannot be cloned.
+    /// Default of the resource is only carried over if it's retained.
+    /// Persisting on drop is not carried over, so copies don't write themselves when they
+    /// are dropped unless they are set to.
+    pub fn to_builder(&self) -> PersistentBuilder<R>
+    where
+        R: Clone,
+    {
+        let mut builder = Persistent::<R>::builder()
+            .name(self.name.clone())
+            .format(self.format)
+            .loaded(self.is_loaded())
+            .revertible(self.is_revertible())
+            .retain_default(self.retain_default)
+            .revert_to_default_on_deserialization_errors(
+                self.revert_to_default_on_deserialization_errors,
+            )
+            .quiet(self.quiet)
+            .create_if_missing(self.create_if_missing)
+            .deny_unknown_fields(self.deny_unknown_fields)
+            .fsync(self.fsync)
+            .skip_redundant_writes(self.skip_redundant_writes)
+            .history(self.history_limit);
+
+        builder.default = self.default.as_deref().cloned();
+        builder.format_options = self.format_options.clone();
+        #[cfg(not(target_family = "wasm"))]
+        {
+            builder.create_dirs = self.create_dirs;
+            builder.check_free_space = self.check_free_space;
+            builder.max_read_bytes = self.max_read_bytes;
+            builder.follow_symlinks = self.follow_symlinks;
+            builder.temp_dir = self.temp_dir.clone();
+            builder.merge = self.merge;
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            builder.cache = self.cache;
         }
+
+        builder
     }
 
     /// Creates a persistent resource.
@@ -41,7 +203,7 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
     /// Panics if `revert_to_default_on_deserialization_errors`
     /// is set to `true` but `revertible` is set to `false`.
     pub fn new(
-        name: impl ToString,
+        name: impl Into<Cow<'static, str>>,
         format: StorageFormat,
         storage: Storage,
         loaded: bool,
@@ -49,142 +211,532 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
         revertible: bool,
         revert_to_default_on_deserialization_errors: bool,
     ) -> Result<Persistent<R>, PersistenceError> {
-        if revert_to_default_on_deserialization_errors && !revertible {
+        let mut persistent = Persistent::<R>::uninitialized(name, format, storage);
+        persistent.revert_to_default_on_deserialization_errors =
+            revert_to_default_on_deserialization_errors;
+        persistent.initialize(loaded, default, revertible)
+    }
+
+    /// Creates a persistent resource from a configuration.
+    ///
+    /// It's the same as `new`, but with named options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `revert_to_default_on_deserialization_errors`
+    /// is set to `true` but `revertible` is set to `false`.
+    pub fn from_config(
+        config: PersistentConfig,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::new(
+            config.name,
+            config.format,
+            config.storage,
+            config.loaded,
+            default,
+            config.revertible,
+            config.revert_to_default_on_deserialization_errors,
+        )
+    }
+
+    /// Creates a persistent resource with the default configuration.
+    ///
+    /// It's a shortcut for building the resource with only its name, format, path
+    /// and default value (i.e., it's loaded, not revertible, and created if it's missing).
+    pub fn with_storage_format(
+        name: impl Into<Cow<'static, str>>,
+        format: StorageFormat,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::builder().name(name).format(format).path(path).default(default).build()
+    }
+
+    /// Creates a persistent resource in Bincode format with the default configuration.
+    #[cfg(feature = "bincode")]
+    pub fn bincode(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Bincode, path, default)
+    }
+
+    /// Creates a persistent resource in BSON format with the default configuration.
+    #[cfg(feature = "bson")]
+    pub fn bson(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Bson, path, default)
+    }
+
+    /// Creates a persistent resource in INI format with the default configuration.
+    #[cfg(feature = "ini")]
+    pub fn ini(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Ini, path, default)
+    }
+
+    /// Creates a persistent resource in JSON format with the default configuration.
+    #[cfg(feature = "json")]
+    pub fn json(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Json, path, default)
+    }
+
+    /// Creates a persistent resource in Pickle format with the default configuration.
+    #[cfg(feature = "pickle")]
+    pub fn pickle(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Pickle, path, default)
+    }
+
+    /// Creates a persistent resource in Protobuf format with the default configuration.
+    #[cfg(feature = "protobuf")]
+    pub fn protobuf(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Protobuf, path, default)
+    }
+
+    /// Creates a persistent resource in RON format with the default configuration.
+    #[cfg(feature = "ron")]
+    pub fn ron(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Ron, path, default)
+    }
+
+    /// Creates a persistent resource in TOML format with the default configuration.
+    #[cfg(feature = "toml")]
+    pub fn toml(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Toml, path, default)
+    }
+
+    /// Creates a persistent resource in YAML format with the default configuration.
+    #[cfg(feature = "yaml")]
+    pub fn yaml(
+        name: impl Into<Cow<'static, str>>,
+        path: impl Into<PathBuf>,
+        default: R,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::with_storage_format(name, StorageFormat::Yaml, path, default)
+    }
+
+    /// Creates a persistent resource from content which is written to its underlying storage
+    /// beforehand (e.g., to seed storages of tests without writing them manually).
+    ///
+    /// The resource is loaded, revertible and reverted to its default automatically
+    /// on deserialization errors, so invalid content can be seeded to test recovery.
+    #[cfg(feature = "testing")]
+    pub fn from_str(
+        name: impl Into<Cow<'static, str>>,
+        format: StorageFormat,
+        storage: Storage,
+        content: &str,
+    ) -> Result<Persistent<R>, PersistenceError>
+    where
+        R: Default,
+    {
+        storage.write_raw(content.as_bytes())?;
+        Persistent::new(name, format, storage, true, R::default(), true, true)
+    }
+
+    /// Creates an unloaded persistent resource without touching the underlying storage.
+    pub(crate) fn uninitialized(
+        name: impl Into<Cow<'static, str>>,
+        format: StorageFormat,
+        storage: Storage,
+    ) -> Persistent<R> {
+        Persistent {
+            name: name.into(),
+            format,
+            storage,
+            fallback_storage: None,
+            resource: None,
+            default: None,
+            revertible: false,
+            retain_default: false,
+            revert_to_default_on_deserialization_errors: false,
+            quiet: false,
+            create_if_missing: true,
+            #[cfg(not(target_family = "wasm"))]
+            create_dirs: true,
+            deny_unknown_fields: false,
+            fsync: false,
+            persist_on_drop: false,
+            skip_redundant_writes: false,
+            history_limit: 0,
+            #[cfg(not(target_family = "wasm"))]
+            check_free_space: false,
+            #[cfg(not(target_family = "wasm"))]
+            max_read_bytes: None,
+            #[cfg(not(target_family = "wasm"))]
+            follow_symlinks: true,
+            #[cfg(not(target_family = "wasm"))]
+            temp_dir: None,
+            format_options: FormatOptions::default(),
+            hooks: Hooks::default(),
+            #[cfg(not(target_family = "wasm"))]
+            merge: None,
+            #[cfg(not(target_family = "wasm"))]
+            holds_record: std::sync::atomic::AtomicBool::new(false),
+            #[cfg(not(target_family = "wasm"))]
+            abandoned_writer: Mutex::new(None),
+            #[cfg(target_family = "wasm")]
+            cache: None,
+            last_modified: Mutex::new(None),
+            #[cfg(feature = "json")]
+            last_persisted: Mutex::new(None),
+            history: Mutex::new(VecDeque::new()),
+            #[cfg(target_family = "wasm")]
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Synchronizes the persistent resource with the underlying storage for the first time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `revert_to_default_on_deserialization_errors`
+    /// is set to `true` but `revertible` is set to `false`.
+    pub(crate) fn initialize(
+        mut self,
+        loaded: bool,
+        default: R,
+        revertible: bool,
+    ) -> Result<Persistent<R>, PersistenceError> {
+        if self.revert_to_default_on_deserialization_errors && !revertible {
             panic!(
                 "revert to default on deserialization errors \
                 is set for a non-revertible persistent resource"
             );
         }
+        self.revertible = revertible;
 
-        let name = name.to_string();
+        // storage format needs to be detected before the fallback storage is migrated
+        #[cfg(any(
+            feature = "ini",
+            feature = "json",
+            feature = "ron",
+            feature = "toml",
+            feature = "yaml",
+        ))]
+        self.detect_format()?;
 
-        if !storage.occupied() {
+        self.migrate_from_fallback_storage()?;
+
+        if !self.storage.occupied() {
             // first run
 
-            storage.initialize().map_err(|error| {
-                // initialize can only return error for filesystem storage
-                log::error!(
-                    "failed to create the parent directory for {} at {}: {}",
-                    name,
-                    storage,
-                    error,
-                );
-                error
-            })?;
+            if self.create_if_missing {
+                self.initialize_storage(&self.storage)?;
+                self.write(&default)
+                    .map(|_| {
+                        if !self.quiet {
+                            log_event!(
+                                info,
+                                self,
+                                "saved default {} to {}",
+                                self.name,
+                                self.storage
+                            );
+                        }
+                    })
+                    .map_err(|error| {
+                        // serialization errors are already logged
+                        if !error.is_serde() {
+                            log_event!(
+                                error,
+                                self,
+                                "failed to save default {} to {}: {}",
+                                self.name,
+                                self.storage,
+                                error,
+                            );
+                        } else {
+                            log_event!(
+                                error,
+                                self,
+                                "failed to save default {} to {} due to a serialization error",
+                                self.name,
+                                self.storage,
+                            );
+                        }
+                        error
+                    })?;
+            } else if !self.quiet {
+                if loaded {
+                    log_event!(
+                        info,
+                        self,
+                        "using default {} without saving it to {}",
+                        self.name,
+                        self.storage
+                    );
+                } else {
+                    log_event!(
+                        info,
+                        self,
+                        "keeping default {} to load it later without saving it to {}",
+                        self.name,
+                        self.storage
+                    );
+                }
+            }
 
-            storage
-                .write(&name, format, &default)
-                .map(|_| {
-                    log::info!("saved default {} to {}", name, storage);
-                })
-                .map_err(|error| {
-                    // serialization errors are already logged
-                    if !error.is_serde() {
-                        log::error!("failed to save default {} to {}: {}", name, storage, error);
-                    } else {
-                        log::error!(
-                            "failed to save default {} to {} due to a serialization error",
-                            name,
-                            storage,
-                        );
-                    }
-                    error
-                })?;
-
-            let resource = if loaded {
-                // we need to make a copy of the default resource without using clone
-                // this is because cloning can have special semantics
-                // e.g., cloning Persistent<Arc<RwLock<R>>> and changing it
-                // would change the default object, which is not desired
-                let serialized = format.serialize(&name, &default).inspect_err(|_| {
-                    log::error!("failed to clone default {} due to a serialization error", name);
-                })?;
-                let reconstructed = format.deserialize(&name, &serialized).inspect_err(|_| {
-                    log::error!("failed to clone default {} due to a deserialization error", name);
-                })?;
-
-                Some(reconstructed)
-            } else {
-                None
-            };
-            let default = if revertible { Some(Box::new(default)) } else { None };
+            if loaded {
+                let reconstructed =
+                    self.reconstruct(&default, format_args!("default {}", self.name))?;
+                self.resource = Some(reconstructed);
+            }
+            // default is the only value to load later as it's not saved
+            if revertible || self.retain_default || (!loaded && !self.create_if_missing) {
+                self.default = Some(Box::new(default));
+            }
 
-            return Ok(Persistent {
-                name,
-                format,
-                storage,
-                resource,
-                default,
-                revert_to_default_on_deserialization_errors,
-            });
+            return Ok(self);
         }
 
-        let default = if revertible { Some(Box::new(default)) } else { None };
+        if revertible || self.retain_default {
+            self.default = Some(Box::new(default));
+        }
 
         if !loaded {
-            return Ok(Persistent {
-                name,
-                format,
-                storage,
-                resource: None,
-                default,
-                revert_to_default_on_deserialization_errors,
-            });
+            return Ok(self);
         }
 
-        let resource = match storage.read(&name, format) {
-            Ok(resource) => resource,
+        match self.read(&self.storage, self.format).and_then(|resource| self.loaded(resource)) {
+            Ok(resource) => {
+                self.resource = Some(resource);
+            },
             Err(error) => {
                 if !error.is_serde() {
-                    log::error!("failed to load {} from {}: {}", name, storage, error);
+                    log_event!(
+                        error,
+                        self,
+                        "failed to load {} from {}: {}",
+                        self.name,
+                        self.storage,
+                        error
+                    );
                 } else {
-                    log::error!(
+                    log_event!(
+                        error,
+                        self,
                         "failed to load {} from {} due to a deserialization error",
-                        name,
-                        storage,
+                        self.name,
+                        self.storage,
                     );
 
-                    if revert_to_default_on_deserialization_errors {
-                        log::info!(
-                            "attempting to revert {} to default in {} automatically",
-                            name,
-                            storage,
-                        );
+                    if self.revert_to_default_on_deserialization_errors {
+                        if !self.quiet {
+                            log_event!(
+                                info,
+                                self,
+                                "attempting to revert {} to default in {} automatically",
+                                self.name,
+                                self.storage,
+                            );
+                        }
 
-                        let mut result = Persistent {
-                            name,
-                            format,
-                            storage,
-                            resource: None,
-                            default,
-                            revert_to_default_on_deserialization_errors,
-                        };
-                        if result.revert_to_default().is_err() {
-                            // return the original deserialization error
-                            return Err(error);
+                        if let Err(recovery_error) = self.revert_to_default() {
+                            return Err(PersistenceError::RecoveryFailed {
+                                primary: Box::new(error),
+                                during_recovery: Box::new(recovery_error),
+                            });
                         }
-                        if loaded && result.revert_to_default_in_memory().is_err() {
-                            // return the original deserialization error
-                            return Err(error);
+                        if let Err(recovery_error) = self.revert_to_default_in_memory() {
+                            return Err(PersistenceError::RecoveryFailed {
+                                primary: Box::new(error),
+                                during_recovery: Box::new(recovery_error),
+                            });
                         }
 
-                        return Ok(result);
+                        return Ok(self);
                     }
                 }
                 return Err(error);
             },
+        }
+
+        self.remember_modification_time();
+        if !self.quiet {
+            log_event!(info, self, "loaded {} from {}", self.name, self.storage);
+        }
+
+        Ok(self)
+    }
+
+    /// Detects the storage format from the content of the storage
+    /// (or the fallback storage if only it is occupied).
+    ///
+    /// Nothing is done if the storage format is not `StorageFormat::Auto`.
+    #[cfg(any(
+        feature = "ini",
+        feature = "json",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml",
+    ))]
+    fn detect_format(&mut self) -> Result<(), PersistenceError> {
+        if self.format != StorageFormat::Auto {
+            return Ok(());
+        }
+
+        let storage = match &self.fallback_storage {
+            Some(fallback_storage) if !self.storage.occupied() && fallback_storage.occupied() => {
+                fallback_storage.clone()
+            },
+            _ => self.storage.clone(),
         };
+        if !storage.occupied() {
+            log_event!(
+                error,
+                self,
+                "failed to detect the storage format of {} as {} doesn't exist",
+                self.name,
+                storage,
+            );
+            return Err(PersistenceError::AmbiguousStorageFormat);
+        }
 
-        log::info!("loaded {} from {}", name, storage);
+        #[cfg(not(target_family = "wasm"))]
+        let content = storage.read_raw_within(self.max_read_bytes);
+        #[cfg(target_family = "wasm")]
+        let content = storage.read_raw();
 
-        Ok(Persistent {
-            name,
-            format,
-            storage,
-            resource: Some(resource),
-            default,
-            revert_to_default_on_deserialization_errors,
-        })
+        let (_, format) = content
+            .and_then(|serialized_resource| {
+                StorageFormat::deserialize_detecting::<R>(
+                    &self.name,
+                    &serialized_resource,
+                    &self.format_options,
+                )
+            })
+            .inspect_err(|error| {
+                // detection errors are logged in format module
+                if !error.is_serde() {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to load {} from {}: {}",
+                        self.name,
+                        storage,
+                        error,
+                    );
+                }
+            })?;
+
+        self.format = format;
+        if !self.quiet {
+            log_event!(
+                info,
+                self,
+                "detected storage format of {} in {} as {}",
+                self.name,
+                storage,
+                format
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Moves the resource from the fallback storage to the storage.
+    ///
+    /// Nothing is done if the storage is already occupied or the fallback storage is not.
+    fn migrate_from_fallback_storage(&self) -> Result<(), PersistenceError> {
+        let fallback_storage = match &self.fallback_storage {
+            Some(fallback_storage) => fallback_storage,
+            None => return Ok(()),
+        };
+        if self.storage.occupied() || !fallback_storage.occupied() {
+            return Ok(());
+        }
+
+        let resource = self.read(fallback_storage, self.format).map_err(|error| {
+            // deserialization errors are logged in format module
+            if !error.is_serde() {
+                log_event!(
+                    error,
+                    (self.name, fallback_storage, self.format),
+                    "failed to load {} from {}: {}",
+                    self.name,
+                    fallback_storage,
+                    error
+                );
+            } else {
+                log_event!(
+                    error,
+                    (self.name, fallback_storage, self.format),
+                    "failed to load {} from {} due to a deserialization error",
+                    self.name,
+                    fallback_storage,
+                );
+            }
+            error
+        })?;
+
+        self.initialize_storage(&self.storage)?;
+
+        self.write(&resource)
+            .map(|_| {
+                if !self.quiet {
+                    log_event!(
+                        info,
+                        self,
+                        "migrated {} from {} to {}",
+                        self.name,
+                        fallback_storage,
+                        self.storage,
+                    );
+                }
+            })
+            .map_err(|error| {
+                // serialization errors are logged in format module
+                if !error.is_serde() {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to migrate {} from {} to {}: {}",
+                        self.name,
+                        fallback_storage,
+                        self.storage,
+                        error,
+                    );
+                } else {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to migrate {} from {} to {} due to a serialization error",
+                        self.name,
+                        fallback_storage,
+                        self.storage,
+                    );
+                }
+                error
+            })
     }
 }
 
@@ -195,6 +747,9 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
     }
 
     /// Gets the storage format of the resource.
+    ///
+    /// If the resource is built with `StorageFormat::Auto`,
+    /// it's the storage format detected from the underlying storage.
     pub fn format(&self) -> StorageFormat {
         self.format
     }
@@ -204,43 +759,217 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
         &self.storage
     }
 
+    /// Gets the fallback storage of the resource.
+    pub fn fallback_storage(&self) -> Option<&Storage> {
+        self.fallback_storage.as_ref()
+    }
+
     /// Gets if the resource is revertible.
     pub fn is_revertible(&self) -> bool {
-        self.default.is_some()
+        self.revertible
     }
 
-    /// Gets if the resource is loaded.
-    pub fn is_loaded(&self) -> bool {
-        self.resource.is_some()
+    /// Gets if the default of the resource is retained.
+    pub fn retains_default(&self) -> bool {
+        self.retain_default
     }
 
-    /// Gets if the resource is unloaded.
-    pub fn is_unloaded(&self) -> bool {
-        self.resource.is_none()
+    /// Gets the default of the resource
+    /// (e.g., to show the default of a key binding next to its current value).
+    ///
+    /// It's `None` if the resource is neither revertible nor set to retain its default.
+    pub fn default(&self) -> Option<&R> {
+        self.default.as_deref()
     }
 
-    /// Gets the resource.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the resource is unloaded.
-    pub fn get(&self) -> &R {
-        if let Some(resource) = &self.resource {
-            resource
-        } else {
-            panic!("tried to get unloaded {}", self.name);
-        }
+    /// Gets if routine logs of the resource are suppressed.
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
     }
 
-    /// Gets the resource mutably.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the resource is unloaded.
-    pub fn get_mut(&mut self) -> &mut R {
-        if let Some(resource) = &mut self.resource {
-            resource
-        } else {
+    /// Gets if the underlying storage of the resource is created when it's missing.
+    pub fn creates_if_missing(&self) -> bool {
+        self.create_if_missing
+    }
+
+    /// Gets if missing parent directories of the underlying storage are created.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn creates_dirs(&self) -> bool {
+        self.create_dirs
+    }
+
+    /// Gets if unknown fields are denied when the resource is loaded.
+    pub fn denies_unknown_fields(&self) -> bool {
+        self.deny_unknown_fields
+    }
+
+    /// Gets if writes of the resource are flushed to the underlying device.
+    pub fn fsyncs(&self) -> bool {
+        self.fsync
+    }
+
+    /// Gets if the resource is cached in memory to be reloaded without reading its storage.
+    #[cfg(target_family = "wasm")]
+    pub fn is_cached(&self) -> bool {
+        self.cache.is_some()
+    }
+
+    /// Gets if the resource is written to its underlying storage when it's dropped.
+    pub fn persists_on_drop(&self) -> bool {
+        self.persist_on_drop
+    }
+
+    /// Gets if writes are skipped when the underlying storage already has the same content.
+    pub fn skips_redundant_writes(&self) -> bool {
+        self.skip_redundant_writes
+    }
+
+    /// Gets the maximum number of previous values of the resource kept in memory to undo.
+    pub fn history_limit(&self) -> usize {
+        self.history_limit
+    }
+
+    /// Gets the line ending of the resource in text storage formats,
+    /// or `None` if line endings are kept as storage formats serialize them.
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        self.format_options.line_ending
+    }
+
+    /// Gets the header comment of the underlying storage.
+    pub fn header(&self) -> Option<&str> {
+        self.format_options.header.as_deref()
+    }
+
+    /// Gets if free space of the underlying device is checked before writing the resource.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn checks_free_space(&self) -> bool {
+        self.check_free_space
+    }
+
+    /// Gets the maximum size of the underlying storage in bytes to read the resource from.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn max_read_bytes(&self) -> Option<u64> {
+        self.max_read_bytes
+    }
+
+    /// Gets if the underlying storage is written through symbolic links.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn follows_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+
+    /// Gets the directory to create temporary files in while writing the resource, if it's set.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn temp_dir(&self) -> Option<&std::path::Path> {
+        self.temp_dir.as_deref()
+    }
+
+    /// Gets a reflectable view of the configuration of the resource.
+    pub fn info(&self) -> PersistentInfo {
+        PersistentInfo {
+            name: self.name.to_string(),
+            format: self.format,
+            storage: self.storage.clone(),
+            fallback_storage: self.fallback_storage.clone(),
+            loaded: self.is_loaded(),
+            revertible: self.is_revertible(),
+        }
+    }
+
+    /// Logs a warning if the extension of the underlying storage doesn't match
+    /// the conventional extension of the storage format of the resource
+    /// (e.g., `key-bindings.json` with `StorageFormat::Toml`).
+    ///
+    /// Storages without extensions are not considered mismatched.
+    ///
+    /// Returns whether the extension mismatches.
+    pub fn warn_if_extension_mismatch(&self) -> bool {
+        let location = match &self.storage {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { path } => path.as_path(),
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { key } | Storage::SessionStorage { key } => {
+                std::path::Path::new(key)
+            },
+        };
+
+        let expected_extension = self.format.extension();
+        let mismatch = match location.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "yaml")]
+            Some(extension) if expected_extension == "yaml" => {
+                !(extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml"))
+            },
+            Some(extension) => !extension.eq_ignore_ascii_case(expected_extension),
+            None => false,
+        };
+
+        if mismatch {
+            log_event!(
+                warn,
+                self,
+                "extension of {} doesn't match the conventional extension of {} ({}) for {}",
+                self.storage,
+                self.format,
+                expected_extension,
+                self.name,
+            );
+        }
+        mismatch
+    }
+
+    /// Gets if the resource is loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.resource.is_some()
+    }
+
+    /// Gets if the resource is unloaded.
+    pub fn is_unloaded(&self) -> bool {
+        self.resource.is_none()
+    }
+
+    /// Gets if the resource is equal to its default
+    /// (e.g., to disable a reset button when there is nothing to reset).
+    ///
+    /// The resource and its default are compared by their serialized forms in the storage format
+    /// of the resource, so `R` doesn't need to implement `PartialEq`.
+    ///
+    /// It's `None` if the default of the resource is not retained, the resource is unloaded,
+    /// or it fails to be serialized.
+    pub fn is_default(&self) -> Option<bool> {
+        let resource = self.resource.as_ref()?;
+        let default = self.default.as_deref()?;
+
+        // serialization errors are logged in format module
+        let serialized_resource =
+            self.format.serialize_with(&self.name, resource, &self.format_options).ok()?;
+        let serialized_default =
+            self.format.serialize_with(&self.name, default, &self.format_options).ok()?;
+
+        Some(serialized_resource == serialized_default)
+    }
+
+    /// Gets the resource.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn get(&self) -> &R {
+        if let Some(resource) = &self.resource {
+            resource
+        } else {
+            panic!("tried to get unloaded {}", self.name);
+        }
+    }
+
+    /// Gets the resource mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn get_mut(&mut self) -> &mut R {
+        if let Some(resource) = &mut self.resource {
+            resource
+        } else {
             panic!("tried to get unloaded {} mutably", self.name);
         }
     }
@@ -254,17 +983,202 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
     pub fn try_get_mut(&mut self) -> Option<&mut R> {
         self.resource.as_mut()
     }
+
+    /// Tries to get the resource through reflection.
+    pub fn try_get_reflect(&self) -> Option<&dyn Reflect>
+    where
+        R: Reflect,
+    {
+        self.resource.as_ref().map(|resource| resource as &dyn Reflect)
+    }
+
+    /// Tries to get the resource mutably through reflection.
+    pub fn try_get_reflect_mut(&mut self) -> Option<&mut dyn Reflect>
+    where
+        R: Reflect,
+    {
+        self.resource.as_mut().map(|resource| resource as &mut dyn Reflect)
+    }
+
+    /// Gets the resource, loading it first if it's unloaded.
+    ///
+    /// If the underlying storage is empty and the default value of the resource is retained
+    /// (i.e., it's revertible or set to retain its default), the default value of the resource
+    /// is written to the underlying storage and loaded.
+    /// Otherwise, the resource is reloaded from the underlying storage.
+    pub fn get_or_load(&mut self) -> Result<&R, PersistenceError> {
+        if self.is_unloaded() {
+            if self.storage.occupied() || self.default.is_none() {
+                self.reload()?;
+            } else {
+                self.initialize_storage(&self.storage)?;
+                self.revert_to_retained_default()?;
+            }
+
+            // resource can still be unloaded if it's reverted to default
+            if self.is_unloaded() {
+                self.revert_to_retained_default_in_memory()?;
+            }
+        }
+        Ok(self.get())
+    }
+
+    /// Gets the resource, inserting the result of a function first if it's unloaded
+    /// (e.g., to lazily construct non-revertible resources without keeping their default).
+    ///
+    /// If the underlying storage is empty, the result of the function is written
+    /// to the underlying storage and loaded. Otherwise, the resource is reloaded
+    /// from the underlying storage and the function is not called.
+    pub fn get_or_insert_with(&mut self, f: impl FnOnce() -> R) -> Result<&R, PersistenceError> {
+        if self.is_unloaded() {
+            if self.storage.occupied() {
+                self.reload()?;
+            } else {
+                self.initialize_storage(&self.storage)?;
+                self.resource = Some(f());
+                if let Err(error) = self.persist() {
+                    self.resource = None;
+                    return Err(error);
+                }
+            }
+        }
+        Ok(self.get())
+    }
+
+    /// Gets a clone of the resource.
+    ///
+    /// The resource is cloned using its `Clone` implementation, so it should only be used
+    /// if cloning the resource has value semantics. Otherwise, see `clone_resource`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn get_cloned(&self) -> R
+    where
+        R: Clone,
+    {
+        self.get().clone()
+    }
+
+    /// Tries to get a clone of the resource.
+    ///
+    /// The resource is cloned using its `Clone` implementation, so it should only be used
+    /// if cloning the resource has value semantics. Otherwise, see `clone_resource`.
+    pub fn try_get_cloned(&self) -> Option<R>
+    where
+        R: Clone,
+    {
+        self.try_get().cloned()
+    }
+
+    /// Clones the resource.
+    ///
+    /// The resource is reconstructed by serializing and deserializing it with its storage format,
+    /// so special semantics of cloning are avoided (e.g., cloning `Arc<RwLock<R>>` would share
+    /// the underlying object, which is not desired for snapshots).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn clone_resource(&self) -> Result<R, PersistenceError> {
+        if let Some(resource) = &self.resource {
+            self.reconstruct(resource, format_args!("{}", self.name))
+        } else {
+            panic!("tried to clone unloaded {}", self.name);
+        }
+    }
 }
 
 impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
+    /// Sets the name of the resource.
+    ///
+    /// Name is only used to identify the resource in logs and errors,
+    /// so the underlying storage of the resource is not changed.
+    pub fn set_name(&mut self, name: impl Into<Cow<'static, str>>) {
+        self.name = name.into();
+    }
+
+    /// Registers a callback to run after each successful save of the resource
+    /// (e.g., to notify consumers outside of the ECS about changes).
+    ///
+    /// Saves which are skipped as they are redundant don't run the callback.
+    pub fn on_persisted(&mut self, callback: impl Fn(&R) + Send + Sync + 'static) {
+        self.hooks.on_persisted.push(Box::new(callback));
+    }
+
+    /// Creates a receiver which receives a clone of the resource after each successful save
+    /// of the resource (e.g., to send changes to another thread).
+    pub fn persisted_receiver(&mut self) -> std::sync::mpsc::Receiver<R>
+    where
+        R: Clone,
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.on_persisted(move |resource: &R| {
+            // the receiver might have been dropped
+            sender.send(resource.clone()).ok();
+        });
+        receiver
+    }
+
     /// Sets the resource.
     ///
     /// Changes are synchronized with the underlying storage immediately.
     pub fn set(&mut self, new_resource: R) -> Result<(), PersistenceError> {
         self.resource = Some(new_resource);
+        #[cfg(not(target_family = "wasm"))]
+        self.holds_record.store(true, std::sync::atomic::Ordering::Relaxed);
         self.persist()
     }
 
+    /// Sets the resource and gets the previous one, or `None` if the resource was unloaded
+    /// (e.g., to keep the previous value for undoing).
+    ///
+    /// Changes are synchronized with the underlying storage immediately.
+    /// Unlike `set`, the previous resource is restored if the new resource cannot be written,
+    /// so the previous resource is never lost.
+    pub fn replace(&mut self, new_resource: R) -> Result<Option<R>, PersistenceError> {
+        let previous_resource = self.resource.replace(new_resource);
+        #[cfg(not(target_family = "wasm"))]
+        let held_record = self.holds_record.swap(true, std::sync::atomic::Ordering::Relaxed);
+        match self.persist() {
+            Ok(()) => Ok(previous_resource),
+            Err(error) => {
+                self.resource = previous_resource;
+                #[cfg(not(target_family = "wasm"))]
+                self.holds_record.store(held_record, std::sync::atomic::Ordering::Relaxed);
+                Err(error)
+            },
+        }
+    }
+
+    /// Undoes the last change to the resource by setting it to its previous value in history
+    /// (see `PersistentBuilder::history`).
+    ///
+    /// Changes are synchronized with the underlying storage immediately.
+    /// Nothing is done if there is no previous value in history.
+    pub fn undo(&mut self) -> Result<(), PersistenceError> {
+        let (current, previous) = match self.history.lock() {
+            Ok(mut history) if history.len() >= 2 => {
+                let current = history.pop_back().unwrap();
+                let previous = history.pop_back().unwrap();
+                (current, previous)
+            },
+            _ => return Ok(()),
+        };
+
+        // previous value is remembered again once it's persisted
+        let result = self
+            .reconstruct_from(&previous, format_args!("previous {}", self.name))
+            .and_then(|resource| self.set(resource));
+        if result.is_err() {
+            if let Ok(mut history) = self.history.lock() {
+                history.push_back(previous);
+                history.push_back(current);
+            }
+        }
+        result
+    }
+
     /// Updates the resource.
     ///
     /// Changes are synchronized with the underlying storage immediately.
@@ -281,6 +1195,135 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
         }
     }
 
+    /// Updates a field of the resource by its JSON pointer in the JSON form of the resource
+    /// (e.g., `/audio/volume` to update `volume` field of `audio` field of the resource).
+    ///
+    /// Changes are synchronized with the underlying storage immediately.
+    ///
+    /// If the patched resource cannot be deserialized or it fails to validate,
+    /// the resource is kept untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    #[cfg(feature = "json")]
+    pub fn patch_json(
+        &mut self,
+        pointer: &str,
+        value: serde_json::Value,
+    ) -> Result<(), PersistenceError> {
+        let resource = match &self.resource {
+            Some(resource) => resource,
+            None => panic!("tried to patch unloaded {}", self.name),
+        };
+
+        let mut json = serde_json::to_value(resource).map_err(|error| {
+            log_event!(error, self, "failed to convert {} to JSON: {}", self.name, error);
+            PersistenceError::JsonSerialization(error)
+        })?;
+        match json.pointer_mut(pointer) {
+            Some(target) => *target = value,
+            None => {
+                log_event!(
+                    error,
+                    self,
+                    "failed to patch {} as {:?} doesn't point to a value",
+                    self.name,
+                    pointer,
+                );
+                return Err(PersistenceError::InvalidJsonPointer(pointer.to_owned()));
+            },
+        }
+
+        let patched = serde_json::from_value::<R>(json)
+            .map_err(|error| {
+                log_event!(
+                    error,
+                    self,
+                    "failed to patch {} at {:?}: {}",
+                    self.name,
+                    pointer,
+                    error
+                );
+                PersistenceError::JsonDeserialization(error)
+            })
+            .and_then(|patched| self.hooks.validate(&patched).map(|_| patched))
+            .inspect_err(|error| {
+                if let PersistenceError::Validation(reason) = error {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to patch {} at {:?} as it failed to validate: {}",
+                        self.name,
+                        pointer,
+                        reason,
+                    );
+                }
+            })?;
+
+        self.resource = Some(patched);
+        self.persist()
+    }
+
+    /// Merges a partial resource in bytes onto the resource using its storage format
+    /// (e.g., to apply overrides provided by a server onto a local configuration).
+    ///
+    /// The partial resource and the resource are merged in their JSON forms. Objects are merged
+    /// recursively, and other values, including arrays, replace the values they are merged onto
+    /// as a whole (i.e., arrays are not appended to). Fields that are missing in the partial
+    /// resource are kept untouched, so the storage format needs to be self-describing
+    /// (i.e., not Bincode).
+    ///
+    /// Changes are synchronized with the underlying storage immediately.
+    ///
+    /// If the merged resource cannot be deserialized or it fails to validate,
+    /// the resource is kept untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    #[cfg(feature = "json")]
+    pub fn merge_bytes(&mut self, bytes: &[u8]) -> Result<(), PersistenceError> {
+        let resource = match &self.resource {
+            Some(resource) => resource,
+            None => panic!("tried to merge onto unloaded {}", self.name),
+        };
+
+        // deserialization errors are logged in format module
+        let partial = self.format.deserialize_with::<serde_json::Value>(
+            &self.name,
+            bytes,
+            &self.format_options,
+        )?;
+
+        let mut json = serde_json::to_value(resource).map_err(|error| {
+            log_event!(error, self, "failed to convert {} to JSON: {}", self.name, error);
+            PersistenceError::JsonSerialization(error)
+        })?;
+        merge_json_values(&mut json, partial);
+
+        let merged = serde_json::from_value::<R>(json)
+            .map_err(|error| {
+                log_event!(error, self, "failed to merge onto {}: {}", self.name, error);
+                PersistenceError::JsonDeserialization(error)
+            })
+            .and_then(|merged| self.hooks.validate(&merged).map(|_| merged))
+            .inspect_err(|error| {
+                if let PersistenceError::Validation(reason) = error {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to merge onto {} as it failed to validate: {}",
+                        self.name,
+                        reason,
+                    );
+                }
+            })?;
+
+        self.resource = Some(merged);
+        self.persist()
+    }
+
     /// Unloads the resource from memory.
     ///
     /// Changes are synchronized with the underlying storage before unloading.
@@ -289,54 +1332,100 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
     ///
     /// Panics if the resource is unloaded.
     pub fn unload(&mut self) -> Result<(), PersistenceError> {
-        if self.resource.is_some() {
+        self.unload_with(true)
+    }
+
+    /// Unloads the resource from memory immediately.
+    ///
+    /// Changes are **not** synchronized with the underlying storage before unloading.
+    pub fn unload_without_persisting(&mut self) {
+        // unloading cannot fail without persisting
+        self.unload_with(false).ok();
+    }
+
+    /// Unloads the resource from memory, synchronizing the changes
+    /// with the underlying storage before unloading if `persist` is `true`.
+    ///
+    /// If persisting fails, the resource is kept loaded and the error is returned.
+    pub fn unload_with(&mut self, persist: bool) -> Result<(), PersistenceError> {
+        if self.resource.is_none() {
+            return Ok(());
+        }
+
+        if persist {
             self.persist().inspect_err(|_| {
-                log::error!(
+                log_event!(
+                    error,
+                    self,
                     "failed to unload {} due to not being able to persist it before unloading",
                     self.name,
                 );
             })?;
-            self.resource = None;
-            log::info!("unloaded {}", self.name);
         }
-        Ok(())
-    }
 
-    /// Unloads the resource from memory immediately.
-    ///
-    /// Changes are **not** synchronized with the underlying storage before unloading.
-    pub fn unload_without_persisting(&mut self) {
-        if self.resource.is_some() {
-            self.resource = None;
-            log::info!("unloaded {} without persisting", self.name);
+        self.resource = None;
+        if !self.quiet {
+            if persist {
+                log_event!(info, self, "unloaded {}", self.name);
+            } else {
+                log_event!(info, self, "unloaded {} without persisting", self.name);
+            }
         }
+
+        Ok(())
     }
 
     /// Reloads the resource from the underlying storage.
     ///
     /// If reloading fails, the underlying resource is kept untouched.
     pub fn reload(&mut self) -> Result<(), PersistenceError> {
-        match self.storage.read(&self.name, self.format) {
-            Ok(resource) => self.resource = Some(resource),
+        #[cfg(target_family = "wasm")]
+        if let Some(resource) = self.cached_resource() {
+            self.resource = Some(resource);
+            if !self.quiet {
+                log_event!(info, self, "reloaded {} from the cache of {}", self.name, self.storage);
+            }
+            return Ok(());
+        }
+
+        match self.read(&self.storage, self.format).and_then(|resource| self.loaded(resource)) {
+            Ok(resource) => {
+                self.resource = Some(resource);
+            },
             Err(error) => {
                 if !error.is_serde() {
-                    log::error!("failed to reload {} from {}: {}", self.name, self.storage, error);
+                    log_event!(
+                        error,
+                        self,
+                        "failed to reload {} from {}: {}",
+                        self.name,
+                        self.storage,
+                        error
+                    );
                 } else {
-                    log::error!(
+                    log_event!(
+                        error,
+                        self,
                         "failed to reload {} from {} due to a deserialization error",
                         self.storage,
                         self.name,
                     );
 
                     if self.revert_to_default_on_deserialization_errors {
-                        log::info!(
-                            "attempting to revert {} to default in {} automatically",
-                            self.name,
-                            self.storage,
-                        );
-                        if self.revert_to_default().is_err() {
-                            // return the original deserialization error
-                            return Err(error);
+                        if !self.quiet {
+                            log_event!(
+                                info,
+                                self,
+                                "attempting to revert {} to default in {} automatically",
+                                self.name,
+                                self.storage,
+                            );
+                        }
+                        if let Err(recovery_error) = self.revert_to_default() {
+                            return Err(PersistenceError::RecoveryFailed {
+                                primary: Box::new(error),
+                                during_recovery: Box::new(recovery_error),
+                            });
                         }
                         return Ok(());
                     }
@@ -344,53 +1433,259 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
                 return Err(error);
             },
         }
-        log::info!("reloaded {} from {}", self.name, self.storage);
+        self.remember_modification_time();
+        if !self.quiet {
+            log_event!(info, self, "reloaded {} from {}", self.name, self.storage);
+        }
         Ok(())
     }
 
-    /// Reverts the resource to it's default value.
+    /// Reloads the resource from the underlying storage and writes it back immediately.
     ///
-    /// Loaded status is kept upon reloading.
-    ///
-    /// # Panics
+    /// The value of the resource is kept as is, but the underlying storage is rewritten
+    /// in the canonical form of the storage format and the current format options
+    /// (e.g., after pretty settings are changed, or the storage is edited by hand).
+    /// Unloaded resources are loaded as well.
     ///
-    /// Panics if the resource is not revertible.
-    pub fn revert_to_default(&mut self) -> Result<(), PersistenceError> {
-        if !self.is_revertible() {
-            panic!("tried to revert non-revertible {}", self.name);
-        }
+    /// In append mode, records in the underlying storage are compacted into a single record.
+    pub fn touch(&mut self) -> Result<(), PersistenceError> {
+        self.reload()?;
+        self.persist()
+    }
 
-        self.storage
-            .write(&self.name, self.format, self.default.as_ref().unwrap())
-            .map(|_| {
-                log::info!("reverted {} to default in {}", self.name, self.storage);
-            })
-            .map_err(|error| {
-                // serialization errors are logged in format module
-                if !error.is_serde() {
-                    log::error!(
-                        "failed to revert {} to default in {}: {}",
-                        self.name,
-                        self.storage,
-                        error,
-                    );
-                } else {
-                    log::error!(
-                        "failed to revert {} to default in {} due to a serialization error",
+    /// Reloads the resource from the underlying storage,
+    /// or reverts it to its default value if the underlying storage cannot be deserialized.
+    ///
+    /// Unlike `reload`, deserialization errors are not returned, regardless of
+    /// `revert_to_default_on_deserialization_errors` setting of the resource,
+    /// as long as the default value of the resource is retained
+    /// (i.e., it's revertible or set to retain its default).
+    /// Otherwise, they are returned within `PersistenceError::RecoveryFailed`
+    /// along with `PersistenceError::DefaultNotRetained`.
+    pub fn reload_or_default(&mut self) -> Result<(), PersistenceError> {
+        match self.reload() {
+            Ok(()) => {},
+            Err(error) if error.is_serde() => {
+                if !self.quiet {
+                    log_event!(
+                        info,
+                        self,
+                        "attempting to revert {} to default in {}",
                         self.name,
                         self.storage,
                     );
                 }
-                error
+                if let Err(recovery_error) = self.revert_to_retained_default() {
+                    return Err(PersistenceError::RecoveryFailed {
+                        primary: Box::new(error),
+                        during_recovery: Box::new(recovery_error),
+                    });
+                }
+            },
+            Err(error) => return Err(error),
+        }
+
+        // resource can still be unloaded if it's reverted to default
+        if self.is_unloaded() {
+            self.revert_to_retained_default_in_memory()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reloads the resource from the underlying storage if it's changed since it was last seen.
+    ///
+    /// Changes are detected using the modification time of the underlying storage,
+    /// so the resource is not deserialized needlessly. Unloaded resources are always reloaded.
+    ///
+    /// Browser storages don't have modification times, so resources stored in them
+    /// are always reloaded.
+    ///
+    /// Returns whether the resource is reloaded.
+    pub fn reload_if_changed(&mut self) -> Result<bool, PersistenceError> {
+        if self.is_loaded() {
+            if let Some(modified) = self.storage.modified() {
+                let last_modified = self.last_modified.lock().ok().and_then(|guard| *guard);
+                if last_modified == Some(modified) {
+                    return Ok(false);
+                }
+            }
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Loads the resource from another storage using another storage format.
+    ///
+    /// Loaded resource is written to the underlying storage of the resource immediately.
+    ///
+    /// If loading fails, the underlying resource is kept untouched.
+    pub fn load_from(
+        &mut self,
+        storage: &Storage,
+        format: StorageFormat,
+    ) -> Result<(), PersistenceError> {
+        match self.read(storage, format).and_then(|resource| self.loaded(resource)) {
+            Ok(resource) => {
+                self.resource = Some(resource);
+            },
+            Err(error) => {
+                if !error.is_serde() {
+                    log_event!(
+                        error,
+                        (self.name, storage, format),
+                        "failed to import {} from {}: {}",
+                        self.name,
+                        storage,
+                        error
+                    );
+                } else {
+                    log_event!(
+                        error,
+                        (self.name, storage, format),
+                        "failed to import {} from {} due to a deserialization error",
+                        self.name,
+                        storage,
+                    );
+
+                    if self.revert_to_default_on_deserialization_errors {
+                        if !self.quiet {
+                            log_event!(
+                                info,
+                                self,
+                                "attempting to revert {} to default in {} automatically",
+                                self.name,
+                                self.storage,
+                            );
+                        }
+                        if let Err(recovery_error) = self.revert_to_default() {
+                            return Err(PersistenceError::RecoveryFailed {
+                                primary: Box::new(error),
+                                during_recovery: Box::new(recovery_error),
+                            });
+                        }
+                        return Ok(());
+                    }
+                }
+                return Err(error);
+            },
+        }
+        if !self.quiet {
+            log_event!(
+                info,
+                (self.name, storage, format),
+                "imported {} from {}",
+                self.name,
+                storage
+            );
+        }
+        self.persist()
+    }
+
+    /// Loads the resource from a reader using its storage format
+    /// (e.g., to load the resource from a decompression stream).
+    ///
+    /// Loaded resource is written to the underlying storage of the resource immediately.
+    ///
+    /// If loading fails, the underlying resource is kept untouched.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn read_from<T: std::io::Read>(&mut self, reader: &mut T) -> Result<(), PersistenceError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(|error| {
+            log_event!(error, self, "failed to read {} from a reader: {}", self.name, error);
+            PersistenceError::from(error)
+        })?;
+
+        self.import(&bytes, "a reader")
+    }
+
+    /// Loads the resource from bytes using its storage format
+    /// (e.g., to reset the resource to a baseline embedded with `include_bytes!`).
+    ///
+    /// Loaded resource is written to the underlying storage of the resource immediately.
+    ///
+    /// If loading fails, the underlying resource is kept untouched.
+    pub fn load_bytes(&mut self, bytes: &[u8]) -> Result<(), PersistenceError> {
+        self.import(bytes, "bytes")
+    }
+
+    /// Reverts the resource to it's default value.
+    ///
+    /// Loaded status is kept upon reloading.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is not revertible.
+    pub fn revert_to_default(&mut self) -> Result<(), PersistenceError> {
+        if !self.is_revertible() {
+            panic!("tried to revert non-revertible {}", self.name);
+        }
+
+        self.revert_to_retained_default()
+    }
+
+    /// Reverts the resource to its retained default value.
+    ///
+    /// Unlike `revert_to_default`, resources which are not revertible but retain their
+    /// default are reverted as well, and an error is returned if the default is not retained.
+    fn revert_to_retained_default(&mut self) -> Result<(), PersistenceError> {
+        let default = self.default.as_deref().ok_or(PersistenceError::DefaultNotRetained)?;
+        self.write(default)
+            .map(|_| {
+                if !self.quiet {
+                    log_event!(info, self, "reverted {} to default in {}", self.name, self.storage);
+                }
+            })
+            .map_err(|error| {
+                // serialization errors are logged in format module
+                if !error.is_serde() {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to revert {} to default in {}: {}",
+                        self.name,
+                        self.storage,
+                        error,
+                    );
+                } else {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to revert {} to default in {} due to a serialization error",
+                        self.name,
+                        self.storage,
+                    );
+                }
+                error
             })?;
 
         if self.is_loaded() {
-            self.revert_to_default_in_memory()?;
+            self.revert_to_retained_default_in_memory()?;
         }
 
         Ok(())
     }
 
+    /// Reverts the resource to it's default value and gets the value before reverting
+    /// (e.g., to offer undoing a reset).
+    ///
+    /// The previous value is cloned with `clone_resource` before reverting, so reverting is
+    /// not attempted if cloning fails. It's `None` if the resource is unloaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is not revertible.
+    pub fn revert_to_default_returning(&mut self) -> Result<Option<R>, PersistenceError> {
+        if !self.is_revertible() {
+            panic!("tried to revert non-revertible {}", self.name);
+        }
+
+        let previous = if self.is_loaded() { Some(self.clone_resource()?) } else { None };
+        self.revert_to_default()?;
+
+        Ok(previous)
+    }
+
     /// Reverts the resource to it's default value only in memory, not persistent storage.
     ///
     /// # Panics
@@ -401,28 +1696,23 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
             panic!("tried to revert non-revertible {}", self.name);
         }
 
-        // we need to make a copy of the default resource without using clone
-        // this is because cloning can have special semantics
-        // e.g., cloning Persistent<Arc<RwLock<R>>> and changing it
-        // would change the default object, which is not desired
-        let serialized = self
-            .format
-            .serialize(&self.name, self.default.as_ref().unwrap())
-            .inspect_err(|_| {
-                log::error!(
-                    "failed to revert {} to default in memory due to a serialization error",
-                    self.name,
-                );
-            })?;
-        let reconstructed = self.format.deserialize(&self.name, &serialized).inspect_err(|_| {
-            log::error!(
-                "failed to revert {} to default in memory due to a deserialization error",
-                self.name,
-            );
-        })?;
+        self.revert_to_retained_default_in_memory()
+    }
+
+    /// Reverts the resource to its retained default value only in memory.
+    ///
+    /// Unlike `revert_to_default_in_memory`, resources which are not revertible but retain their
+    /// default are reverted as well, and an error is returned if the default is not retained.
+    fn revert_to_retained_default_in_memory(&mut self) -> Result<(), PersistenceError> {
+        let default = self.default.as_deref().ok_or(PersistenceError::DefaultNotRetained)?;
+        let reconstructed = self.reconstruct(default, format_args!("default {}", self.name))?;
 
         self.resource = Some(reconstructed);
-        log::info!("reverted {} to default in memory", self.name);
+        #[cfg(not(target_family = "wasm"))]
+        self.holds_record.store(false, std::sync::atomic::Ordering::Relaxed);
+        if !self.quiet {
+            log_event!(info, self, "reverted {} to default in memory", self.name);
+        }
         Ok(())
     }
 }
@@ -435,45 +1725,1462 @@ impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
     /// Panics if the resource is unloaded.
     pub fn persist(&self) -> Result<(), PersistenceError> {
         if let Some(resource) = &self.resource {
-            self.storage
-                .write(&self.name, self.format, resource)
+            self.hooks.saving(resource);
+            if !self.create_if_missing && !self.storage.occupied() {
+                self.initialize_storage(&self.storage)?;
+            }
+            self.write(resource).map(|length| self.saved(resource, length)).map_err(|error| {
+                // serialization errors are logged in format module
+                if !error.is_serde() {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to save new {} to {}: {}",
+                        self.name,
+                        self.storage,
+                        error,
+                    );
+                } else {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to save new {} to {} due to a serialization error",
+                        self.name,
+                        self.storage,
+                    );
+                }
+                error
+            })
+        } else {
+            panic!("tried to save unloaded {}", self.name);
+        }
+    }
+
+    /// Writes the resource to the underlying storage, giving up if writing takes longer
+    /// than a timeout (e.g., to avoid freezing on an unresponsive network filesystem).
+    ///
+    /// The resource is serialized on the current thread, and the serialized resource is written
+    /// the same way as `persist` on a separate thread. Writing cannot be cancelled, so if it
+    /// doesn't finish in time, it's abandoned and `PersistenceError::Timeout` is returned,
+    /// but it may still finish later in the background. Resources are written through temporary
+    /// files, so an abandoned write never leaves the underlying storage half written, but it may
+    /// leave a stray temporary file behind if the application exits before it finishes.
+    /// If the thread panics before writing finishes, `PersistenceError::WriterPanicked`
+    /// is returned.
+    ///
+    /// At most one abandoned write is kept in the background for each resource, so while it's
+    /// still in progress, `PersistenceError::Timeout` is returned without trying to write again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn persist_with_timeout(
+        &self,
+        timeout: std::time::Duration,
+    ) -> Result<(), PersistenceError> {
+        let Some(resource) = &self.resource else {
+            panic!("tried to save unloaded {}", self.name);
+        };
+
+        self.hooks.saving(resource);
+        if !self.create_if_missing && !self.storage.occupied() {
+            self.initialize_storage(&self.storage)?;
+        }
+
+        let mut abandoned_writer =
+            self.abandoned_writer.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if abandoned_writer.as_ref().is_some_and(|writer| !writer.is_finished()) {
+            log_event!(
+                error,
+                self,
+                "failed to save new {} to {} as the previous write is still in progress",
+                self.name,
+                self.storage,
+            );
+            return Err(PersistenceError::Timeout(timeout));
+        }
+
+        // serialization errors are logged in format module
+        let bytes = self.format.serialize_with(&self.name, resource, &self.record_options())?;
+
+        let storage = self.storage.clone();
+        let options = self.write_options(&self.storage, self.format, resource);
+
+        let result = self
+            .ensure_dirs(&self.storage)
+            .and_then(|_| {
+                let (sender, receiver) = std::sync::mpsc::channel();
+                let writer = std::thread::spawn(move || {
+                    sender.send(storage.write_serialized_with(&bytes, &options)).ok();
+                });
+                match receiver.recv_timeout(timeout) {
+                    Ok(result) => result,
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                        *abandoned_writer = Some(writer);
+                        Err(PersistenceError::Timeout(timeout))
+                    },
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                        Err(PersistenceError::WriterPanicked)
+                    },
+                }
+            })
+            .map_err(|error| error.with_context(&self.name, &self.storage));
+        match result {
+            Ok(length) => {
+                self.remember_in_history(resource);
+                self.remember_written(resource);
+                self.saved(resource, length);
+                Ok(())
+            },
+            Err(error) => {
+                log_event!(
+                    error,
+                    self,
+                    "failed to save new {} to {}: {}",
+                    self.name,
+                    self.storage,
+                    error,
+                );
+                Err(error)
+            },
+        }
+    }
+
+    /// Writes the resource to the underlying storage.
+    ///
+    /// It's an alias for `persist`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn flush(&self) -> Result<(), PersistenceError> {
+        self.persist()
+    }
+
+    /// Writes the resource to another storage using another storage format.
+    ///
+    /// The storage and the storage format of the resource are not changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn persist_to(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+    ) -> Result<(), PersistenceError> {
+        if let Some(resource) = &self.resource {
+            self.initialize_storage(storage)?;
+            self.write_to_storage(storage, format, resource)
                 .map(|_| {
-                    log::info!("saved new {} to {}", self.name, self.storage);
+                    if !self.quiet {
+                        log_event!(
+                            info,
+                            (self.name, storage, format),
+                            "exported {} to {}",
+                            self.name,
+                            storage
+                        );
+                    }
                 })
                 .map_err(|error| {
                     // serialization errors are logged in format module
                     if !error.is_serde() {
-                        log::error!(
-                            "failed to save new {} to {}: {}",
-                            self.name,
-                            self.storage,
+                        log_event!(
                             error,
+                            (self.name, storage, format),
+                            "failed to export {} to {}: {}",
+                            self.name,
+                            storage,
+                            error
                         );
                     } else {
-                        log::error!(
-                            "failed to save new {} to {} due to a serialization error",
+                        log_event!(
+                            error,
+                            (self.name, storage, format),
+                            "failed to export {} to {} due to a serialization error",
                             self.name,
-                            self.storage,
+                            storage,
                         );
                     }
                     error
                 })
         } else {
-            panic!("tried to save unloaded {}", self.name);
+            panic!("tried to export unloaded {}", self.name);
         }
     }
-}
 
-impl<R: Resource + Serialize + DeserializeOwned> Deref for Persistent<R> {
-    type Target = R;
+    /// Writes the resource to the underlying storage using another storage format once.
+    ///
+    /// The storage format of the resource is not changed, so the resource is still read
+    /// (e.g., when it's reloaded) and written (e.g., when it's persisted) in its own format.
+    /// Unless the underlying storage is persisted in the storage format of the resource again,
+    /// reading it afterwards fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn persist_as(&self, format: StorageFormat) -> Result<(), PersistenceError> {
+        self.persist_to(&self.storage, format)
+    }
 
-    fn deref(&self) -> &R {
-        self.get()
+    /// Writes the resource to a file as pretty JSON
+    /// (e.g., to attach a human readable version of a binary save to a bug report).
+    ///
+    /// The storage and the storage format of the resource are not changed.
+    ///
+    /// Fails with `PersistenceError::DisabledStorageFormat` if `json` and `pretty` features
+    /// are not enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn export_pretty(&self, path: &std::path::Path) -> Result<(), PersistenceError> {
+        #[cfg(all(feature = "json", feature = "pretty"))]
+        {
+            self.persist_to(
+                &Storage::Filesystem { path: path.to_path_buf() },
+                StorageFormat::JsonPretty,
+            )
+        }
+        #[cfg(not(all(feature = "json", feature = "pretty")))]
+        {
+            if self.is_unloaded() {
+                panic!("tried to export unloaded {}", self.name);
+            }
+            log_event!(
+                error,
+                self,
+                "failed to export {} to {} as pretty JSON: json and pretty features are required",
+                self.name,
+                path.display(),
+            );
+            Err(PersistenceError::DisabledStorageFormat("json-pretty".to_owned()))
+        }
     }
-}
 
-impl<R: Resource + Serialize + DeserializeOwned> DerefMut for Persistent<R> {
-    fn deref_mut(&mut self) -> &mut R {
-        self.get_mut()
+    /// Gets the fields of the resource which are changed since it was last
+    /// loaded from or saved to its underlying storage (e.g., to implement undo).
+    ///
+    /// Fields are reported as JSON pointers to the changed values in the JSON form
+    /// of the resource (e.g., `/audio/volume`), and they are sorted by their keys.
+    /// If the resource hasn't been synchronized with the underlying storage yet,
+    /// the whole resource (i.e., an empty JSON pointer) is reported as changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    #[cfg(feature = "json")]
+    pub fn diff(&self) -> Result<Vec<String>, PersistenceError> {
+        let resource = match &self.resource {
+            Some(resource) => resource,
+            None => panic!("tried to diff unloaded {}", self.name),
+        };
+
+        let new_value = serde_json::to_value(resource).map_err(|error| {
+            log_event!(error, self, "failed to serialize {} to JSON\n\n{}", self.name, error);
+            PersistenceError::JsonSerialization(error)
+        })?;
+        let old_value = self.last_persisted.lock().ok().and_then(|guard| guard.clone());
+
+        let mut changed_paths = Vec::new();
+        match old_value {
+            Some(old_value) => changed_json_paths(&old_value, &new_value, "", &mut changed_paths),
+            None => changed_paths.push(String::new()),
+        }
+        Ok(changed_paths)
+    }
+
+    /// Gets the bytes that would be written to the underlying storage
+    /// if the resource is persisted (e.g., to show the changes before saving).
+    ///
+    /// The underlying storage of the resource is not touched.
+    ///
+    /// In append mode, the bytes are of the record that would be appended.
+    /// In browser storages, binary storage formats are stored as arrays of bytes,
+    /// and the rest are stored as strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn preview(&self) -> Result<Vec<u8>, PersistenceError> {
+        if let Some(resource) = &self.resource {
+            // serialization errors are logged in format module
+            self.format.serialize_with(&self.name, resource, &self.record_options())
+        } else {
+            panic!("tried to preview unloaded {}", self.name);
+        }
+    }
+
+    /// Gets the string that would be written to the underlying storage
+    /// if the resource is persisted (e.g., to log the resource).
+    ///
+    /// The underlying storage of the resource is not touched.
+    ///
+    /// Binary storage formats cannot be represented as strings,
+    /// so they result in `PersistenceError::BinaryStorageFormat`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    pub fn format_as_string(&self) -> Result<String, PersistenceError> {
+        if self.format.is_binary() {
+            return Err(PersistenceError::BinaryStorageFormat(self.format));
+        }
+        let bytes = self.preview()?;
+        String::from_utf8(bytes).map_err(|error| PersistenceError::Encoding(error.utf8_error()))
+    }
+
+    /// Writes the resource to a writer using its storage format
+    /// (e.g., to dump the resource to the standard output).
+    ///
+    /// The underlying storage of the resource is not touched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> Result<(), PersistenceError> {
+        if let Some(resource) = &self.resource {
+            // serialization errors are logged in format module
+            let bytes = self.format.serialize_with(&self.name, resource, &self.format_options)?;
+            writer.write_all(&bytes).map_err(|error| {
+                log_event!(error, self, "failed to write {} to a writer: {}", self.name, error);
+                PersistenceError::from(error)
+            })
+        } else {
+            panic!("tried to write unloaded {}", self.name);
+        }
+    }
+
+    /// Reveals the underlying storage of the resource in the file manager of the platform
+    /// (e.g., to let players find their save files).
+    ///
+    /// The file is selected in Explorer on Windows and in Finder on macOS, and its parent
+    /// directory is opened on other desktop platforms (see `Storage::reveal_command`).
+    ///
+    /// Fails with `PersistenceError::FileManagerUnavailable` if there is no file manager
+    /// on the platform or no graphical session to show it in.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn reveal_in_file_manager(&self) -> Result<(), PersistenceError> {
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            log_event!(
+                error,
+                self,
+                "failed to reveal {} in the file manager: no graphical session is available",
+                self.name,
+            );
+            return Err(PersistenceError::FileManagerUnavailable);
+        }
+
+        let mut command = self.storage.reveal_command().inspect_err(|error| {
+            log_event!(
+                error,
+                self,
+                "failed to reveal {} in the file manager: {}",
+                self.name,
+                error,
+            );
+        })?;
+        let status = command.status().map_err(|error| {
+            log_event!(
+                error,
+                self,
+                "failed to reveal {} in the file manager: {}",
+                self.name,
+                error,
+            );
+            if error.kind() == std::io::ErrorKind::NotFound {
+                // the file manager of the platform is not installed (e.g., no xdg-open)
+                PersistenceError::FileManagerUnavailable
+            } else {
+                PersistenceError::from(error)
+            }
+        })?;
+
+        // explorer exits with a failure status even when it succeeds
+        if !status.success() && !cfg!(target_os = "windows") {
+            log_event!(
+                error,
+                self,
+                "failed to reveal {} in the file manager: {:?} exited with {}",
+                self.name,
+                command.get_program(),
+                status,
+            );
+            return Err(PersistenceError::FileManagerUnavailable);
+        }
+
+        Ok(())
+    }
+
+    /// Changes the storage format of the resource.
+    ///
+    /// The resource in the underlying storage is read with the old storage format
+    /// and written with the new storage format immediately, the same way as `persist`
+    /// (i.e., save hooks run for the written resource). If the underlying storage is empty,
+    /// the resource in memory is written instead, unless the resource is unloaded or set
+    /// not to create its storage if it's missing. If the path of the resource has the extension
+    /// of the old storage format, it's replaced with the extension of the new storage format
+    /// (e.g., `settings.toml` becomes `settings.json`), and the old path is removed.
+    ///
+    /// The storage format and the underlying storage of the resource are only changed
+    /// if both writing and removing succeed. Changes to the resource that are not persisted
+    /// are kept in memory untouched.
+    pub fn change_format(&mut self, format: StorageFormat) -> Result<(), PersistenceError> {
+        if format == self.format {
+            return Ok(());
+        }
+
+        let storage = self
+            .storage
+            .with_replaced_extension(self.format.extension(), format.extension())
+            .unwrap_or_else(|| self.storage.clone());
+
+        let read_resource = if self.storage.occupied() {
+            let resource = self.read(&self.storage, self.format).map_err(|error| {
+                // deserialization errors are logged in format module
+                if !error.is_serde() {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to load {} from {}: {}",
+                        self.name,
+                        self.storage,
+                        error,
+                    );
+                } else {
+                    log_event!(
+                        error,
+                        self,
+                        "failed to load {} from {} due to a deserialization error",
+                        self.name,
+                        self.storage,
+                    );
+                }
+                error
+            })?;
+            Some(resource)
+        } else {
+            None
+        };
+        let converted_resource = match &read_resource {
+            Some(resource) => Some(resource),
+            None if self.create_if_missing => self.resource.as_ref(),
+            None => None,
+        };
+
+        let length = match converted_resource {
+            Some(resource) => {
+                self.hooks.saving(resource);
+                self.initialize_storage(&storage)?;
+                let length =
+                    self.write_to_storage(&storage, format, resource).map_err(|error| {
+                        // serialization errors are logged in format module
+                        if !error.is_serde() {
+                            log_event!(
+                                error,
+                                (self.name, storage, format),
+                                "failed to convert {} to {} in {}: {}",
+                                self.name,
+                                format,
+                                storage,
+                                error,
+                            );
+                        } else {
+                            log_event!(
+                                error,
+                                (self.name, storage, format),
+                                "failed to convert {} to {} in {} due to a serialization error",
+                                self.name,
+                                format,
+                                storage,
+                            );
+                        }
+                        error
+                    })?;
+
+                if storage != self.storage {
+                    if let Err(error) = self.storage.remove() {
+                        log_event!(
+                            error,
+                            self,
+                            "failed to remove the old storage of {} at {}: {}",
+                            self.name,
+                            self.storage,
+                            error,
+                        );
+                        // converted storage is removed to keep the resource in its old storage
+                        if let Err(removal_error) = storage.remove() {
+                            log_event!(
+                                error,
+                                (self.name, storage, format),
+                                "failed to remove the converted storage of {} at {}: {}",
+                                self.name,
+                                storage,
+                                removal_error,
+                            );
+                        }
+                        return Err(error);
+                    }
+                }
+
+                Some(length)
+            },
+            None => None,
+        };
+
+        let old_format = std::mem::replace(&mut self.format, format);
+        self.storage = storage;
+        self.convert_history(old_format);
+        match converted_resource.zip(length) {
+            Some((resource, length)) => {
+                self.remember_written(resource);
+                self.saved(resource, length);
+            },
+            None => self.remember_modification_time(),
+        }
+
+        if !self.quiet {
+            log_event!(
+                info,
+                self,
+                "converted {} from {} to {} in {}",
+                self.name,
+                old_format,
+                self.format,
+                self.storage,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Copies the underlying storage of the resource to another storage as is.
+    ///
+    /// Content of the underlying storage is copied without deserializing it,
+    /// so changes to the resource that are not persisted are not included in the snapshot.
+    pub fn snapshot_to(&self, storage: &Storage) -> Result<(), PersistenceError> {
+        #[cfg(not(target_family = "wasm"))]
+        let copied = self.ensure_dirs(storage).and_then(|_| self.storage.copy_to(storage));
+        #[cfg(target_family = "wasm")]
+        let copied = self.storage.copy_to(storage);
+
+        copied
+            .map(|_| {
+                if !self.quiet {
+                    log_event!(
+                        info,
+                        self,
+                        "snapshotted {} from {} to {}",
+                        self.name,
+                        self.storage,
+                        storage,
+                    );
+                }
+            })
+            .map_err(|error| {
+                log_event!(
+                    error,
+                    self,
+                    "failed to snapshot {} from {} to {}: {}",
+                    self.name,
+                    self.storage,
+                    storage,
+                    error,
+                );
+                error
+            })
+    }
+
+    /// Sets the underlying storage of the resource.
+    ///
+    /// The resource is written to the new storage immediately, so the next time it's persisted
+    /// or reloaded, the new storage is used. If the resource is unloaded, the resource in the
+    /// old storage is moved to the new storage.
+    ///
+    /// If `remove_old_storage` is `true`, the old storage is removed after the resource is
+    /// written to the new storage successfully.
+    pub fn set_storage(
+        &mut self,
+        storage: Storage,
+        remove_old_storage: bool,
+    ) -> Result<(), PersistenceError> {
+        if storage == self.storage {
+            return Ok(());
+        }
+
+        self.initialize_storage(&storage)?;
+
+        let result = match &self.resource {
+            Some(resource) => self.write_to_storage(&storage, self.format, resource).map(|_| None),
+            None if self.storage.occupied() => {
+                self.read(&self.storage, self.format).and_then(|resource| {
+                    self.write_to_storage(&storage, self.format, &resource).map(|_| Some(resource))
+                })
+            },
+            None => Ok(None),
+        };
+        let read_resource = result.map_err(|error| {
+            // serialization errors are logged in format module
+            if !error.is_serde() {
+                log_event!(
+                    error,
+                    self,
+                    "failed to move {} from {} to {}: {}",
+                    self.name,
+                    self.storage,
+                    storage,
+                    error,
+                );
+            } else {
+                log_event!(
+                    error,
+                    self,
+                    "failed to move {} from {} to {} due to a serde error",
+                    self.name,
+                    self.storage,
+                    storage,
+                );
+            }
+            error
+        })?;
+
+        let old_storage = std::mem::replace(&mut self.storage, storage);
+        match self.resource.as_ref().or(read_resource.as_ref()) {
+            Some(resource) => self.remember_written(resource),
+            None => self.remember_modification_time(),
+        }
+
+        if !self.quiet {
+            log_event!(info, self, "moved {} from {} to {}", self.name, old_storage, self.storage);
+        }
+
+        if remove_old_storage {
+            old_storage.remove().map_err(|error| {
+                log_event!(
+                    error,
+                    self,
+                    "failed to remove the old storage of {} at {}: {}",
+                    self.name,
+                    old_storage,
+                    error,
+                );
+                error
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Persistent<R> {
+    /// Runs the load hook on a loaded resource and validates it.
+    ///
+    /// Validation errors are treated as deserialization errors.
+    fn loaded(&self, mut resource: R) -> Result<R, PersistenceError> {
+        self.hooks.loaded(&mut resource).inspect_err(|error| {
+            log_event!(error, self, "failed to validate {}: {}", self.name, error);
+        })?;
+        // loaded resources are merges of all records in append mode
+        #[cfg(not(target_family = "wasm"))]
+        self.holds_record.store(false, std::sync::atomic::Ordering::Relaxed);
+        #[cfg(feature = "json")]
+        self.remember_persisted(&resource);
+        self.remember_in_history(&resource);
+        #[cfg(target_family = "wasm")]
+        self.remember_in_cache(&resource);
+        Ok(resource)
+    }
+
+    /// Makes a copy of a resource by serializing and deserializing it with the storage format.
+    ///
+    /// We need to make copies without using clone, as cloning can have special semantics
+    /// (e.g., cloning `Arc<RwLock<R>>` and changing the clone would change the original).
+    fn reconstruct(&self, resource: &R, what: fmt::Arguments<'_>) -> Result<R, PersistenceError> {
+        let serialized = self
+            .format
+            .serialize_with(&self.name, resource, &self.format_options)
+            .inspect_err(|_| {
+                log_event!(error, self, "failed to clone {} due to a serialization error", what);
+            })?;
+        self.reconstruct_from(&serialized, what)
+    }
+
+    /// Makes a copy of a resource from its serialized form in the storage format.
+    fn reconstruct_from(
+        &self,
+        serialized: &[u8],
+        what: fmt::Arguments<'_>,
+    ) -> Result<R, PersistenceError> {
+        self.format.deserialize_with(&self.name, serialized, &self.format_options).inspect_err(
+            |_| {
+                log_event!(error, self, "failed to clone {} due to a deserialization error", what);
+            },
+        )
+    }
+
+    /// Loads the resource from bytes and persists it.
+    fn import(&mut self, bytes: &[u8], source: &str) -> Result<(), PersistenceError> {
+        match self
+            .format
+            .deserialize_with(&self.name, bytes, &self.format_options)
+            .and_then(|resource| self.loaded(resource))
+        {
+            Ok(resource) => {
+                self.resource = Some(resource);
+            },
+            Err(error) => {
+                // deserialization errors are logged in format module
+                log_event!(
+                    error,
+                    self,
+                    "failed to import {} from {} due to a deserialization error",
+                    self.name,
+                    source,
+                );
+
+                if self.revert_to_default_on_deserialization_errors {
+                    if !self.quiet {
+                        log_event!(
+                            info,
+                            self,
+                            "attempting to revert {} to default in {} automatically",
+                            self.name,
+                            self.storage,
+                        );
+                    }
+                    if let Err(recovery_error) = self.revert_to_default() {
+                        return Err(PersistenceError::RecoveryFailed {
+                            primary: Box::new(error),
+                            during_recovery: Box::new(recovery_error),
+                        });
+                    }
+                    return Ok(());
+                }
+                return Err(error);
+            },
+        }
+        if !self.quiet {
+            log_event!(info, self, "imported {} from {}", self.name, source);
+        }
+        self.persist()
+    }
+
+    /// Writes the resource to the underlying storage.
+    ///
+    /// Number of bytes written to the underlying storage is returned,
+    /// or `None` if writing is skipped as it's redundant.
+    fn write(&self, resource: &R) -> Result<Option<usize>, PersistenceError> {
+        self.write_to_storage(&self.storage, self.format, resource)
+    }
+
+    /// Writes the resource to a storage using a storage format.
+    ///
+    /// Resource is appended to the underlying storage as a record in append mode,
+    /// and the storage is synced afterwards if it's set.
+    ///
+    /// Free space of the underlying device is checked beforehand if it's set.
+    ///
+    /// Writing is skipped if it's redundant and redundant writes are set to be skipped.
+    ///
+    /// Resource is serialized at most once, and it's serialized into the storage directly
+    /// if the serialized resource is not needed beforehand.
+    ///
+    /// Resource is remembered in history regardless of the storage and the storage format,
+    /// but the rest of the change tracking of the resource is updated only if they are
+    /// the ones of the resource.
+    ///
+    /// Number of bytes written to the storage is returned,
+    /// or `None` if writing is skipped as it's redundant.
+    ///
+    /// Filesystem errors are wrapped with the name of the resource and the storage.
+    fn write_to_storage(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+        resource: &R,
+    ) -> Result<Option<usize>, PersistenceError> {
+        self.write_to_storage_without_context(storage, format, resource)
+            .map_err(|error| error.with_context(&self.name, storage))
+    }
+
+    /// Writes the resource to a storage using a storage format without wrapping filesystem errors.
+    fn write_to_storage_without_context(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+        resource: &R,
+    ) -> Result<Option<usize>, PersistenceError> {
+        let options = self.record_options();
+
+        #[cfg(not(target_family = "wasm"))]
+        let length = {
+            self.ensure_dirs(storage)?;
+
+            let write_options = self.write_options(storage, format, resource);
+            if write_options.needs_serialized() {
+                // serialization errors are logged in format module
+                let bytes = format.serialize_with(&self.name, resource, &options)?;
+                storage.write_serialized_with(&bytes, &write_options)?
+            } else {
+                if !self.follow_symlinks {
+                    storage.ensure_not_symlink()?;
+                }
+                let length = storage.write_in(
+                    &self.name,
+                    format,
+                    resource,
+                    &options,
+                    self.temp_dir.as_deref(),
+                    self.follow_symlinks,
+                )?;
+                if self.fsync {
+                    storage.sync()?;
+                }
+                Some(length)
+            }
+        };
+        #[cfg(target_family = "wasm")]
+        let length = {
+            // serialization errors are logged in format module
+            let bytes =
+                crate::storage::serialize_for_browser(&self.name, format, resource, &options)?;
+            if self.skip_redundant_writes
+                && storage.read_raw().is_ok_and(|content| content == bytes)
+            {
+                None
+            } else {
+                Some(storage.write_serialized(format, &bytes)?)
+            }
+        };
+
+        self.remember_in_history(resource);
+        if *storage == self.storage && format == self.format {
+            self.remember_written(resource);
+        }
+        Ok(length)
+    }
+
+    /// Gets the format options to serialize the resource for writing.
+    ///
+    /// Headers are omitted in append mode as they would be read as records.
+    fn record_options(&self) -> Cow<'_, FormatOptions> {
+        #[cfg(not(target_family = "wasm"))]
+        if self.merge.is_some() {
+            return Cow::Owned(FormatOptions { header: None, ..self.format_options.clone() });
+        }
+        Cow::Borrowed(&self.format_options)
+    }
+
+    /// Gets the options to write the resource to a storage using a storage format.
+    #[cfg(not(target_family = "wasm"))]
+    fn write_options(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+        resource: &R,
+    ) -> crate::storage::WriteOptions {
+        // only the resource in memory can be a record (e.g., defaults are written as a whole),
+        // and it's appended only to the underlying storage as other storages are unrelated,
+        // so merges of all records are written as a whole to compact the underlying storage
+        let append = self.merge.is_some()
+            && *storage == self.storage
+            && format == self.format
+            && self.holds_record.load(std::sync::atomic::Ordering::Relaxed)
+            && self.resource.as_ref().is_some_and(|current| std::ptr::eq(current, resource));
+
+        crate::storage::WriteOptions {
+            append,
+            temp_dir: self.temp_dir.clone(),
+            follow_symlinks: self.follow_symlinks,
+            check_free_space: self.check_free_space,
+            skip_redundant_writes: self.skip_redundant_writes,
+            fsync: self.fsync,
+        }
+    }
+
+    /// Reads the resource from a storage.
+    ///
+    /// Records are merged in append mode.
+    ///
+    /// Unknown fields are checked if they are denied and the storage format supports it.
+    ///
+    /// Size of the storage is limited while it's read if there is a limit.
+    ///
+    /// Filesystem errors are wrapped with the name of the resource and the storage.
+    fn read(&self, storage: &Storage, format: StorageFormat) -> Result<R, PersistenceError> {
+        self.read_without_context(storage, format)
+            .map_err(|error| error.with_context(&self.name, storage))
+    }
+
+    /// Reads the resource from a storage without wrapping filesystem errors.
+    fn read_without_context(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+    ) -> Result<R, PersistenceError> {
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(merge) = self.merge {
+            let mut records = storage.read_records_with::<R>(
+                &self.name,
+                format,
+                &self.format_options,
+                self.max_read_bytes,
+            )?;
+            if records.is_empty() {
+                // storage is read as a whole to report the deserialization error
+                return self.read_value(storage, format);
+            }
+
+            let mut resource = records.remove(0);
+            for record in records {
+                merge(&mut resource, record);
+            }
+            return Ok(resource);
+        }
+
+        if self.deny_unknown_fields {
+            #[cfg(feature = "json")]
+            if format.is_json() {
+                return self.read_json_strictly(storage, format);
+            }
+            #[cfg(feature = "toml")]
+            if format.is_toml() {
+                return self.read_toml_strictly(storage, format);
+            }
+        }
+        self.read_value(storage, format)
+    }
+
+    /// Reads a value from a storage.
+    ///
+    /// Size of the storage is limited while it's read if there is a limit.
+    fn read_value<T: Serialize + DeserializeOwned>(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+    ) -> Result<T, PersistenceError> {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let bytes = storage.read_raw_within(self.max_read_bytes)?;
+            format.deserialize_with(&self.name, &bytes, &self.format_options)
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            storage.read_with(&self.name, format, &self.format_options)
+        }
+    }
+
+    /// Reads the resource from a storage in JSON format and denies unknown fields.
+    #[cfg(feature = "json")]
+    fn read_json_strictly(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+    ) -> Result<R, PersistenceError> {
+        let value = self.read_value::<serde_json::Value>(storage, format)?;
+
+        let resource = serde_json::from_value::<R>(value.clone()).map_err(|error| {
+            log_event!(error, self, "failed to parse {} as JSON\n\n{}", self.name, error);
+            PersistenceError::JsonDeserialization(error)
+        })?;
+        let known_value = serde_json::to_value(&resource).map_err(|error| {
+            log_event!(error, self, "failed to serialize {} to JSON\n\n{}", self.name, error);
+            PersistenceError::JsonSerialization(error)
+        })?;
+
+        let mut unknown_fields = Vec::new();
+        unknown_json_fields(&value, &known_value, "", &mut unknown_fields);
+        self.deny(unknown_fields, "JSON")?;
+
+        Ok(resource)
+    }
+
+    /// Reads the resource from a storage in TOML format and denies unknown fields.
+    #[cfg(feature = "toml")]
+    fn read_toml_strictly(
+        &self,
+        storage: &Storage,
+        format: StorageFormat,
+    ) -> Result<R, PersistenceError> {
+        let value = self.read_value::<toml::Value>(storage, format)?;
+
+        let resource = value.clone().try_into::<R>().map_err(|error| {
+            log_event!(error, self, "failed to parse {} as TOML\n\n{}", self.name, error);
+            PersistenceError::TomlDeserialization(error)
+        })?;
+        let known_value = toml::Value::try_from(&resource).map_err(|error| {
+            log_event!(error, self, "failed to serialize {} to TOML\n\n{}", self.name, error);
+            PersistenceError::TomlSerialization(error)
+        })?;
+
+        let mut unknown_fields = Vec::new();
+        unknown_toml_fields(&value, &known_value, "", &mut unknown_fields);
+        self.deny(unknown_fields, "TOML")?;
+
+        Ok(resource)
+    }
+
+    /// Returns an error if there are unknown fields.
+    #[cfg(any(feature = "json", feature = "toml"))]
+    fn deny(&self, unknown_fields: Vec<String>, format: &str) -> Result<(), PersistenceError> {
+        if unknown_fields.is_empty() {
+            return Ok(());
+        }
+        log_event!(
+            error,
+            self,
+            "failed to parse {} as {} due to unknown fields\n\n{}",
+            self.name,
+            format,
+            unknown_fields.join(", "),
+        );
+        Err(PersistenceError::UnknownFields(unknown_fields))
+    }
+
+    /// Initializes a storage for the resource.
+    fn initialize_storage(&self, storage: &Storage) -> Result<(), PersistenceError> {
+        #[cfg(not(target_family = "wasm"))]
+        if !self.create_dirs {
+            return storage.ensure_parent_exists().inspect_err(|error| {
+                log_event!(error, self, "failed to write {} to {}: {}", self.name, storage, error);
+            });
+        }
+
+        storage.initialize().map_err(|error| {
+            // initialize can only return error for filesystem storage
+            log_event!(
+                error,
+                self,
+                "failed to create the parent directory for {} at {}: {}",
+                self.name,
+                storage,
+                error,
+            );
+            error.with_context(&self.name, storage)
+        })
+    }
+
+    /// Ensures the parent directory of a storage exists if missing directories are not created.
+    #[cfg(not(target_family = "wasm"))]
+    fn ensure_dirs(&self, storage: &Storage) -> Result<(), PersistenceError> {
+        if self.create_dirs { Ok(()) } else { storage.ensure_parent_exists() }
+    }
+
+    /// Logs saving the resource to the underlying storage and runs the persisted hooks,
+    /// unless saving is skipped as it's redundant.
+    fn saved(&self, resource: &R, length: Option<usize>) {
+        match length {
+            Some(length) => {
+                if !self.quiet {
+                    log_event!(
+                        info,
+                        self,
+                        "saved new {} to {} ({} bytes)",
+                        self.name,
+                        self.storage,
+                        length,
+                    );
+                }
+                self.hooks.persisted(resource);
+            },
+            None => {
+                if !self.quiet {
+                    log_event!(
+                        info,
+                        self,
+                        "skipped saving {} to {} as it's not changed",
+                        self.name,
+                        self.storage,
+                    );
+                }
+            },
+        }
+    }
+
+    /// Treats the resource as persisted after it's written to the underlying storage
+    /// by a transaction.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn committed(&self, length: usize) {
+        if let Some(resource) = &self.resource {
+            self.remember_in_history(resource);
+            self.remember_written(resource);
+            self.saved(resource, Some(length));
+        }
+    }
+
+    /// Remembers the resource as it's written to the underlying storage.
+    fn remember_written(&self, resource: &R) {
+        self.remember_modification_time();
+        #[cfg(feature = "json")]
+        self.remember_persisted(resource);
+        #[cfg(target_family = "wasm")]
+        self.remember_in_cache(resource);
+    }
+
+    /// Remembers the modification time of the underlying storage.
+    fn remember_modification_time(&self) {
+        if let Ok(mut last_modified) = self.last_modified.lock() {
+            *last_modified = self.storage.modified();
+        }
+    }
+
+    /// Remembers the resource as it's in the underlying storage to reload it from the cache.
+    #[cfg(target_family = "wasm")]
+    fn remember_in_cache(&self, resource: &R) {
+        if let Some(clone) = self.cache {
+            if let Ok(mut cached) = self.cached.lock() {
+                *cached = Some((self.storage.to_string(), self.storage.version(), clone(resource)));
+            }
+        }
+    }
+
+    /// Gets the cached resource if the underlying storage is not changed since it's cached.
+    #[cfg(target_family = "wasm")]
+    fn cached_resource(&self) -> Option<R> {
+        let clone = self.cache?;
+        let cached = self.cached.lock().ok()?;
+        match &*cached {
+            Some((storage, version, resource))
+                if *storage == self.storage.to_string() && *version == self.storage.version() =>
+            {
+                Some(clone(resource))
+            },
+            _ => None,
+        }
+    }
+
+    /// Remembers the resource as it's in the underlying storage to undo changes later.
+    ///
+    /// Resource is remembered in its serialized form, and it's not remembered again
+    /// if it's the same as the last remembered resource.
+    fn remember_in_history(&self, resource: &R) {
+        if self.history_limit == 0 {
+            return;
+        }
+        let Ok(bytes) = self.format.serialize_with(&self.name, resource, &self.format_options)
+        else {
+            return;
+        };
+        if let Ok(mut history) = self.history.lock() {
+            if history.back() == Some(&bytes) {
+                return;
+            }
+            history.push_back(bytes);
+            // current resource is kept in addition to the previous resources
+            while history.len() > self.history_limit + 1 {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Converts the history from a storage format to the storage format of the resource.
+    ///
+    /// Values which cannot be converted are forgotten.
+    fn convert_history(&self, format: StorageFormat) {
+        if let Ok(mut history) = self.history.lock() {
+            let converted = history
+                .drain(..)
+                .filter_map(|bytes| {
+                    format
+                        .deserialize_with::<R>(&self.name, &bytes, &self.format_options)
+                        .and_then(|resource| {
+                            self.format.serialize_with(&self.name, &resource, &self.format_options)
+                        })
+                        .ok()
+                })
+                .collect::<VecDeque<_>>();
+            *history = converted;
+        }
+    }
+
+    /// Remembers the resource as it's in the underlying storage to detect changes later.
+    #[cfg(feature = "json")]
+    fn remember_persisted(&self, resource: &R) {
+        if let Ok(mut last_persisted) = self.last_persisted.lock() {
+            *last_persisted = serde_json::to_value(resource).ok();
+        }
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Deref for Persistent<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.get()
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> DerefMut for Persistent<R> {
+    fn deref_mut(&mut self) -> &mut R {
+        self.get_mut()
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Drop for Persistent<R> {
+    fn drop(&mut self) {
+        if self.persist_on_drop && self.is_loaded() {
+            // errors are logged in persist
+            self.persist().ok();
+        }
+    }
+}
+
+/// Persistent resources can be referenced as their resources.
+///
+/// # Panics
+///
+/// Panics if the resource is unloaded.
+impl<R: Resource + Serialize + DeserializeOwned> AsRef<R> for Persistent<R> {
+    fn as_ref(&self) -> &R {
+        self.get()
+    }
+}
+
+/// Persistent resources can be mutably referenced as their resources.
+///
+/// # Panics
+///
+/// Panics if the resource is unloaded.
+impl<R: Resource + Serialize + DeserializeOwned> AsMut<R> for Persistent<R> {
+    fn as_mut(&mut self) -> &mut R {
+        self.get_mut()
+    }
+}
+
+/// Persistent resources can be borrowed as their resources.
+///
+/// # Panics
+///
+/// Panics if the resource is unloaded.
+impl<R: Resource + Serialize + DeserializeOwned> Borrow<R> for Persistent<R> {
+    fn borrow(&self) -> &R {
+        self.get()
+    }
+}
+
+/// Persistent resources can be mutably borrowed as their resources.
+///
+/// # Panics
+///
+/// Panics if the resource is unloaded.
+impl<R: Resource + Serialize + DeserializeOwned> BorrowMut<R> for Persistent<R> {
+    fn borrow_mut(&mut self) -> &mut R {
+        self.get_mut()
+    }
+}
+
+/// Persistent resources are serialized as their resources (e.g., to embed them in other resources).
+///
+/// Configuration of the persistent resource (e.g., its name or its storage) is not serialized,
+/// and serializing unloaded resources fails. They are deserialized as `UnconfiguredPersistent`.
+impl<R: Resource + Serialize + DeserializeOwned> Serialize for Persistent<R> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.resource {
+            Some(resource) => resource.serialize(serializer),
+            None => {
+                Err(serde::ser::Error::custom(format!("tried to serialize unloaded {}", self.name)))
+            },
+        }
+    }
+}
+
+/// A deserialized persistent resource which is not configured yet.
+///
+/// Configuration of persistent resources is not serialized, so persistent resources
+/// are deserialized as unconfigured persistent resources (e.g., when they're embedded in
+/// other resources), which need to be configured with `UnconfiguredPersistent::configure`
+/// to become persistent resources synchronized with their underlying storages.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnconfiguredPersistent<R: Resource + Serialize + DeserializeOwned> {
+    resource: R,
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> UnconfiguredPersistent<R> {
+    /// Gets the deserialized resource.
+    pub fn get(&self) -> &R {
+        &self.resource
+    }
+
+    /// Gets the deserialized resource, consuming the unconfigured persistent resource.
+    pub fn into_inner(self) -> R {
+        self.resource
+    }
+
+    /// Configures the deserialized persistent resource.
+    ///
+    /// Persistent resource is created from the configuration with the deserialized resource
+    /// as the default, so it's only written to the underlying storage if the underlying storage
+    /// is not occupied. Other options are not carried over as they are not deserialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `revert_to_default_on_deserialization_errors`
+    /// is set to `true` but `revertible` is set to `false`.
+    pub fn configure(self, config: PersistentConfig) -> Result<Persistent<R>, PersistenceError> {
+        Persistent::from_config(config, self.resource)
+    }
+}
+
+/// Unconfigured persistent resources are deserialized from their resources.
+impl<'de, R: Resource + Serialize + DeserializeOwned> serde::Deserialize<'de>
+    for UnconfiguredPersistent<R>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<UnconfiguredPersistent<R>, D::Error> {
+        Ok(UnconfiguredPersistent { resource: R::deserialize(deserializer)? })
+    }
+}
+
+/// Persistent resources are compared by their resources.
+///
+/// Unloaded resources are equal to each other, and they are not equal to loaded resources.
+impl<R: Resource + Serialize + DeserializeOwned + PartialEq> PartialEq for Persistent<R> {
+    fn eq(&self, other: &Persistent<R>) -> bool {
+        self.resource == other.resource
+    }
+}
+
+impl<R: Resource + Serialize + DeserializeOwned + Eq> Eq for Persistent<R> {}
+
+/// Persistent resources are hashed by their resources,
+/// so loaded resources have the same hash as their resources.
+///
+/// Nothing is hashed for unloaded resources.
+impl<R: Resource + Serialize + DeserializeOwned + Hash> Hash for Persistent<R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let Some(resource) = &self.resource {
+            resource.hash(state);
+        }
+    }
+}
+
+/// Merges a JSON value onto another JSON value by merging objects recursively
+/// and replacing other values.
+#[cfg(feature = "json")]
+fn merge_json_values(target: &mut serde_json::Value, source: serde_json::Value) {
+    use serde_json::Value;
+    match (target, source) {
+        (Value::Object(target_object), Value::Object(source_object)) => {
+            for (key, source_value) in source_object {
+                match target_object.get_mut(&key) {
+                    Some(target_value) => merge_json_values(target_value, source_value),
+                    None => {
+                        target_object.insert(key, source_value);
+                    },
+                }
+            }
+        },
+        (target, source) => *target = source,
+    }
+}
+
+/// Collects the JSON pointers to the values which differ between two JSON values.
+#[cfg(feature = "json")]
+fn changed_json_paths(
+    old_value: &serde_json::Value,
+    new_value: &serde_json::Value,
+    path: &str,
+    changed_paths: &mut Vec<String>,
+) {
+    use serde_json::Value;
+    match (old_value, new_value) {
+        (Value::Object(old_object), Value::Object(new_object)) => {
+            let mut keys = old_object.keys().chain(new_object.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                match (old_object.get(key), new_object.get(key)) {
+                    (Some(old_value), Some(new_value)) => {
+                        changed_json_paths(old_value, new_value, &path, changed_paths);
+                    },
+                    _ => changed_paths.push(path),
+                }
+            }
+        },
+        (Value::Array(old_array), Value::Array(new_array)) => {
+            for index in 0..old_array.len().max(new_array.len()) {
+                let path = format!("{}/{}", path, index);
+                match (old_array.get(index), new_array.get(index)) {
+                    (Some(old_value), Some(new_value)) => {
+                        changed_json_paths(old_value, new_value, &path, changed_paths);
+                    },
+                    _ => changed_paths.push(path),
+                }
+            }
+        },
+        (old_value, new_value) => {
+            if old_value != new_value {
+                changed_paths.push(path.to_owned());
+            }
+        },
+    }
+}
+
+/// Collects the paths of the fields in a JSON value which are not in the known JSON value.
+#[cfg(feature = "json")]
+fn unknown_json_fields(
+    value: &serde_json::Value,
+    known_value: &serde_json::Value,
+    path: &str,
+    unknown_fields: &mut Vec<String>,
+) {
+    use serde_json::Value;
+    match (value, known_value) {
+        (Value::Object(object), Value::Object(known_object)) => {
+            for (key, value) in object {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match known_object.get(key) {
+                    Some(known_value) => {
+                        unknown_json_fields(value, known_value, &path, unknown_fields);
+                    },
+                    None => unknown_fields.push(path),
+                }
+            }
+        },
+        (Value::Array(array), Value::Array(known_array)) => {
+            for (index, (value, known_value)) in array.iter().zip(known_array).enumerate() {
+                let path = format!("{}[{}]", path, index);
+                unknown_json_fields(value, known_value, &path, unknown_fields);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Collects the paths of the fields in a TOML value which are not in the known TOML value.
+#[cfg(feature = "toml")]
+fn unknown_toml_fields(
+    value: &toml::Value,
+    known_value: &toml::Value,
+    path: &str,
+    unknown_fields: &mut Vec<String>,
+) {
+    use toml::Value;
+    match (value, known_value) {
+        (Value::Table(table), Value::Table(known_table)) => {
+            for (key, value) in table {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match known_table.get(key) {
+                    Some(known_value) => {
+                        unknown_toml_fields(value, known_value, &path, unknown_fields);
+                    },
+                    None => unknown_fields.push(path),
+                }
+            }
+        },
+        (Value::Array(array), Value::Array(known_array)) => {
+            for (index, (value, known_value)) in array.iter().zip(known_array).enumerate() {
+                let path = format!("{}[{}]", path, index);
+                unknown_toml_fields(value, known_value, &path, unknown_fields);
+            }
+        },
+        _ => {},
     }
 }
@@ -3,6 +3,8 @@
 pub(crate) use crate::{
     builder::PersistentBuilder,
     error::PersistenceError,
+    format::FormatOptions,
+    hooks::Hooks,
     storage::Storage,
 };
 pub(crate) use bevy::{
@@ -14,19 +16,65 @@ pub(crate) use serde::{
     de::DeserializeOwned,
 };
 pub(crate) use std::{
+    borrow::{
+        Borrow,
+        BorrowMut,
+        Cow,
+    },
     fmt::{
         self,
         Display,
     },
+    hash::{
+        Hash,
+        Hasher,
+    },
     ops::{
         Deref,
         DerefMut,
     },
     path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+    time::SystemTime,
 };
 pub(crate) use thiserror::Error;
 
 pub use crate::{
-    format::StorageFormat,
-    persistent::Persistent,
+    app::PersistentAppExt,
+    event::PersistenceFailed,
+    format::{
+        LineEnding,
+        StorageFormat,
+    },
+    group::PersistentGroup,
+    merge::Merge,
+    param::PersistMut,
+    persistent::{
+        Persistent,
+        PersistentConfig,
+        PersistentInfo,
+        UnconfiguredPersistent,
+    },
+    proxy::{
+        Proxied,
+        Proxy,
+    },
+    registry::PersistentRegistry,
+    slots::SaveSlots,
 };
+
+#[cfg(feature = "ini")]
+pub use crate::format::IniOptions;
+
+#[cfg(all(feature = "json", feature = "pretty"))]
+pub use crate::format::JsonIndent;
+
+#[cfg(feature = "protobuf")]
+pub use crate::protobuf::Protobuf;
+
+#[cfg(feature = "toml-edit")]
+pub use crate::format::TomlConfig;
+
+#[cfg(not(target_family = "wasm"))]
+pub use crate::transaction::Transaction;
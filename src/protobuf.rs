@@ -0,0 +1,260 @@
+//! Persistence of protocol buffer messages.
+//!
+//! Protocol buffer messages implement `prost::Message` instead of serde traits, so they cannot
+//! be persisted directly. Instead, they are wrapped in `Protobuf<M>`, which serializes messages
+//! into their protocol buffer encoding as a byte string, and persisted as
+//! `Persistent<Protobuf<M>>` with `StorageFormat::Protobuf`.
+//!
+//! `StorageFormat::Protobuf` writes the encoded bytes as is, so the underlying storage can be
+//! decoded by other protocol buffer implementations (e.g., a backend). For the same reason,
+//! it only supports resources which are serialized as byte strings, and serializing anything
+//! else fails with `PersistenceError::ProtobufSerialization`.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_persistent::prelude::*;
+//! use bevy_persistent::protobuf::Protobuf;
+//!
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! struct Profile {
+//!     #[prost(string, tag = "1")]
+//!     nickname: String,
+//!     #[prost(uint32, tag = "2")]
+//!     level: u32,
+//! }
+//!
+//! let profile = Persistent::<Protobuf<Profile>>::builder()
+//!     .name("profile")
+//!     .format(StorageFormat::Protobuf)
+//!     .path("profile.pb")
+//!     .default(Protobuf(Profile::default()))
+//!     .build()
+//!     .expect("failed to initialize profile");
+//!
+//! println!("level {}", profile.level);
+//! ```
+
+use crate::prelude::*;
+use prost::Message;
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serializer,
+    de::{
+        self,
+        Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{
+        self,
+        Impossible,
+    },
+};
+use std::marker::PhantomData;
+
+/// A protocol buffer message which is persisted in its protocol buffer encoding.
+///
+/// It dereferences to the message, so `Persistent<Protobuf<M>>` can be used mostly as
+/// `Persistent<M>`.
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+pub struct Protobuf<M: Message + Default + 'static>(pub M);
+
+impl<M: Message + Default + 'static> Protobuf<M> {
+    /// Gets the message.
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+}
+
+impl<M: Message + Default + 'static> From<M> for Protobuf<M> {
+    fn from(message: M) -> Protobuf<M> {
+        Protobuf(message)
+    }
+}
+
+impl<M: Message + Default + 'static> Deref for Protobuf<M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.0
+    }
+}
+
+impl<M: Message + Default + 'static> DerefMut for Protobuf<M> {
+    fn deref_mut(&mut self) -> &mut M {
+        &mut self.0
+    }
+}
+
+impl<M: Message + Default + 'static> Serialize for Protobuf<M> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0.encode_to_vec())
+    }
+}
+
+impl<'de, M: Message + Default + 'static> Deserialize<'de> for Protobuf<M> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Protobuf<M>, D::Error> {
+        deserializer.deserialize_bytes(ProtobufVisitor(PhantomData))
+    }
+}
+
+/// A visitor of encoded protocol buffer messages.
+struct ProtobufVisitor<M>(PhantomData<M>);
+
+impl<'de, M: Message + Default + 'static> Visitor<'de> for ProtobufVisitor<M> {
+    type Value = Protobuf<M>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a protocol buffer message")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Protobuf<M>, E> {
+        M::decode(bytes).map(Protobuf).map_err(E::custom)
+    }
+
+    // byte strings are sequences in some storage formats (e.g., JSON)
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Protobuf<M>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+/// An error of protocol buffer storage format.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct Error(String);
+
+impl ser::Error for Error {
+    fn custom<T: Display>(message: T) -> Error {
+        Error(message.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(message: T) -> Error {
+        Error(message.to_string())
+    }
+}
+
+/// Serializes a resource which is serialized as a byte string into the byte string.
+pub(crate) fn to_vec<R: Serialize + ?Sized>(resource: &R) -> Result<Vec<u8>, Error> {
+    resource.serialize(BytesSerializer)
+}
+
+/// Deserializes a resource which is deserialized from a byte string from the byte string.
+pub(crate) fn from_slice<R: DeserializeOwned>(bytes: &[u8]) -> Result<R, Error> {
+    R::deserialize(BytesDeserializer(bytes))
+}
+
+/// A serializer which only accepts byte strings.
+struct BytesSerializer;
+
+macro_rules! unsupported {
+    ($($method:ident($($argument:ty),*) -> $result:ty;)*) => {
+        $(
+            fn $method(self, $(_: $argument),*) -> Result<$result, Error> {
+                Err(unsupported())
+            }
+        )*
+    };
+}
+
+/// Gets the error of serializing anything other than a byte string.
+fn unsupported() -> Error {
+    Error("protobuf storage format only supports resources serialized as bytes".to_owned())
+}
+
+impl Serializer for BytesSerializer {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<Vec<u8>, Error>;
+    type SerializeTuple = Impossible<Vec<u8>, Error>;
+    type SerializeTupleStruct = Impossible<Vec<u8>, Error>;
+    type SerializeTupleVariant = Impossible<Vec<u8>, Error>;
+    type SerializeMap = Impossible<Vec<u8>, Error>;
+    type SerializeStruct = Impossible<Vec<u8>, Error>;
+    type SerializeStructVariant = Impossible<Vec<u8>, Error>;
+
+    fn serialize_bytes(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(bytes.to_vec())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Vec<u8>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Vec<u8>, Error> {
+        Err(unsupported())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Vec<u8>, Error> {
+        Err(unsupported())
+    }
+
+    unsupported! {
+        serialize_bool(bool) -> Vec<u8>;
+        serialize_i8(i8) -> Vec<u8>;
+        serialize_i16(i16) -> Vec<u8>;
+        serialize_i32(i32) -> Vec<u8>;
+        serialize_i64(i64) -> Vec<u8>;
+        serialize_u8(u8) -> Vec<u8>;
+        serialize_u16(u16) -> Vec<u8>;
+        serialize_u32(u32) -> Vec<u8>;
+        serialize_u64(u64) -> Vec<u8>;
+        serialize_f32(f32) -> Vec<u8>;
+        serialize_f64(f64) -> Vec<u8>;
+        serialize_char(char) -> Vec<u8>;
+        serialize_str(&str) -> Vec<u8>;
+        serialize_none() -> Vec<u8>;
+        serialize_unit() -> Vec<u8>;
+        serialize_unit_struct(&'static str) -> Vec<u8>;
+        serialize_unit_variant(&'static str, u32, &'static str) -> Vec<u8>;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize)
+            -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize)
+            -> Self::SerializeStructVariant;
+    }
+}
+
+/// A deserializer which only provides a byte string.
+struct BytesDeserializer<'a>(&'a [u8]);
+
+impl<'de> Deserializer<'de> for BytesDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bytes(self.0)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
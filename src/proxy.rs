@@ -0,0 +1,70 @@
+//! Persistence of resources through serializable proxies.
+
+use crate::prelude::*;
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serializer,
+};
+
+/// A resource that is persisted through a serializable proxy.
+///
+/// It's useful for resources that cannot be serialized directly
+/// (e.g., because they hold handles or runtime state), as the proxy only
+/// needs to contain the parts of the resource which should be persisted.
+pub trait Proxy: Send + Sync + Sized + 'static {
+    /// The serializable proxy of the resource.
+    type Proxy: Serialize + DeserializeOwned;
+
+    /// Converts the resource to its proxy.
+    fn to_proxy(&self) -> Self::Proxy;
+
+    /// Converts a proxy back to the resource.
+    fn from_proxy(proxy: Self::Proxy) -> Self;
+}
+
+/// A resource which is persisted through its proxy.
+///
+/// It dereferences to the resource, so `Persistent<Proxied<R>>` can be used mostly as
+/// `Persistent<R>`, while the proxy of the resource is written to its underlying storage.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Resource)]
+pub struct Proxied<R: Proxy>(pub R);
+
+impl<R: Proxy> Proxied<R> {
+    /// Gets the resource.
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+}
+
+impl<R: Proxy> From<R> for Proxied<R> {
+    fn from(resource: R) -> Proxied<R> {
+        Proxied(resource)
+    }
+}
+
+impl<R: Proxy> Deref for Proxied<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.0
+    }
+}
+
+impl<R: Proxy> DerefMut for Proxied<R> {
+    fn deref_mut(&mut self) -> &mut R {
+        &mut self.0
+    }
+}
+
+impl<R: Proxy> Serialize for Proxied<R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.to_proxy().serialize(serializer)
+    }
+}
+
+impl<'de, R: Proxy> Deserialize<'de> for Proxied<R> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Proxied<R>, D::Error> {
+        R::Proxy::deserialize(deserializer).map(|proxy| Proxied(R::from_proxy(proxy)))
+    }
+}
@@ -0,0 +1,47 @@
+//! A registry of persistent resources.
+
+use crate::prelude::*;
+use std::any::TypeId;
+
+/// A function to get the information of a registered persistent resource from the world.
+type InfoFn = fn(&World) -> Option<PersistentInfo>;
+
+/// A registry of persistent resources in the world
+/// (e.g., to list them with their storages in a debugging UI).
+///
+/// Persistent resources initialized with `PersistentAppExt::init_persistent` are registered
+/// automatically, and others can be registered with `PersistentAppExt::register_persistent`.
+///
+/// Only the configuration of the resources is exposed (see `PersistentInfo`),
+/// as the resources themselves are of different types.
+#[derive(Default, Resource)]
+pub struct PersistentRegistry {
+    entries: Vec<(TypeId, InfoFn)>,
+}
+
+impl PersistentRegistry {
+    /// Registers a persistent resource.
+    ///
+    /// Registering the same persistent resource multiple times has no effect.
+    pub fn register<R: Resource + Serialize + DeserializeOwned>(&mut self) {
+        let type_id = TypeId::of::<Persistent<R>>();
+        if self.entries.iter().any(|(registered_type_id, _)| *registered_type_id == type_id) {
+            return;
+        }
+        self.entries.push((type_id, |world: &World| {
+            world.get_resource::<Persistent<R>>().map(Persistent::info)
+        }));
+    }
+
+    /// Gets the current configuration of the registered persistent resources in the world
+    /// in the order they are registered.
+    ///
+    /// Registered persistent resources which are not in the world are skipped
+    /// (e.g., the ones which are not initialized yet).
+    pub fn registered(world: &World) -> Vec<PersistentInfo> {
+        match world.get_resource::<PersistentRegistry>() {
+            Some(registry) => registry.entries.iter().filter_map(|(_, info)| info(world)).collect(),
+            None => vec![],
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Serde helpers for fields of persistent resources.
+//!
+//! Helpers are used with `#[serde(with = "...")]` attributes, for example:
+//!
+//! ```rust
+//! use bevy::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Resource, Serialize, Deserialize)]
+//! struct Settings {
+//!     #[serde(with = "bevy_persistent::serde_helpers::duration_human")]
+//!     autosave_interval: Duration,
+//! }
+//! ```
+
+/// Serializes durations as the number of seconds (e.g., `1.5`).
+pub mod duration_seconds {
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+        de::Error,
+    };
+    use std::time::Duration;
+
+    /// Serializes a duration as the number of seconds.
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    /// Deserializes a duration from the number of seconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(seconds).map_err(|error| {
+            D::Error::custom(format!("invalid duration of {} seconds: {}", seconds, error))
+        })
+    }
+}
+
+/// Serializes durations as human readable strings (e.g., `1h 30m 5s`).
+///
+/// Supported units are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`,
+/// and components can be separated by whitespace (e.g., `1h30m` and `1h 30m` are the same).
+pub mod duration_human {
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+        de::Error,
+    };
+    use std::time::Duration;
+
+    /// Serializes a duration as a human readable string.
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format(*duration))
+    }
+
+    /// Deserializes a duration from a human readable string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        parse(&string).ok_or_else(|| D::Error::custom(format!("invalid duration {:?}", string)))
+    }
+
+    /// Formats a duration as a human readable string.
+    fn format(duration: Duration) -> String {
+        if duration.is_zero() {
+            return "0s".to_owned();
+        }
+
+        let seconds = duration.as_secs();
+        let nanoseconds = duration.subsec_nanos() as u64;
+
+        let components = [
+            (seconds / 86400, "d"),
+            (seconds / 3600 % 24, "h"),
+            (seconds / 60 % 60, "m"),
+            (seconds % 60, "s"),
+            (nanoseconds / 1_000_000, "ms"),
+            (nanoseconds / 1_000 % 1_000, "us"),
+            (nanoseconds % 1_000, "ns"),
+        ];
+
+        components
+            .iter()
+            .filter(|(amount, _)| *amount != 0)
+            .map(|(amount, unit)| format!("{}{}", amount, unit))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parses a duration from a human readable string.
+    fn parse(string: &str) -> Option<Duration> {
+        let mut duration = Duration::ZERO;
+        let mut remaining = string.trim_start();
+
+        if remaining.is_empty() {
+            return None;
+        }
+        while !remaining.is_empty() {
+            let amount_length = remaining.find(|c: char| !c.is_ascii_digit())?;
+            let amount = remaining[..amount_length].parse::<u64>().ok()?;
+            remaining = &remaining[amount_length..];
+
+            let unit_length =
+                remaining.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(remaining.len());
+            let unit = &remaining[..unit_length];
+            remaining = remaining[unit_length..].trim_start();
+
+            let component = match unit {
+                "d" => Duration::from_secs(amount.checked_mul(86400)?),
+                "h" => Duration::from_secs(amount.checked_mul(3600)?),
+                "m" => Duration::from_secs(amount.checked_mul(60)?),
+                "s" => Duration::from_secs(amount),
+                "ms" => Duration::from_millis(amount),
+                "us" => Duration::from_micros(amount),
+                "ns" => Duration::from_nanos(amount),
+                _ => return None,
+            };
+            duration = duration.checked_add(component)?;
+        }
+
+        Some(duration)
+    }
+}
+
+/// Serializes numbers of bytes as human readable strings (e.g., `512 KiB`).
+///
+/// Sizes are serialized with the largest binary unit that represents them exactly,
+/// and both decimal (e.g., `MB`) and binary (e.g., `MiB`) units are supported
+/// when deserializing, along with fractional amounts (e.g., `1.5 GiB`).
+pub mod byte_size {
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+        de::Error,
+    };
+
+    /// Serializes a number of bytes as a human readable string.
+    pub fn serialize<S: Serializer>(size: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format(*size))
+    }
+
+    /// Deserializes a number of bytes from a human readable string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        parse(&string).ok_or_else(|| D::Error::custom(format!("invalid byte size {:?}", string)))
+    }
+
+    /// Formats a number of bytes as a human readable string.
+    fn format(size: u64) -> String {
+        let units = [("TiB", 1u64 << 40), ("GiB", 1 << 30), ("MiB", 1 << 20), ("KiB", 1 << 10)];
+        for (unit, multiplier) in units {
+            if size >= multiplier && size.is_multiple_of(multiplier) {
+                return format!("{} {}", size / multiplier, unit);
+            }
+        }
+        format!("{} B", size)
+    }
+
+    /// Parses a number of bytes from a human readable string.
+    fn parse(string: &str) -> Option<u64> {
+        let string = string.trim();
+
+        let amount_length =
+            string.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(string.len());
+        let amount = &string[..amount_length];
+        let unit = string[amount_length..].trim();
+
+        let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "kb" => 1_000,
+            "mb" => 1_000_000,
+            "gb" => 1_000_000_000,
+            "tb" => 1_000_000_000_000,
+            "kib" => 1 << 10,
+            "mib" => 1 << 20,
+            "gib" => 1 << 30,
+            "tib" => 1 << 40,
+            _ => return None,
+        };
+
+        if let Ok(amount) = amount.parse::<u64>() {
+            return amount.checked_mul(multiplier);
+        }
+
+        let amount = amount.parse::<f64>().ok()?;
+        let size = (amount * multiplier as f64).round();
+        if !size.is_finite() || size < 0.0 || size > u64::MAX as f64 {
+            return None;
+        }
+        Some(size as u64)
+    }
+}
@@ -0,0 +1,146 @@
+//! Save slots of a persistent resource.
+
+use crate::prelude::*;
+use std::marker::PhantomData;
+
+#[cfg(not(target_family = "wasm"))]
+use std::collections::BTreeMap;
+
+/// Save slots of a persistent resource.
+///
+/// Each slot is stored in the same directory with the same storage format,
+/// and the path of a slot is derived from its index (e.g., `saves/slot-2.bin`).
+#[derive(Debug)]
+pub struct SaveSlots<R: Resource + Serialize + DeserializeOwned> {
+    name: String,
+    format: StorageFormat,
+    directory: PathBuf,
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> SaveSlots<R> {
+    /// Creates save slots.
+    pub fn new(
+        name: impl ToString,
+        format: StorageFormat,
+        directory: impl Into<PathBuf>,
+    ) -> SaveSlots<R> {
+        SaveSlots {
+            name: name.to_string(),
+            format,
+            directory: directory.into(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Gets the name of the save slots.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the storage format of the save slots.
+    pub fn format(&self) -> StorageFormat {
+        self.format
+    }
+
+    /// Gets the directory of the save slots.
+    pub fn directory(&self) -> &PathBuf {
+        &self.directory
+    }
+
+    /// Gets the path of a slot.
+    pub fn path(&self, index: usize) -> PathBuf {
+        self.directory.join(format!("slot-{}.{}", index, self.format.extension()))
+    }
+
+    /// Creates a persistent resource builder for a slot.
+    ///
+    /// Name, format and path of the builder are set, so only the default
+    /// (and any other option) needs to be set before building the slot.
+    pub fn builder(&self, index: usize) -> PersistentBuilder<R> {
+        Persistent::<R>::builder()
+            .name(format!("{} slot {}", self.name, index))
+            .format(self.format)
+            .path(self.path(index))
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl<R: Resource + Serialize + DeserializeOwned> SaveSlots<R> {
+    /// Gets the indices of occupied slots in ascending order.
+    ///
+    /// Slots are detected by scanning the directory of the save slots,
+    /// so they are not loaded. If the directory doesn't exist, no slots are occupied.
+    pub fn occupied(&self) -> Result<Vec<usize>, PersistenceError> {
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let suffix = format!(".{}", self.format.extension());
+
+        let mut indices = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.path().is_file() {
+                continue;
+            }
+            let file_name = entry.file_name();
+            let index = file_name
+                .to_str()
+                .and_then(|file_name| file_name.strip_prefix("slot-"))
+                .and_then(|file_name| file_name.strip_suffix(&suffix))
+                .and_then(|index| index.parse::<usize>().ok());
+            if let Some(index) = index {
+                indices.push(index);
+            }
+        }
+        indices.sort_unstable();
+
+        Ok(indices)
+    }
+
+    /// Loads all occupied slots (e.g., to preview them in a "continue" menu).
+    ///
+    /// Slots which cannot be loaded (e.g., because they are corrupt) are skipped with a warning,
+    /// use `load_all_with_errors` to get their errors instead. Slots are loaded directly from
+    /// their storages with the default format options, so nothing is written.
+    pub fn load_all(&self) -> Result<BTreeMap<usize, R>, PersistenceError> {
+        let (slots, errors) = self.load_all_with_errors()?;
+        for (index, error) in errors {
+            log::warn!(
+                "skipped slot {} of {} as it couldn't be loaded: {}",
+                index,
+                self.name,
+                error
+            );
+        }
+        Ok(slots)
+    }
+
+    /// Loads all occupied slots and gets the errors of slots which cannot be loaded separately.
+    ///
+    /// Only failing to scan the directory of the save slots is an error,
+    /// so a corrupt slot doesn't prevent loading the other slots.
+    #[allow(clippy::type_complexity)]
+    pub fn load_all_with_errors(
+        &self,
+    ) -> Result<(BTreeMap<usize, R>, BTreeMap<usize, PersistenceError>), PersistenceError> {
+        let mut slots = BTreeMap::new();
+        let mut errors = BTreeMap::new();
+        for index in self.occupied()? {
+            let name = format!("{} slot {}", self.name, index);
+            let storage = Storage::Filesystem { path: self.path(index) };
+            match storage.read::<R>(&name, self.format) {
+                Ok(slot) => {
+                    slots.insert(index, slot);
+                },
+                Err(error) => {
+                    errors.insert(index, error);
+                },
+            }
+        }
+        Ok((slots, errors))
+    }
+}
@@ -1,6 +1,8 @@
 //! A storage.
 
 use crate::prelude::*;
+#[cfg(target_family = "wasm")]
+use std::collections::BTreeMap;
 
 /// A storage.
 #[derive(Clone, Debug, Eq, PartialEq, Reflect)]
@@ -13,6 +15,59 @@ pub enum Storage {
     SessionStorage { key: String },
 }
 
+/// Versions of browser storages, which are bumped when they are written or removed
+/// (e.g., to detect if cached resources are stale).
+#[cfg(target_family = "wasm")]
+static VERSIONS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// Options of writing serialized resources to storages.
+#[cfg(not(target_family = "wasm"))]
+#[derive(Clone, Debug)]
+pub(crate) struct WriteOptions {
+    /// Whether the resource is appended to the storage as a record.
+    pub(crate) append: bool,
+    /// Directory to create temporary files in.
+    pub(crate) temp_dir: Option<PathBuf>,
+    /// Whether the storage is written through symbolic links.
+    pub(crate) follow_symlinks: bool,
+    /// Whether free space of the underlying device is checked beforehand.
+    pub(crate) check_free_space: bool,
+    /// Whether writing is skipped if the storage already has the same content.
+    pub(crate) skip_redundant_writes: bool,
+    /// Whether the storage is synced afterwards.
+    pub(crate) fsync: bool,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl WriteOptions {
+    /// Gets whether resources need to be serialized into bytes before writing them.
+    pub(crate) fn needs_serialized(&self) -> bool {
+        self.append || self.check_free_space || self.skip_redundant_writes
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl Storage {
+    /// Gets the version of the storage.
+    ///
+    /// Only changes made by this crate are tracked,
+    /// so changes made externally (e.g., by JavaScript) don't bump the version.
+    pub(crate) fn version(&self) -> u64 {
+        VERSIONS
+            .lock()
+            .ok()
+            .and_then(|versions| versions.get(&self.to_string()).copied())
+            .unwrap_or(0)
+    }
+
+    /// Bumps the version of the storage.
+    pub(crate) fn bump_version(&self) {
+        if let Ok(mut versions) = VERSIONS.lock() {
+            *versions.entry(self.to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
 impl Storage {
     /// Initializes the storage.
     pub fn initialize(&self) -> Result<(), PersistenceError> {
@@ -31,6 +86,18 @@ impl Storage {
         Ok(())
     }
 
+    /// Ensures the parent directory of the storage exists without creating it.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn ensure_parent_exists(&self) -> Result<(), PersistenceError> {
+        let Storage::Filesystem { path } = self;
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.is_dir() {
+                return Err(PersistenceError::MissingDirectory(parent.to_path_buf()));
+            }
+        }
+        Ok(())
+    }
+
     /// Gets if the storage is occupied.
     pub fn occupied(&self) -> bool {
         match self {
@@ -55,17 +122,252 @@ impl Storage {
         }
     }
 
+    /// Gets the modification time of the storage.
+    ///
+    /// Browser storages don't have modification times, so `None` is returned for them.
+    pub fn modified(&self) -> Option<SystemTime> {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { path } => {
+                std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { .. } => None,
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { .. } => None,
+        }
+    }
+
+    /// Removes the storage.
+    ///
+    /// Removing an unoccupied storage is not an error.
+    pub fn remove(&self) -> Result<(), PersistenceError> {
+        #[cfg(target_family = "wasm")]
+        self.bump_version();
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { path } => {
+                if let Err(error) = std::fs::remove_file(path) {
+                    if error.kind() != std::io::ErrorKind::NotFound {
+                        return Err(error.into());
+                    }
+                }
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { key } => {
+                use gloo_storage::{
+                    LocalStorage,
+                    Storage,
+                };
+                LocalStorage::delete(key);
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { key } => {
+                use gloo_storage::{
+                    SessionStorage,
+                    Storage,
+                };
+                SessionStorage::delete(key);
+            },
+        }
+        Ok(())
+    }
+
+    /// Copies the content of the storage to another storage as is.
+    ///
+    /// Content is not deserialized and serialized again, so it's preserved exactly
+    /// (e.g., comments in hand-edited files are kept).
+    pub fn copy_to(&self, destination: &Storage) -> Result<(), PersistenceError> {
+        destination.initialize()?;
+        #[cfg(target_family = "wasm")]
+        destination.bump_version();
+        match (self, destination) {
+            #[cfg(not(target_family = "wasm"))]
+            (
+                Storage::Filesystem { path: source_path },
+                Storage::Filesystem { path: destination_path },
+            ) => {
+                std::fs::copy(source_path, destination_path)?;
+            },
+            #[cfg(target_family = "wasm")]
+            (source, destination) => {
+                use gloo_storage::{
+                    LocalStorage,
+                    SessionStorage,
+                    Storage,
+                    errors::StorageError,
+                };
+
+                let (source_storage, source_key) = match source {
+                    self::Storage::LocalStorage { key } => (LocalStorage::raw(), key),
+                    self::Storage::SessionStorage { key } => (SessionStorage::raw(), key),
+                };
+                let (destination_storage, destination_key) = match destination {
+                    self::Storage::LocalStorage { key } => (LocalStorage::raw(), key),
+                    self::Storage::SessionStorage { key } => (SessionStorage::raw(), key),
+                };
+
+                let content = source_storage
+                    .get_item(source_key)
+                    .ok()
+                    .flatten()
+                    .ok_or_else(|| StorageError::KeyNotFound(source_key.to_owned()))?;
+
+                destination_storage.set_item(destination_key, &content).map_err(|error| {
+                    // browsers only throw errors while setting items (e.g., when quota exceeded)
+                    StorageError::JsError(
+                        gloo_utils::errors::JsError::try_from(error)
+                            .expect("browser storage threw a non-error value"),
+                    )
+                })?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Reads the content of the storage as is.
+    ///
+    /// Browser storages store strings, so contents which are JSON arrays of bytes
+    /// (i.e., how binary storage formats are stored) are read as the bytes,
+    /// and other contents are read as UTF-8 bytes.
+    pub fn read_raw(&self) -> Result<Vec<u8>, PersistenceError> {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { path } => Ok(std::fs::read(path)?),
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { key } => {
+                read_raw_from_browser::<gloo_storage::LocalStorage>(key)
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { key } => {
+                read_raw_from_browser::<gloo_storage::SessionStorage>(key)
+            },
+        }
+    }
+
+    /// Reads the last bytes of the storage as is (e.g., to read recent records of a large storage
+    /// in append mode without reading all of it).
+    ///
+    /// Whole content is read if it's not larger than the number of bytes.
+    /// Browser storages cannot be read partially, so they are read as a whole and sliced.
+    pub fn read_tail(&self, bytes: usize) -> Result<Vec<u8>, PersistenceError> {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { path } => {
+                use std::io::{
+                    Read,
+                    Seek,
+                    SeekFrom,
+                };
+
+                let mut file = std::fs::File::open(path)?;
+                let length = file.metadata()?.len();
+                let start = length.saturating_sub(bytes as u64);
+                file.seek(SeekFrom::Start(start))?;
+
+                let mut tail = Vec::with_capacity((length - start) as usize);
+                file.read_to_end(&mut tail)?;
+
+                Ok(tail)
+            },
+            #[cfg(target_family = "wasm")]
+            _ => {
+                let mut content = self.read_raw()?;
+                Ok(content.split_off(content.len().saturating_sub(bytes)))
+            },
+        }
+    }
+
+    /// Writes bytes to the storage as is.
+    ///
+    /// Files are written through temporary files, so they are never left half written.
+    /// Browser storages store strings, so bytes which are not valid UTF-8 are stored
+    /// as JSON arrays of bytes (i.e., how binary storage formats are stored).
+    pub fn write_raw(&self, bytes: &[u8]) -> Result<(), PersistenceError> {
+        self.initialize()?;
+        #[cfg(target_family = "wasm")]
+        self.bump_version();
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { .. } => {
+                self.write_serialized(bytes, None, true)?;
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { key } => {
+                write_raw_to_browser::<gloo_storage::LocalStorage>(key, bytes)?;
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { key } => {
+                write_raw_to_browser::<gloo_storage::SessionStorage>(key, bytes)?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Gets the storage with its key prefixed.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn with_key_prefix(self, prefix: &str) -> Storage {
+        match self {
+            Storage::LocalStorage { key } => {
+                Storage::LocalStorage { key: format!("{}::{}", prefix, key) }
+            },
+            Storage::SessionStorage { key } => {
+                Storage::SessionStorage { key: format!("{}::{}", prefix, key) }
+            },
+        }
+    }
+
+    /// Gets the storage with the extension of its path replaced,
+    /// if its path has the specified extension.
+    ///
+    /// Keys of browser storages are treated as paths.
+    pub(crate) fn with_replaced_extension(&self, from: &str, to: &str) -> Option<Storage> {
+        let replace = |path: &std::path::Path| {
+            if path.extension().and_then(|extension| extension.to_str()) == Some(from) {
+                Some(path.with_extension(to))
+            } else {
+                None
+            }
+        };
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Storage::Filesystem { path } => replace(path).map(|path| Storage::Filesystem { path }),
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { key } => {
+                replace(std::path::Path::new(key)).and_then(|key| {
+                    key.to_str().map(|key| Storage::LocalStorage { key: key.to_owned() })
+                })
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { key } => {
+                replace(std::path::Path::new(key)).and_then(|key| {
+                    key.to_str().map(|key| Storage::SessionStorage { key: key.to_owned() })
+                })
+            },
+        }
+    }
+
     /// Reads a resource from the storage.
     pub fn read<R: Serialize + DeserializeOwned>(
         &self,
         name: &str,
         format: StorageFormat,
+    ) -> Result<R, PersistenceError> {
+        self.read_with(name, format, &FormatOptions::default())
+    }
+
+    /// Reads a resource from the storage with format options.
+    pub(crate) fn read_with<R: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        format: StorageFormat,
+        options: &FormatOptions,
     ) -> Result<R, PersistenceError> {
         match self {
             #[cfg(not(target_family = "wasm"))]
             Storage::Filesystem { path } => {
                 let bytes = std::fs::read(path)?;
-                format.deserialize::<R>(name, &bytes)
+                format.deserialize_with::<R>(name, &bytes, options)
             },
             #[cfg(target_family = "wasm")]
             Storage::LocalStorage { key } => {
@@ -79,35 +381,58 @@ impl Storage {
                 if format == StorageFormat::Json {
                     return Ok(LocalStorage::get::<R>(key).inspect_err(|error| {
                         if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to parse {} as JSON\n\n{}", name, error);
+                            log_event!(
+                                error,
+                                (name, self, format),
+                                "failed to parse {} as JSON\n\n{}",
+                                name,
+                                error
+                            );
                         }
                     })?);
                 }
                 #[cfg(all(feature = "json", feature = "pretty"))]
-                if format == StorageFormat::JsonPretty {
+                if matches!(format, StorageFormat::JsonPretty | StorageFormat::JsonPrettyWith(_)) {
                     return Ok(LocalStorage::get::<R>(key).inspect_err(|error| {
                         if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to parse {} as pretty JSON\n\n{}", name, error);
+                            log_event!(
+                                error,
+                                (name, self, format),
+                                "failed to parse {} as pretty JSON\n\n{}",
+                                name,
+                                error
+                            );
                         }
                     })?);
                 }
 
-                #[cfg(feature = "bincode")]
-                if format == StorageFormat::Bincode {
+                if format.is_binary() {
                     let bytes = LocalStorage::get::<Vec<u8>>(key).inspect_err(|error| {
                         if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to get {} as a byte array\n\n{}", name, error);
+                            log_event!(
+                                error,
+                                (name, self, format),
+                                "failed to get {} as a byte array\n\n{}",
+                                name,
+                                error
+                            );
                         }
                     })?;
-                    return format.deserialize::<R>(name, &bytes);
+                    return format.deserialize_with::<R>(name, &bytes, options);
                 }
 
                 let content = LocalStorage::get::<String>(key).inspect_err(|error| {
                     if let StorageError::SerdeError(error) = &error {
-                        log::error!("failed to get {} as a string\n\n{}", name, error);
+                        log_event!(
+                            error,
+                            (name, self, format),
+                            "failed to get {} as a string\n\n{}",
+                            name,
+                            error
+                        );
                     }
                 })?;
-                format.deserialize::<R>(name, content.as_bytes())
+                format.deserialize_with::<R>(name, content.as_bytes(), options)
             },
             #[cfg(target_family = "wasm")]
             Storage::SessionStorage { key } => {
@@ -121,145 +446,410 @@ impl Storage {
                 if format == StorageFormat::Json {
                     return Ok(SessionStorage::get::<R>(key).inspect_err(|error| {
                         if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to parse {} as JSON\n\n{}", name, error);
+                            log_event!(
+                                error,
+                                (name, self, format),
+                                "failed to parse {} as JSON\n\n{}",
+                                name,
+                                error
+                            );
                         }
                     })?);
                 }
                 #[cfg(all(feature = "json", feature = "pretty"))]
-                if format == StorageFormat::JsonPretty {
+                if matches!(format, StorageFormat::JsonPretty | StorageFormat::JsonPrettyWith(_)) {
                     return Ok(SessionStorage::get::<R>(key).inspect_err(|error| {
                         if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to parse {} as pretty JSON\n\n{}", name, error);
+                            log_event!(
+                                error,
+                                (name, self, format),
+                                "failed to parse {} as pretty JSON\n\n{}",
+                                name,
+                                error
+                            );
                         }
                     })?);
                 }
 
-                #[cfg(feature = "bincode")]
-                if format == StorageFormat::Bincode {
+                if format.is_binary() {
                     let bytes = SessionStorage::get::<Vec<u8>>(key).inspect_err(|error| {
                         if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to get {} as a byte array\n\n{}", name, error);
+                            log_event!(
+                                error,
+                                (name, self, format),
+                                "failed to get {} as a byte array\n\n{}",
+                                name,
+                                error
+                            );
                         }
                     })?;
-                    return format.deserialize::<R>(name, &bytes);
+                    return format.deserialize_with::<R>(name, &bytes, options);
                 }
 
                 let content = SessionStorage::get::<String>(key).inspect_err(|error| {
                     if let StorageError::SerdeError(error) = &error {
-                        log::error!("failed to get {} as a string\n\n{}", name, error);
+                        log_event!(
+                            error,
+                            (name, self, format),
+                            "failed to get {} as a string\n\n{}",
+                            name,
+                            error
+                        );
                     }
                 })?;
-                format.deserialize::<R>(name, content.as_bytes())
+                format.deserialize_with::<R>(name, content.as_bytes(), options)
             },
         }
     }
 
-    /// Writes a resource to the storage.
-    pub fn write<R: Serialize + DeserializeOwned>(
-        &self,
-        name: &str,
-        format: StorageFormat,
-        resource: &R,
-    ) -> Result<(), PersistenceError> {
+    /// Flushes the storage to the underlying device.
+    ///
+    /// Browser storages are managed by browsers, so nothing is done for them.
+    pub fn sync(&self) -> Result<(), PersistenceError> {
         match self {
             #[cfg(not(target_family = "wasm"))]
             Storage::Filesystem { path } => {
-                let bytes = format.serialize(name, resource)?;
-
-                use std::io::Write;
-                std::fs::OpenOptions::new()
-                    .create(true)
-                    .truncate(true)
-                    .write(true)
-                    .open(path)
-                    .and_then(|mut file| file.write_all(&bytes))?;
+                std::fs::OpenOptions::new().write(true).open(path)?.sync_all()?;
             },
             #[cfg(target_family = "wasm")]
-            Storage::LocalStorage { key } => {
-                use gloo_storage::{
-                    LocalStorage,
-                    Storage,
-                    errors::StorageError,
+            Storage::LocalStorage { .. } => {},
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { .. } => {},
+        }
+        Ok(())
+    }
+
+    /// Ensures the underlying device has enough free space to write bytes to the storage.
+    ///
+    /// Files are written through temporary files, which are written completely before replacing
+    /// the existing content, so existing content of the storage is not considered free.
+    /// If the directory of temporary files is set, the device which holds it is checked as well.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn ensure_free_space(
+        &self,
+        length: u64,
+        append: bool,
+        temp_dir: Option<&std::path::Path>,
+    ) -> Result<(), PersistenceError> {
+        match self {
+            Storage::Filesystem { path } => {
+                let directory = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent,
+                    _ => std::path::Path::new("."),
                 };
 
-                #[cfg(feature = "json")]
-                if format == StorageFormat::Json {
-                    LocalStorage::set::<&R>(key, resource).inspect_err(|error| {
-                        if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to serialize {} to JSON\n\n{}", name, error);
-                        }
-                    })?;
-                    return Ok(());
-                }
-                #[cfg(all(feature = "json", feature = "pretty"))]
-                if format == StorageFormat::JsonPretty {
-                    LocalStorage::set::<&R>(key, resource).inspect_err(|error| {
-                        if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
-                        }
-                    })?;
-                    return Ok(());
-                }
+                let (needed, directories) = if append {
+                    // records are prefixed with a newline and appended in place
+                    (length + 1, vec![directory])
+                } else {
+                    // temporary files are copied next to the file if they are on another device
+                    (length, temp_dir.into_iter().chain([directory]).collect())
+                };
 
-                #[cfg(feature = "bincode")]
-                if format == StorageFormat::Bincode {
-                    let bytes = format.serialize(name, resource)?;
-                    LocalStorage::set::<&[u8]>(key, &bytes)?;
-                    return Ok(());
+                for directory in directories {
+                    let available = fs2::available_space(directory)?;
+                    if needed > available {
+                        return Err(PersistenceError::InsufficientSpace { needed, available });
+                    }
                 }
+            },
+        }
+        Ok(())
+    }
 
-                let bytes = format.serialize(name, resource)?;
+    /// Ensures the storage is not a symbolic link.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn ensure_not_symlink(&self) -> Result<(), PersistenceError> {
+        let Storage::Filesystem { path } = self;
+        let is_symlink = std::fs::symlink_metadata(path)
+            .map(|metadata| metadata.file_type().is_symlink())
+            .unwrap_or(false);
+        if is_symlink {
+            return Err(PersistenceError::Symlink(path.clone()));
+        }
+        Ok(())
+    }
 
-                // unwrapping is okay in this case because
-                // remaining storage formats all return a string
-                // and that string is converted to bytes
-                let string = std::str::from_utf8(&bytes).unwrap();
-                LocalStorage::set::<&str>(key, string)?;
-            },
-            #[cfg(target_family = "wasm")]
-            Storage::SessionStorage { key } => {
-                use gloo_storage::{
-                    SessionStorage,
-                    Storage,
-                    errors::StorageError,
+    /// Reads the content of the storage as is if it's not larger than the limit in bytes.
+    ///
+    /// The limit is enforced while reading, so a storage which grows after it's opened
+    /// cannot be read beyond the limit. Whole content is read if there is no limit.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn read_raw_within(&self, limit: Option<u64>) -> Result<Vec<u8>, PersistenceError> {
+        match self {
+            Storage::Filesystem { path } => {
+                let Some(limit) = limit else {
+                    return Ok(std::fs::read(path)?);
                 };
 
-                #[cfg(feature = "json")]
-                if format == StorageFormat::Json {
-                    SessionStorage::set::<&R>(key, resource).inspect_err(|error| {
-                        if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to serialize {} to JSON\n\n{}", name, error);
-                        }
-                    })?;
-                    return Ok(());
-                }
-                #[cfg(all(feature = "json", feature = "pretty"))]
-                if format == StorageFormat::JsonPretty {
-                    SessionStorage::set::<&R>(key, resource).inspect_err(|error| {
-                        if let StorageError::SerdeError(error) = &error {
-                            log::error!("failed to serialize {} to pretty JSON\n\n{}", name, error);
-                        }
-                    })?;
-                    return Ok(());
-                }
+                use std::io::Read;
+                let file = std::fs::File::open(path)?;
+
+                // a single byte more than the limit is read to detect exceeding it
+                let mut content = Vec::new();
+                (&file).take(limit.saturating_add(1)).read_to_end(&mut content)?;
 
-                #[cfg(feature = "bincode")]
-                if format == StorageFormat::Bincode {
-                    let bytes = format.serialize(name, resource)?;
-                    SessionStorage::set::<&[u8]>(key, &bytes)?;
-                    return Ok(());
+                let read = content.len() as u64;
+                if read > limit {
+                    let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(read);
+                    return Err(PersistenceError::TooLarge { size: size.max(read), limit });
                 }
+                Ok(content)
+            },
+        }
+    }
+
+    /// Gets the command which reveals the storage in the file manager of the platform.
+    ///
+    /// It selects the file in Explorer on Windows (`explorer /select,<path>`) and in Finder
+    /// on macOS (`open -R <path>`), and it opens the parent directory with `xdg-open`
+    /// on other desktop platforms, since there is no standard way of selecting files in them.
+    ///
+    /// Fails with `PersistenceError::FileManagerUnavailable` on other platforms.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn reveal_command(&self) -> Result<std::process::Command, PersistenceError> {
+        #[cfg(target_os = "windows")]
+        {
+            let Storage::Filesystem { path } = self;
+
+            // explorer expects the path to be in the same argument with the flag
+            let mut argument = std::ffi::OsString::from("/select,");
+            argument.push(path);
+
+            let mut command = std::process::Command::new("explorer");
+            command.arg(argument);
+            Ok(command)
+        }
+        #[cfg(target_os = "macos")]
+        {
+            let Storage::Filesystem { path } = self;
+
+            let mut command = std::process::Command::new("open");
+            command.arg("-R").arg(path);
+            Ok(command)
+        }
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        {
+            let Storage::Filesystem { path } = self;
+            let parent = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => std::path::Path::new("."),
+            };
+
+            let mut command = std::process::Command::new("xdg-open");
+            command.arg(parent);
+            Ok(command)
+        }
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))),
+        )))]
+        {
+            Err(PersistenceError::FileManagerUnavailable)
+        }
+    }
 
-                let bytes = format.serialize(name, resource)?;
+    /// Appends a serialized resource to the storage as a newline-delimited record
+    /// and gets the number of bytes appended.
+    ///
+    /// If symbolic links are not followed, the file which is opened is checked to be
+    /// the storage itself rather than the target of a symbolic link before appending to it.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn append_serialized(
+        &self,
+        bytes: &[u8],
+        follow_symlinks: bool,
+    ) -> Result<usize, PersistenceError> {
+        match self {
+            Storage::Filesystem { path } => {
+                let mut record = Vec::with_capacity(bytes.len() + 1);
+                record.push(b'\n');
+                record.extend_from_slice(bytes);
 
-                // unwrapping is okay in this case because
-                // remaining storage formats all return a string
-                // and that string is converted to bytes
-                let string = std::str::from_utf8(&bytes).unwrap();
-                SessionStorage::set::<&str>(key, string)?;
+                use std::io::Write;
+                open_for_appending(path, follow_symlinks)?.write_all(&record)?;
+
+                Ok(record.len())
+            },
+        }
+    }
+
+    /// Reads newline-delimited records from the storage
+    /// if it's not larger than the limit in bytes.
+    ///
+    /// Blank lines are skipped.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn read_records_with<R: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        format: StorageFormat,
+        options: &FormatOptions,
+        limit: Option<u64>,
+    ) -> Result<Vec<R>, PersistenceError> {
+        self.read_raw_within(limit)?
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .map(|line| format.deserialize_with::<R>(name, line, options))
+            .collect()
+    }
+
+    /// Writes a resource to the storage and gets the number of bytes written.
+    ///
+    /// Browser storages store resources as strings, so the number of serialized bytes
+    /// is returned for them.
+    ///
+    /// Files are written through a temporary file next to the file, which is renamed
+    /// to the file afterwards, so errors don't leave the file half written.
+    ///
+    /// In Bincode, JSON and YAML storage formats, resources are serialized into files directly
+    /// (i.e., without serializing them into bytes in memory first).
+    pub fn write<R: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        format: StorageFormat,
+        resource: &R,
+    ) -> Result<usize, PersistenceError> {
+        self.write_with(name, format, resource, &FormatOptions::default())
+    }
+
+    /// Writes a resource to the storage with format options
+    /// and gets the number of bytes written.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn write_with<R: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        format: StorageFormat,
+        resource: &R,
+        options: &FormatOptions,
+    ) -> Result<usize, PersistenceError> {
+        self.write_in(name, format, resource, options, None, true)
+    }
+
+    /// Writes a resource to the storage with format options through a temporary file
+    /// in a directory and gets the number of bytes written.
+    ///
+    /// Temporary file is created next to the file if the directory is not set.
+    ///
+    /// If symbolic links are not followed and the storage is a symbolic link,
+    /// the symbolic link itself is replaced rather than its target.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn write_in<R: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        format: StorageFormat,
+        resource: &R,
+        options: &FormatOptions,
+        temp_dir: Option<&std::path::Path>,
+        follow_symlinks: bool,
+    ) -> Result<usize, PersistenceError> {
+        let Storage::Filesystem { path } = self;
+
+        if format.is_streamable() {
+            return write_atomically(path, temp_dir, follow_symlinks, |file| {
+                format.serialize_into(name, resource, options, std::io::BufWriter::new(file))
+            });
+        }
+
+        let bytes = format.serialize_with(name, resource, options)?;
+        self.write_serialized(&bytes, temp_dir, follow_symlinks)
+    }
+
+    /// Writes a serialized resource to the storage with write options
+    /// and gets the number of bytes written, or `None` if writing is skipped as it's redundant.
+    ///
+    /// Redundant writes are not skipped when the resource is appended as a record.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn write_serialized_with(
+        &self,
+        bytes: &[u8],
+        options: &WriteOptions,
+    ) -> Result<Option<usize>, PersistenceError> {
+        if !options.follow_symlinks {
+            self.ensure_not_symlink()?;
+        }
+        if options.check_free_space {
+            self.ensure_free_space(
+                bytes.len() as u64,
+                options.append,
+                options.temp_dir.as_deref(),
+            )?;
+        }
+
+        let length = if options.append {
+            self.append_serialized(bytes, options.follow_symlinks)?
+        } else {
+            // storages larger than the serialized resource cannot have the same content
+            let limit = Some(bytes.len() as u64);
+            if options.skip_redundant_writes
+                && self.read_raw_within(limit).is_ok_and(|content| content == bytes)
+            {
+                return Ok(None);
+            }
+            self.write_serialized(bytes, options.temp_dir.as_deref(), options.follow_symlinks)?
+        };
+
+        if options.fsync {
+            self.sync()?;
+        }
+        Ok(Some(length))
+    }
+
+    /// Writes a serialized resource to the storage through a temporary file in a directory
+    /// and gets the number of bytes written.
+    ///
+    /// Temporary file is created next to the file if the directory is not set.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn write_serialized(
+        &self,
+        bytes: &[u8],
+        temp_dir: Option<&std::path::Path>,
+        follow_symlinks: bool,
+    ) -> Result<usize, PersistenceError> {
+        let Storage::Filesystem { path } = self;
+        write_atomically(path, temp_dir, follow_symlinks, |file| {
+            use std::io::Write;
+            file.write_all(bytes)?;
+            Ok(bytes.len())
+        })
+    }
+
+    /// Writes a resource to the storage with format options
+    /// and gets the number of bytes written.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn write_with<R: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        format: StorageFormat,
+        resource: &R,
+        options: &FormatOptions,
+    ) -> Result<usize, PersistenceError> {
+        let bytes = serialize_for_browser(name, format, resource, options)?;
+        self.write_serialized(format, &bytes)
+    }
+
+    /// Writes a resource which is serialized with `serialize_for_browser` to the storage
+    /// and gets the number of bytes written.
+    ///
+    /// JSON is stored as is, resources in binary storage formats are stored as JSON arrays,
+    /// and resources in other storage formats are stored as JSON strings.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn write_serialized(
+        &self,
+        format: StorageFormat,
+        bytes: &[u8],
+    ) -> Result<usize, PersistenceError> {
+        self.bump_version();
+        match self {
+            #[cfg(target_family = "wasm")]
+            Storage::LocalStorage { key } => {
+                write_to_browser::<gloo_storage::LocalStorage>(key, format, bytes)
+            },
+            #[cfg(target_family = "wasm")]
+            Storage::SessionStorage { key } => {
+                write_to_browser::<gloo_storage::SessionStorage>(key, format, bytes)
             },
         }
-        Ok(())
     }
 }
 
@@ -287,3 +877,306 @@ impl Display for Storage {
         }
     }
 }
+
+/// Writes a file by writing it into a temporary file in a directory and renaming
+/// the temporary file to the file, and gets the number of bytes written.
+///
+/// Temporary file is created next to the file if the directory is not set, and if the directory
+/// is on another filesystem, it's copied into another temporary file next to the file to rename.
+///
+/// Symbolic links are replaced by renames, so they are resolved to write their targets
+/// only if they are followed.
+#[cfg(not(target_family = "wasm"))]
+fn write_atomically(
+    path: &std::path::Path,
+    temp_dir: Option<&std::path::Path>,
+    follow_symlinks: bool,
+    write: impl FnOnce(&mut std::fs::File) -> Result<usize, PersistenceError>,
+) -> Result<usize, PersistenceError> {
+    let resolved;
+    let path = if follow_symlinks {
+        resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        resolved.as_path()
+    } else {
+        path
+    };
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    let directory = temp_dir.unwrap_or(parent);
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let prefix = format!(".{}.", file_name);
+
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix).suffix(".tmp");
+
+    // temporary files are only accessible by their owners by default
+    // so permissions of the file are kept if it exists
+    #[cfg(unix)]
+    let permissions =
+        std::fs::metadata(path).map(|metadata| metadata.permissions()).unwrap_or_else(|_| {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::Permissions::from_mode(0o644)
+        });
+    #[cfg(unix)]
+    builder.permissions(permissions);
+    #[cfg(not(unix))]
+    if let Ok(metadata) = std::fs::metadata(path) {
+        builder.permissions(metadata.permissions());
+    }
+
+    // temporary file is removed when it's dropped if it's not persisted
+    let mut temporary = builder.tempfile_in(directory)?;
+    let length = write(temporary.as_file_mut())?;
+
+    // content of the temporary file is flushed to the disk before it's renamed
+    // so that the file is never replaced by an incomplete one after a crash
+    temporary.as_file().sync_all()?;
+
+    match temporary.persist(path) {
+        Ok(_) => {
+            #[cfg(unix)]
+            sync_directory(parent)?;
+            Ok(length)
+        },
+        Err(error) if is_cross_device(&error.error) => {
+            log::warn!(
+                "temporary directory {} is on another filesystem than {}, \
+                so the temporary file is copied next to the file before renaming",
+                directory.display(),
+                path.display(),
+            );
+
+            // file is never truncated in place so that it's not left incomplete on failures
+            let sibling = builder.tempfile_in(parent)?;
+            std::fs::copy(error.file.path(), sibling.path())?;
+            sibling.as_file().sync_all()?;
+            sibling.persist(path).map_err(|error| error.error)?;
+
+            #[cfg(unix)]
+            sync_directory(parent)?;
+            Ok(length)
+        },
+        Err(error) => Err(error.error.into()),
+    }
+}
+
+/// Opens a file to append to it, and creates it if it doesn't exist.
+///
+/// If symbolic links are not followed, opening a symbolic link fails with
+/// `PersistenceError::Symlink`, even if it's replaced between checking and opening it.
+#[cfg(not(target_family = "wasm"))]
+fn open_for_appending(
+    path: &std::path::Path,
+    follow_symlinks: bool,
+) -> Result<std::fs::File, PersistenceError> {
+    if follow_symlinks {
+        return Ok(std::fs::OpenOptions::new().create(true).append(true).open(path)?);
+    }
+
+    // exclusive creation fails if the path is a symbolic link, even if it's dangling
+    let file = match std::fs::OpenOptions::new().append(true).open(path) {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(std::fs::OpenOptions::new().create_new(true).append(true).open(path)?);
+        },
+        result => result?,
+    };
+
+    // opened file is compared with the path itself to detect symbolic links
+    // as it cannot be opened without following symbolic links portably
+    let link_metadata = std::fs::symlink_metadata(path)?;
+    if link_metadata.file_type().is_symlink() {
+        return Err(PersistenceError::Symlink(path.to_path_buf()));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = file.metadata()?;
+        if (metadata.dev(), metadata.ino()) != (link_metadata.dev(), link_metadata.ino()) {
+            return Err(PersistenceError::Symlink(path.to_path_buf()));
+        }
+    }
+
+    Ok(file)
+}
+
+/// Flushes a directory to the disk so that renaming files in the directory is durable.
+///
+/// It's only done on unix as directories cannot be opened as files on other platforms.
+#[cfg(unix)]
+fn sync_directory(directory: &std::path::Path) -> Result<(), PersistenceError> {
+    std::fs::File::open(directory)?.sync_all()?;
+    Ok(())
+}
+
+/// Gets whether an error is caused by renaming a file across filesystems.
+#[cfg(not(target_family = "wasm"))]
+fn is_cross_device(error: &std::io::Error) -> bool {
+    if cfg!(windows) {
+        // ERROR_NOT_SAME_DEVICE
+        error.raw_os_error() == Some(17)
+    } else if cfg!(unix) {
+        // EXDEV
+        error.raw_os_error() == Some(18)
+    } else {
+        false
+    }
+}
+
+/// Approximate quota of browser storages in UTF-16 code units.
+///
+/// Browsers don't expose the quota of browser storages, so the most common quota is used.
+#[cfg(target_family = "wasm")]
+const BROWSER_STORAGE_QUOTA: usize = 5 * 1024 * 1024;
+
+/// Ensures a value of the specified length can be stored in a browser storage.
+///
+/// Available space is estimated from the items in the browser storage,
+/// so it's approximate and writes may still fail.
+#[cfg(target_family = "wasm")]
+fn ensure_quota<S: gloo_storage::Storage>(
+    key: &str,
+    value_length: usize,
+) -> Result<(), PersistenceError> {
+    let storage = S::raw();
+
+    let mut used = 0;
+    for index in 0..storage.length().unwrap_or(0) {
+        if let Ok(Some(existing_key)) = storage.key(index) {
+            if existing_key == key {
+                // existing value will be replaced
+                continue;
+            }
+            let existing_value = storage.get_item(&existing_key).ok().flatten().unwrap_or_default();
+            used += existing_key.encode_utf16().count() + existing_value.encode_utf16().count();
+        }
+    }
+
+    let needed = key.encode_utf16().count() + value_length;
+    let available = BROWSER_STORAGE_QUOTA.saturating_sub(used);
+
+    if needed > available {
+        return Err(PersistenceError::QuotaExceeded { needed, available });
+    }
+    Ok(())
+}
+
+/// Serializes a resource into bytes the way it's stored in browser storages.
+///
+/// Browser storages store JSON compactly, so pretty JSON storage formats are serialized as JSON.
+#[cfg(target_family = "wasm")]
+pub(crate) fn serialize_for_browser<R: Serialize + DeserializeOwned>(
+    name: &str,
+    format: StorageFormat,
+    resource: &R,
+    options: &FormatOptions,
+) -> Result<Vec<u8>, PersistenceError> {
+    #[cfg(feature = "json")]
+    if format.is_json() {
+        return StorageFormat::Json.serialize_with(name, resource, options);
+    }
+    format.serialize_with(name, resource, options)
+}
+
+/// Writes a serialized resource to a browser storage and gets the number of bytes written.
+///
+/// Length of the stored value is estimated from the serialized resource
+/// to ensure the quota before writing it.
+#[cfg(target_family = "wasm")]
+fn write_to_browser<S: gloo_storage::Storage>(
+    key: &str,
+    format: StorageFormat,
+    bytes: &[u8],
+) -> Result<usize, PersistenceError> {
+    if format.is_binary() {
+        ensure_quota::<S>(key, json_array_length(bytes))?;
+        S::set::<&[u8]>(key, bytes)?;
+        return Ok(bytes.len());
+    }
+
+    // unwrapping is okay in this case because
+    // remaining storage formats are all human readable
+    // so they return a string converted to bytes
+    let string = std::str::from_utf8(bytes).unwrap();
+
+    #[cfg(feature = "json")]
+    if format.is_json() {
+        ensure_quota::<S>(key, string.encode_utf16().count())?;
+        S::raw().set_item(key, string).map_err(|error| {
+            // browsers only throw errors while setting items (e.g., when quota exceeded)
+            gloo_storage::errors::StorageError::JsError(
+                gloo_utils::errors::JsError::try_from(error)
+                    .expect("browser storage threw a non-error value"),
+            )
+        })?;
+        return Ok(string.len());
+    }
+
+    ensure_quota::<S>(key, json_string_length(string))?;
+    S::set::<&str>(key, string)?;
+
+    Ok(string.len())
+}
+
+/// Reads the content of a browser storage as is, decoding JSON arrays of bytes.
+#[cfg(target_family = "wasm")]
+fn read_raw_from_browser<S: gloo_storage::Storage>(key: &str) -> Result<Vec<u8>, PersistenceError> {
+    let content = S::raw()
+        .get_item(key)
+        .ok()
+        .flatten()
+        .ok_or_else(|| gloo_storage::errors::StorageError::KeyNotFound(key.to_owned()))?;
+    match S::get::<Vec<u8>>(key) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => Ok(content.into_bytes()),
+    }
+}
+
+/// Writes bytes to a browser storage as is, encoding them as a JSON array
+/// if they are not valid UTF-8.
+///
+/// Quota is not estimated, so it's up to the browser to reject values which don't fit.
+#[cfg(target_family = "wasm")]
+fn write_raw_to_browser<S: gloo_storage::Storage>(
+    key: &str,
+    bytes: &[u8],
+) -> Result<(), PersistenceError> {
+    let Ok(content) = std::str::from_utf8(bytes) else {
+        S::set::<&[u8]>(key, bytes)?;
+        return Ok(());
+    };
+
+    S::raw().set_item(key, content).map_err(|error| {
+        // browsers only throw errors while setting items (e.g., when quota exceeded)
+        gloo_storage::errors::StorageError::JsError(
+            gloo_utils::errors::JsError::try_from(error)
+                .expect("browser storage threw a non-error value"),
+        )
+    })?;
+    Ok(())
+}
+
+/// Gets the length of bytes when they are stored as a JSON array in a browser storage.
+#[cfg(target_family = "wasm")]
+fn json_array_length(bytes: &[u8]) -> usize {
+    let digits = |byte: u8| {
+        match byte {
+            0..=9 => 1,
+            10..=99 => 2,
+            _ => 3,
+        }
+    };
+    // brackets and commas
+    let punctuation = bytes.len().max(1) + 1;
+    bytes.iter().map(|byte| digits(*byte)).sum::<usize>() + punctuation
+}
+
+/// Gets the length of a string when it's stored as a JSON string in a browser storage.
+#[cfg(target_family = "wasm")]
+fn json_string_length(string: &str) -> usize {
+    // quotes and escapes are approximated
+    string.encode_utf16().count() + 2
+}
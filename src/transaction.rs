@@ -0,0 +1,244 @@
+//! A transaction of writes to storages.
+
+use crate::prelude::*;
+use std::path::Path;
+
+/// A transaction of writes to storages.
+///
+/// Writes are collected in memory and committed together, so that on errors, storages are restored
+/// to their previous state on a best-effort basis (e.g., to save a game state which is split
+/// across multiple files consistently).
+///
+/// When committing, contents are written to temporary files next to their storages first,
+/// and temporary files are renamed to their storages one by one only after all of them are
+/// written, while previous contents are kept in backup files until all renames succeed.
+/// If anything fails along the way, storages are restored from their backups.
+///
+/// Committing is not atomic as a whole, so if the process crashes while renaming temporary files,
+/// some storages may be written while others are not, and backup files may be left behind.
+/// Write options of persistent resources, such as syncing storages after writing them
+/// or not following symbolic links, are not applied either.
+#[derive(Debug, Default)]
+pub struct Transaction<'a> {
+    writes: Vec<PendingWrite<'a>>,
+    error: Option<PersistenceError>,
+}
+
+/// A write of a transaction which is not committed yet.
+struct PendingWrite<'a> {
+    storage: Storage,
+    format: StorageFormat,
+    bytes: Vec<u8>,
+    create_dirs: bool,
+    persistent: Option<&'a dyn Committable>,
+}
+
+impl fmt::Debug for PendingWrite<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingWrite")
+            .field("storage", &self.storage)
+            .field("format", &self.format)
+            .field("bytes", &self.bytes)
+            .field("create_dirs", &self.create_dirs)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A persistent resource whose change tracking is updated when a transaction is committed.
+trait Committable {
+    /// Updates the change tracking of the resource after it's committed.
+    fn committed(&self, length: usize);
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Committable for Persistent<R> {
+    fn committed(&self, length: usize) {
+        Persistent::committed(self, length);
+    }
+}
+
+impl<'a> Transaction<'a> {
+    /// Creates an empty transaction.
+    pub fn new() -> Transaction<'a> {
+        Transaction::default()
+    }
+
+    /// Adds a write of a persistent resource to its underlying storage to the transaction.
+    ///
+    /// The resource is serialized immediately, and if serialization fails,
+    /// committing the transaction fails without writing anything.
+    ///
+    /// The resource is borrowed until the transaction is committed, so its change tracking
+    /// (e.g., the last persisted value and the history) is updated after committing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is unloaded or it's in append mode.
+    pub fn add<R: Resource + Serialize + DeserializeOwned>(
+        &mut self,
+        persistent: &'a Persistent<R>,
+    ) -> &mut Transaction<'a> {
+        if persistent.merge.is_some() {
+            panic!("tried to add {} in append mode to a transaction", persistent.name);
+        }
+        if self.error.is_some() {
+            return self;
+        }
+
+        if let Some(resource) = &persistent.resource {
+            persistent.hooks.saving(resource);
+        }
+        match persistent.preview() {
+            Ok(bytes) => {
+                self.writes.push(PendingWrite {
+                    storage: persistent.storage.clone(),
+                    format: persistent.format,
+                    bytes,
+                    create_dirs: persistent.create_dirs,
+                    persistent: Some(persistent),
+                });
+            },
+            Err(error) => {
+                // serialization errors are logged in format module
+                log_event!(
+                    error,
+                    persistent,
+                    "failed to add {} to a transaction due to a serialization error",
+                    persistent.name,
+                );
+                self.error = Some(error);
+            },
+        }
+        self
+    }
+
+    /// Adds a write of already serialized bytes to a storage to the transaction.
+    pub fn add_bytes(
+        &mut self,
+        storage: Storage,
+        format: StorageFormat,
+        bytes: Vec<u8>,
+    ) -> &mut Transaction<'a> {
+        self.writes.push(PendingWrite {
+            storage,
+            format,
+            bytes,
+            create_dirs: true,
+            persistent: None,
+        });
+        self
+    }
+
+    /// Gets the number of writes in the transaction.
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Gets if the transaction is empty.
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Commits the transaction.
+    ///
+    /// Persistent resources in the transaction are treated as persisted once it's committed
+    /// (i.e., their change tracking is updated and their persisted hooks run).
+    pub fn commit(self) -> Result<(), PersistenceError> {
+        if let Some(error) = self.error {
+            log::error!("failed to commit a transaction as one of its writes failed to serialize");
+            return Err(error);
+        }
+
+        let mut staged = Vec::with_capacity(self.writes.len());
+        for write in &self.writes {
+            let PendingWrite { storage, bytes, create_dirs, .. } = write;
+            let Storage::Filesystem { path } = storage;
+            let temporary = sibling(path, "tmp");
+
+            let initialized =
+                if *create_dirs { storage.initialize() } else { storage.ensure_parent_exists() };
+            let result = initialized
+                .and_then(|_| std::fs::write(&temporary, bytes).map_err(PersistenceError::from));
+            if let Err(error) = result {
+                log_event!(
+                    error,
+                    ("transaction", storage, write.format),
+                    "failed to commit a transaction as writing to {} failed: {}",
+                    storage,
+                    error,
+                );
+                std::fs::remove_file(&temporary).ok();
+                rollback(&[], &staged);
+                return Err(error);
+            }
+
+            staged.push((temporary, path.clone()));
+        }
+
+        let mut committed = Vec::with_capacity(staged.len());
+        for (index, (temporary, destination)) in staged.iter().enumerate() {
+            let backup = destination.exists().then(|| sibling(destination, "bak"));
+
+            let mut result = Ok(());
+            if let Some(backup) = &backup {
+                result = std::fs::rename(destination, backup);
+            }
+            if result.is_ok() {
+                result = std::fs::rename(temporary, destination);
+                if result.is_err() {
+                    if let Some(backup) = &backup {
+                        std::fs::rename(backup, destination).ok();
+                    }
+                }
+            }
+
+            if let Err(error) = result {
+                let write = &self.writes[index];
+                log_event!(
+                    error,
+                    ("transaction", write.storage, write.format),
+                    "failed to commit a transaction as moving {} into place failed: {}",
+                    write.storage,
+                    error,
+                );
+                rollback(&committed, &staged[index..]);
+                return Err(error.into());
+            }
+
+            committed.push((destination.clone(), backup));
+        }
+
+        for (_, backup) in committed {
+            if let Some(backup) = backup {
+                std::fs::remove_file(backup).ok();
+            }
+        }
+
+        for PendingWrite { bytes, persistent, .. } in &self.writes {
+            if let Some(persistent) = persistent {
+                persistent.committed(bytes.len());
+            }
+        }
+
+        log::info!("committed a transaction of {} writes", self.writes.len());
+        Ok(())
+    }
+}
+
+/// Gets the path of a hidden file next to a path (e.g., `saves/.world.bin.tmp`).
+pub(crate) fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}", file_name, suffix))
+}
+
+/// Restores committed files from their backups and removes staged temporary files.
+fn rollback(committed: &[(PathBuf, Option<PathBuf>)], staged: &[(PathBuf, PathBuf)]) {
+    for (destination, backup) in committed.iter().rev() {
+        match backup {
+            Some(backup) => std::fs::rename(backup, destination).ok(),
+            None => std::fs::remove_file(destination).ok(),
+        };
+    }
+    for (temporary, _) in staged {
+        std::fs::remove_file(temporary).ok();
+    }
+}
@@ -0,0 +1,139 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+
+    #[derive(Resource)]
+    struct SavePaths {
+        config: PathBuf,
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn init_persistent() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut app = App::new();
+        app.insert_resource(SavePaths { config: tempdir.path().to_owned() });
+        app.init_persistent::<KeyBindings>(|world| {
+            let save_paths = world.resource::<SavePaths>();
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(save_paths.config.join("key-bindings.toml"))
+                .default(KeyBindings::default())
+        });
+
+        assert!(app.world().get_resource::<Persistent<KeyBindings>>().is_none());
+
+        app.update();
+
+        let resource = app.world().resource::<Persistent<KeyBindings>>();
+        assert_eq!(resource.storage(), &Storage::Filesystem { path: path.clone() });
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        assert!(path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn persist_mut() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut app = App::new();
+        app.insert_resource(
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .build()?,
+        );
+
+        let content = std::fs::read_to_string(&path)?;
+
+        app.add_systems(Update, |key_bindings: PersistMut<KeyBindings>| {
+            assert_eq!(key_bindings.get(), &KeyBindings::default());
+        });
+        app.update();
+
+        assert_eq!(std::fs::read_to_string(&path)?, content);
+
+        app.add_systems(PostUpdate, |mut key_bindings: PersistMut<KeyBindings>| {
+            key_bindings.crouch = KeyCode::ControlLeft;
+        });
+        app.update();
+
+        let expected = KeyBindings { crouch: KeyCode::ControlLeft, ..KeyBindings::default() };
+        assert_eq!(app.world().resource::<Persistent<KeyBindings>>().get(), &expected);
+
+        let persisted =
+            Persistent::<KeyBindings>::toml("key bindings", &path, KeyBindings::default())?;
+        assert_eq!(persisted.get(), &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn registry() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Settings {
+            volume: f64,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let settings_path = tempdir.path().join("settings.toml");
+
+        let mut app = App::new();
+        app.init_persistent::<KeyBindings>({
+            let path = key_bindings_path.clone();
+            move |_| {
+                Persistent::<KeyBindings>::builder()
+                    .name("key bindings")
+                    .format(StorageFormat::Toml)
+                    .path(&path)
+                    .default(KeyBindings::default())
+            }
+        });
+        app.insert_resource(
+            Persistent::<Settings>::builder()
+                .name("settings")
+                .format(StorageFormat::Toml)
+                .path(&settings_path)
+                .default(Settings::default())
+                .loaded(false)
+                .build()?,
+        );
+        app.register_persistent::<Settings>();
+        app.register_persistent::<Settings>();
+
+        let registered = PersistentRegistry::registered(app.world());
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered[0].name, "settings");
+
+        app.update();
+
+        let registered = PersistentRegistry::registered(app.world());
+        assert_eq!(registered.len(), 2);
+
+        assert_eq!(registered[0].name, "key bindings");
+        assert_eq!(registered[0].format, StorageFormat::Toml);
+        assert_eq!(registered[0].storage, Storage::Filesystem { path: key_bindings_path });
+        assert!(registered[0].loaded);
+
+        assert_eq!(registered[1].name, "settings");
+        assert_eq!(registered[1].format, StorageFormat::Toml);
+        assert_eq!(registered[1].storage, Storage::Filesystem { path: settings_path });
+        assert!(!registered[1].loaded);
+
+        Ok(())
+    }
+}
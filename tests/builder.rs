@@ -4,6 +4,7 @@ use common::*;
 #[cfg(not(target_family = "wasm"))]
 mod native {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     #[cfg(feature = "toml")]
@@ -79,6 +80,169 @@ mod native {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_builder_path_template() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let template =
+            format!("{}/profiles/{{profile}}/key-bindings.toml", tempdir.path().display());
+        let context = HashMap::from([("profile".to_owned(), "alice".to_owned())]);
+
+        let path = tempdir.path().join("profiles").join("alice").join("key-bindings.toml");
+        assert!(!path.exists());
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path_template(&template, &context)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert!(path.exists());
+        assert_eq!(resource.storage(), &Storage::Filesystem { path });
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_builder_path_template_missing_placeholder() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let template =
+            format!("{}/profiles/{{profile}}/key-bindings.toml", tempdir.path().display());
+        let context = HashMap::from([("user".to_owned(), "alice".to_owned())]);
+
+        let error = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path_template(&template, &context)
+            .default(KeyBindings::default())
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(
+            &error,
+            PersistenceError::MissingPathPlaceholder(placeholder) if placeholder == "profile",
+        ));
+        assert_eq!(error.to_string(), "path template placeholder {profile} is not in the context");
+
+        assert!(!tempdir.path().join("profiles").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_builder_name() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        const NAME: &str = "key bindings";
+        let resource = Persistent::<KeyBindings>::builder()
+            .name(NAME)
+            .format(StorageFormat::Toml)
+            .path(tempdir.path().join("key-bindings.toml"))
+            .default(KeyBindings::default())
+            .build()?;
+
+        let name: &str = resource.name();
+        assert_eq!(name, NAME);
+
+        let player = 2;
+        let resource = Persistent::<KeyBindings>::builder()
+            .name(format!("key bindings of player {}", player))
+            .format(StorageFormat::Toml)
+            .path(tempdir.path().join("key-bindings-2.toml"))
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert_eq!(resource.name(), "key bindings of player 2");
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_builder_shortcut_toml() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let shortcut_path = tempdir.path().join("shortcut").join("key-bindings.toml");
+        let builder_path = tempdir.path().join("builder").join("key-bindings.toml");
+
+        let shortcut = Persistent::<KeyBindings>::toml(
+            "key bindings",
+            &shortcut_path,
+            KeyBindings::default(),
+        )?;
+        let builder = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&builder_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert_eq!(shortcut.name(), builder.name());
+        assert_eq!(shortcut.format(), builder.format());
+        assert_eq!(shortcut.is_loaded(), builder.is_loaded());
+        assert_eq!(shortcut.is_revertible(), builder.is_revertible());
+        assert_eq!(shortcut.get(), builder.get());
+        assert_eq!(std::fs::read(&shortcut_path)?, std::fs::read(&builder_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_builder_shortcut_json() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let shortcut_path = tempdir.path().join("shortcut").join("key-bindings.json");
+        let builder_path = tempdir.path().join("builder").join("key-bindings.json");
+
+        let shortcut = Persistent::<KeyBindings>::json(
+            "key bindings",
+            &shortcut_path,
+            KeyBindings::default(),
+        )?;
+        let builder = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Json)
+            .path(&builder_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert_eq!(shortcut.name(), builder.name());
+        assert_eq!(shortcut.format(), builder.format());
+        assert_eq!(shortcut.is_loaded(), builder.is_loaded());
+        assert_eq!(shortcut.is_revertible(), builder.is_revertible());
+        assert_eq!(shortcut.get(), builder.get());
+        assert_eq!(std::fs::read(&shortcut_path)?, std::fs::read(&builder_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_builder_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let resource = <bevy_persistent::PersistentBuilder<KeyBindings> as Default>::default()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .build()?;
+
+        assert!(path.exists());
+
+        assert!(resource.is_loaded());
+        assert_eq!(resource.name(), "key bindings");
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
     #[test]
     #[should_panic(expected = "persistent resource name is not set")]
     fn test_builder_no_name() {
@@ -86,9 +250,43 @@ mod native {
     }
 
     #[test]
-    #[should_panic(expected = "persistent resource format is not set")]
-    fn test_builder_no_format() {
-        Persistent::<KeyBindings>::builder().name("key bindings").build().ok();
+    #[cfg(feature = "ron")]
+    fn test_builder_no_format() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.ron");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert_eq!(resource.format(), StorageFormat::Ron);
+        assert_eq!(
+            ron::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            KeyBindings::default()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_builder_no_format_unknown_extension() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.cfg");
+
+        let error = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(&error, PersistenceError::UninferredStorageFormat(p) if p == &path));
+        assert!(!path.exists());
+
+        Ok(())
     }
 
     #[test]
@@ -149,9 +347,10 @@ mod wasm {
 
         assert_eq!(resource.name(), name);
         assert_eq!(resource.format(), format);
-        assert_eq!(resource.storage(), &Storage::LocalStorage {
-            key: "key-bindings.toml".to_owned()
-        },);
+        assert_eq!(
+            resource.storage(),
+            &Storage::LocalStorage { key: "key-bindings.toml".to_owned() },
+        );
         assert_eq!(resource.get(), &default);
 
         Ok(())
@@ -181,9 +380,10 @@ mod wasm {
 
         assert_eq!(resource.name(), name);
         assert_eq!(resource.format(), format);
-        assert_eq!(resource.storage(), &Storage::LocalStorage {
-            key: "key-bindings.toml".to_owned()
-        },);
+        assert_eq!(
+            resource.storage(),
+            &Storage::LocalStorage { key: "key-bindings.toml".to_owned() },
+        );
 
         assert!(!resource.is_loaded());
         assert!(resource.is_unloaded());
@@ -227,9 +427,10 @@ mod wasm {
 
         assert_eq!(resource.name(), name);
         assert_eq!(resource.format(), format);
-        assert_eq!(resource.storage(), &Storage::SessionStorage {
-            key: "key-bindings.toml".to_owned()
-        },);
+        assert_eq!(
+            resource.storage(),
+            &Storage::SessionStorage { key: "key-bindings.toml".to_owned() },
+        );
         assert_eq!(resource.get(), &default);
 
         Ok(())
@@ -259,9 +460,10 @@ mod wasm {
 
         assert_eq!(resource.name(), name);
         assert_eq!(resource.format(), format);
-        assert_eq!(resource.storage(), &Storage::SessionStorage {
-            key: "key-bindings.toml".to_owned()
-        },);
+        assert_eq!(
+            resource.storage(),
+            &Storage::SessionStorage { key: "key-bindings.toml".to_owned() },
+        );
 
         assert!(!resource.is_loaded());
         assert!(resource.is_unloaded());
@@ -282,5 +484,47 @@ mod wasm {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    #[cfg(feature = "toml")]
+    fn test_builder_key_prefix() -> anyhow::Result<()> {
+        LocalStorage::clear();
+
+        let path = PathBuf::from("local").join("key-bindings.toml");
+
+        let mut first = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .key_prefix("first")
+            .build()?;
+        let mut second = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .key_prefix("second")
+            .build()?;
+
+        assert_eq!(
+            first.storage(),
+            &Storage::LocalStorage { key: "first::key-bindings.toml".to_owned() },
+        );
+        assert_eq!(first.storage().to_string(), "/local/first::key-bindings.toml");
+
+        assert!(LocalStorage::raw().get_item("key-bindings.toml").unwrap().is_none());
+
+        first.update(|key_bindings| key_bindings.jump = KeyCode::KeyJ)?;
+        second.update(|key_bindings| key_bindings.jump = KeyCode::KeyK)?;
+
+        first.reload()?;
+        second.reload()?;
+
+        assert_eq!(first.jump, KeyCode::KeyJ);
+        assert_eq!(second.jump, KeyCode::KeyK);
+
+        Ok(())
+    }
+
     wasm_bindgen_test_configure!(run_in_browser);
 }
@@ -1,7 +1,10 @@
+#![allow(dead_code)]
 #![allow(unused_imports)]
 
 pub use bevy::prelude::*;
 pub use bevy_persistent::{
+    error::PersistenceError,
+    event::Operation,
     prelude::*,
     storage::Storage,
 };
@@ -9,9 +12,15 @@ pub use serde::{
     Deserialize,
     Serialize,
 };
-pub use std::path::PathBuf;
+pub use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Resource, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Resource, Serialize)]
 pub struct KeyBindings {
     pub jump: KeyCode,
     pub crouch: KeyCode,
@@ -22,3 +31,42 @@ impl Default for KeyBindings {
         KeyBindings { jump: KeyCode::Space, crouch: KeyCode::KeyC }
     }
 }
+
+#[derive(Clone, Default)]
+pub struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn capture_logs<T>(f: impl FnOnce() -> T) -> (T, String) {
+    use bevy::log::tracing_subscriber::{
+        self,
+        filter::LevelFilter,
+        util::SubscriberInitExt,
+    };
+
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .without_time()
+        .with_max_level(LevelFilter::TRACE)
+        .finish();
+
+    let guard = subscriber.set_default();
+    let result = f();
+    drop(guard);
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    (result, logs)
+}
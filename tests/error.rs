@@ -0,0 +1,233 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+
+    fn assert_deserialization_error(format: StorageFormat, expected_format: StorageFormat) {
+        let error = format.deserialize::<KeyBindings>("key bindings", b"").unwrap_err();
+
+        assert!(error.is_serde());
+        assert!(error.is_deserialization());
+        assert!(!error.is_serialization());
+        assert_eq!(error.format(), Some(expected_format));
+    }
+
+    fn assert_serialization_error(error: PersistenceError, expected_format: StorageFormat) {
+        assert!(error.is_serde());
+        assert!(error.is_serialization());
+        assert!(!error.is_deserialization());
+        assert_eq!(error.format(), Some(expected_format));
+    }
+
+    #[test]
+    fn test_filesystem_error() {
+        let error = PersistenceError::from(std::io::Error::other("disk is full"));
+
+        assert!(!error.is_quota());
+        assert!(!error.is_serde());
+        assert!(!error.is_serialization());
+        assert!(!error.is_deserialization());
+        assert_eq!(error.format(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_io_error() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        std::fs::remove_file(&path)?;
+        std::fs::create_dir(&path)?;
+
+        let error = resource.set(KeyBindings::default()).unwrap_err();
+        assert!(matches!(&error, PersistenceError::Io { name, .. } if name == "key bindings"));
+
+        assert!(!error.is_serde());
+        assert!(!error.is_serialization());
+        assert!(!error.is_deserialization());
+        assert_eq!(error.format(), None);
+
+        let message = error.to_string();
+        assert!(message.contains("key bindings"));
+        assert!(message.contains(&resource.storage().to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insufficient_space_error() {
+        let error = PersistenceError::InsufficientSpace { needed: 2048, available: 1024 };
+
+        assert!(!error.is_serde());
+        assert!(!error.is_serialization());
+        assert!(!error.is_deserialization());
+        assert_eq!(error.format(), None);
+        assert!(error.is_quota());
+        assert_eq!(
+            error.to_string(),
+            "insufficient space on the device (2048 bytes needed, 1024 available)",
+        );
+    }
+
+    #[test]
+    fn test_missing_directory_error() {
+        let error = PersistenceError::MissingDirectory(PathBuf::from("saves"));
+
+        assert!(!error.is_serde());
+        assert!(!error.is_serialization());
+        assert!(!error.is_deserialization());
+        assert_eq!(error.format(), None);
+        assert_eq!(error.to_string(), "directory saves does not exist");
+    }
+
+    #[test]
+    fn test_too_large_error() {
+        let error = PersistenceError::TooLarge { size: 2048, limit: 1024 };
+
+        assert!(!error.is_serde());
+        assert!(!error.is_serialization());
+        assert!(!error.is_deserialization());
+        assert_eq!(error.format(), None);
+        assert_eq!(
+            error.to_string(),
+            "storage is too large to read (2048 bytes, limit is 1024 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_recovery_failed_error() {
+        let error = PersistenceError::RecoveryFailed {
+            primary: Box::new(PersistenceError::Validation("jump is not bound".to_owned())),
+            during_recovery: Box::new(PersistenceError::from(std::io::Error::other(
+                "disk is full",
+            ))),
+        };
+
+        assert!(error.is_serde());
+        assert!(!error.is_serialization());
+        assert!(error.is_deserialization());
+        assert_eq!(error.format(), None);
+        assert_eq!(
+            error.to_string(),
+            "validation failed: jump is not bound (recovery failed: disk is full)",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_encoding_error() {
+        let error = StorageFormat::Toml.deserialize::<KeyBindings>("key bindings", &[0xff]);
+        let error = error.unwrap_err();
+
+        assert!(matches!(error, PersistenceError::Encoding(_)));
+        assert!(error.is_deserialization());
+        assert_eq!(error.format(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn test_bincode_errors() {
+        assert_deserialization_error(StorageFormat::Bincode, StorageFormat::Bincode);
+    }
+
+    #[test]
+    #[cfg(feature = "bson")]
+    fn test_bson_errors() {
+        assert_deserialization_error(StorageFormat::Bson, StorageFormat::Bson);
+
+        let error = StorageFormat::Bson.serialize("number", &42u32).unwrap_err();
+        assert!(error.to_string().contains("BSON requires a document at the top level"));
+        assert_serialization_error(error, StorageFormat::Bson);
+    }
+
+    #[test]
+    #[cfg(feature = "ini")]
+    fn test_ini_errors() {
+        assert_deserialization_error(StorageFormat::Ini, StorageFormat::Ini);
+
+        let error = StorageFormat::Ini.serialize("number", &42u32).unwrap_err();
+        assert_serialization_error(error, StorageFormat::Ini);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_errors() {
+        assert_deserialization_error(StorageFormat::Json, StorageFormat::Json);
+        #[cfg(feature = "pretty")]
+        assert_deserialization_error(StorageFormat::JsonPretty, StorageFormat::Json);
+
+        let map = std::collections::HashMap::from([((1u32, 2u32), 3u32)]);
+        let error = StorageFormat::Json.serialize("map", &map).unwrap_err();
+        assert_serialization_error(error, StorageFormat::Json);
+    }
+
+    #[test]
+    #[cfg(feature = "pickle")]
+    fn test_pickle_errors() {
+        assert_deserialization_error(StorageFormat::Pickle, StorageFormat::Pickle);
+    }
+
+    #[test]
+    #[cfg(feature = "protobuf")]
+    fn test_protobuf_errors() {
+        assert_deserialization_error(StorageFormat::Protobuf, StorageFormat::Protobuf);
+
+        let error = StorageFormat::Protobuf.serialize("key bindings", &KeyBindings::default());
+        assert_serialization_error(error.unwrap_err(), StorageFormat::Protobuf);
+    }
+
+    #[test]
+    #[cfg(feature = "ron")]
+    fn test_ron_errors() {
+        assert_deserialization_error(StorageFormat::Ron, StorageFormat::Ron);
+        #[cfg(feature = "pretty")]
+        assert_deserialization_error(StorageFormat::RonPretty, StorageFormat::Ron);
+        #[cfg(feature = "pretty")]
+        assert_deserialization_error(StorageFormat::RonPrettyWithStructNames, StorageFormat::Ron);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_toml_errors() {
+        assert_deserialization_error(StorageFormat::Toml, StorageFormat::Toml);
+        #[cfg(feature = "pretty")]
+        assert_deserialization_error(StorageFormat::TomlPretty, StorageFormat::Toml);
+
+        let error = StorageFormat::Toml.serialize("number", &42u32).unwrap_err();
+        assert_serialization_error(error, StorageFormat::Toml);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_source_serde() {
+        let content = "[keybindings]\njump = \n";
+        let error = StorageFormat::Toml
+            .deserialize::<KeyBindings>("key bindings", content.as_bytes())
+            .unwrap_err();
+
+        let source = error.source_serde().unwrap();
+        let toml_error = source.downcast_ref::<toml::de::Error>().unwrap();
+
+        let span = toml_error.span().unwrap();
+        assert!(span.start >= content.find("jump").unwrap());
+        assert!(span.end <= content.len());
+
+        let error = PersistenceError::from(std::io::Error::other("disk is full"));
+        assert!(error.source_serde().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_yaml_errors() {
+        assert_deserialization_error(StorageFormat::Yaml, StorageFormat::Yaml);
+    }
+}
@@ -26,6 +26,27 @@ mod native {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "bson")]
+    fn test_bson() -> anyhow::Result<()> {
+        let format = StorageFormat::Bson;
+        let resource = KeyBindings::default();
+
+        let actual_serialized_resource = format.serialize("key bindings", &resource).unwrap();
+        let expected_serialized_resource = bson::to_vec(&resource)?;
+
+        assert_eq!(actual_serialized_resource, expected_serialized_resource);
+
+        let actual_deserialized_resource =
+            format.deserialize::<KeyBindings>("key bindings", &actual_serialized_resource).unwrap();
+        let expected_deserialized_resource =
+            bson::from_slice::<KeyBindings>(&expected_serialized_resource)?;
+
+        assert_eq!(expected_deserialized_resource, actual_deserialized_resource);
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "ini")]
     fn test_ini() -> anyhow::Result<()> {
@@ -48,6 +69,20 @@ mod native {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "ini")]
+    fn test_ini_not_a_map() {
+        let format = StorageFormat::Ini;
+        let resource = vec![KeyBindings::default()];
+
+        let error = format.serialize("key bindings", &resource).unwrap_err();
+        assert!(matches!(error, PersistenceError::UnsupportedIniShape(_)));
+        assert_eq!(
+            error.to_string(),
+            "INI only supports flat resources but the resource is serialized as a sequence",
+        );
+    }
+
     #[test]
     #[cfg(feature = "json")]
     fn test_json() -> anyhow::Result<()> {
@@ -95,6 +130,62 @@ mod native {
         Ok(())
     }
 
+    #[test]
+    #[cfg(all(feature = "json", feature = "pretty"))]
+    fn test_json_pretty_with() -> anyhow::Result<()> {
+        let resource = KeyBindings::default();
+
+        let tabs = StorageFormat::JsonPrettyWith(JsonIndent::Tabs);
+        let four_spaces = StorageFormat::JsonPrettyWith(JsonIndent::Spaces(4));
+
+        let tabs_serialized_resource = tabs.serialize("key bindings", &resource).unwrap();
+        let four_spaces_serialized_resource =
+            four_spaces.serialize("key bindings", &resource).unwrap();
+
+        let tabs_serialized_resource_str = std::str::from_utf8(&tabs_serialized_resource)?;
+        let four_spaces_serialized_resource_str =
+            std::str::from_utf8(&four_spaces_serialized_resource)?;
+
+        assert!(tabs_serialized_resource_str.contains("\n\t\"jump\""));
+        assert!(four_spaces_serialized_resource_str.contains("\n    \"jump\""));
+        assert_ne!(tabs_serialized_resource, four_spaces_serialized_resource);
+
+        let tabs_deserialized_resource =
+            tabs.deserialize::<KeyBindings>("key bindings", &tabs_serialized_resource).unwrap();
+        let four_spaces_deserialized_resource = four_spaces
+            .deserialize::<KeyBindings>("key bindings", &four_spaces_serialized_resource)
+            .unwrap();
+
+        assert_eq!(tabs_deserialized_resource, resource);
+        assert_eq!(four_spaces_deserialized_resource, resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "pickle")]
+    fn test_pickle() -> anyhow::Result<()> {
+        let format = StorageFormat::Pickle;
+        let resource = KeyBindings::default();
+
+        let actual_serialized_resource = format.serialize("key bindings", &resource).unwrap();
+        let expected_serialized_resource =
+            serde_pickle::to_vec(&resource, serde_pickle::SerOptions::new())?;
+
+        assert_eq!(actual_serialized_resource, expected_serialized_resource);
+
+        let actual_deserialized_resource =
+            format.deserialize::<KeyBindings>("key bindings", &actual_serialized_resource).unwrap();
+        let expected_deserialized_resource = serde_pickle::from_slice::<KeyBindings>(
+            &expected_serialized_resource,
+            serde_pickle::DeOptions::new(),
+        )?;
+
+        assert_eq!(expected_deserialized_resource, actual_deserialized_resource);
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "ron")]
     fn test_ron() -> anyhow::Result<()> {
@@ -235,6 +326,212 @@ mod native {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(all(feature = "yaml", feature = "pretty"))]
+    fn test_yaml_pretty() -> anyhow::Result<()> {
+        let format = StorageFormat::YamlPretty;
+        let resource = KeyBindings::default();
+
+        let actual_serialized_resource = format.serialize("key bindings", &resource).unwrap();
+        let expected_serialized_resource =
+            format!("---\n{}", serde_yaml::to_string(&resource).unwrap()).into_bytes();
+
+        assert!(actual_serialized_resource.starts_with(b"---\n"));
+        assert_eq!(actual_serialized_resource, expected_serialized_resource);
+
+        let actual_deserialized_resource =
+            format.deserialize::<KeyBindings>("key bindings", &actual_serialized_resource).unwrap();
+        let expected_deserialized_resource = serde_yaml::from_str::<KeyBindings>(
+            std::str::from_utf8(&expected_serialized_resource)?,
+        )
+        .unwrap();
+
+        assert_eq!(expected_deserialized_resource, actual_deserialized_resource);
+        assert_eq!(actual_deserialized_resource, resource);
+
+        let unmarked_serialized_resource = serde_yaml::to_string(&resource)?.into_bytes();
+        let unmarked_deserialized_resource = format
+            .deserialize::<KeyBindings>("key bindings", &unmarked_serialized_resource)
+            .unwrap();
+
+        assert_eq!(unmarked_deserialized_resource, resource);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_binary() {
+        #[cfg(feature = "bincode")]
+        assert!(StorageFormat::Bincode.is_binary());
+        #[cfg(feature = "bincode")]
+        assert!(!StorageFormat::Bincode.is_human_readable());
+        #[cfg(feature = "bson")]
+        assert!(StorageFormat::Bson.is_binary());
+        #[cfg(feature = "pickle")]
+        assert!(StorageFormat::Pickle.is_binary());
+        #[cfg(feature = "protobuf")]
+        assert!(StorageFormat::Protobuf.is_binary());
+
+        #[cfg(feature = "json")]
+        assert!(StorageFormat::Json.is_human_readable());
+        #[cfg(all(feature = "json", feature = "pretty"))]
+        assert!(StorageFormat::JsonPretty.is_human_readable());
+        #[cfg(feature = "toml")]
+        assert!(StorageFormat::Toml.is_human_readable());
+        #[cfg(all(feature = "toml", feature = "pretty"))]
+        assert!(StorageFormat::TomlPretty.is_human_readable());
+        #[cfg(feature = "yaml")]
+        assert!(StorageFormat::Yaml.is_human_readable());
+        #[cfg(feature = "yaml")]
+        assert!(!StorageFormat::Yaml.is_binary());
+    }
+
+    #[test]
+    fn test_extension() {
+        #[cfg(feature = "bincode")]
+        assert_eq!(StorageFormat::Bincode.extension(), "bin");
+        #[cfg(feature = "json")]
+        assert_eq!(StorageFormat::Json.extension(), "json");
+        #[cfg(all(feature = "json", feature = "pretty"))]
+        assert_eq!(StorageFormat::JsonPretty.extension(), "json");
+        #[cfg(feature = "pickle")]
+        assert_eq!(StorageFormat::Pickle.extension(), "pkl");
+        #[cfg(feature = "protobuf")]
+        assert_eq!(StorageFormat::Protobuf.extension(), "pb");
+        #[cfg(feature = "toml")]
+        assert_eq!(StorageFormat::Toml.extension(), "toml");
+        #[cfg(feature = "yaml")]
+        assert_eq!(StorageFormat::Yaml.extension(), "yaml");
+    }
+
+    #[test]
+    fn test_from_str() {
+        #[cfg(feature = "bincode")]
+        assert_eq!("bincode".parse::<StorageFormat>().unwrap(), StorageFormat::Bincode);
+        #[cfg(feature = "bson")]
+        assert_eq!("bson".parse::<StorageFormat>().unwrap(), StorageFormat::Bson);
+        #[cfg(feature = "ini")]
+        assert_eq!("ini".parse::<StorageFormat>().unwrap(), StorageFormat::Ini);
+        #[cfg(feature = "json")]
+        assert_eq!("json".parse::<StorageFormat>().unwrap(), StorageFormat::Json);
+        #[cfg(all(feature = "json", feature = "pretty"))]
+        assert_eq!("json-pretty".parse::<StorageFormat>().unwrap(), StorageFormat::JsonPretty);
+        #[cfg(feature = "pickle")]
+        assert_eq!("pickle".parse::<StorageFormat>().unwrap(), StorageFormat::Pickle);
+        #[cfg(feature = "protobuf")]
+        assert_eq!("protobuf".parse::<StorageFormat>().unwrap(), StorageFormat::Protobuf);
+        #[cfg(feature = "ron")]
+        assert_eq!("ron".parse::<StorageFormat>().unwrap(), StorageFormat::Ron);
+        #[cfg(all(feature = "ron", feature = "pretty"))]
+        assert_eq!("ron-pretty".parse::<StorageFormat>().unwrap(), StorageFormat::RonPretty);
+        #[cfg(all(feature = "ron", feature = "pretty"))]
+        assert_eq!(
+            "ron-pretty-with-struct-names".parse::<StorageFormat>().unwrap(),
+            StorageFormat::RonPrettyWithStructNames,
+        );
+        #[cfg(feature = "toml")]
+        assert_eq!("toml".parse::<StorageFormat>().unwrap(), StorageFormat::Toml);
+        #[cfg(all(feature = "toml", feature = "pretty"))]
+        assert_eq!("toml-pretty".parse::<StorageFormat>().unwrap(), StorageFormat::TomlPretty);
+        #[cfg(feature = "yaml")]
+        assert_eq!("yaml".parse::<StorageFormat>().unwrap(), StorageFormat::Yaml);
+
+        #[cfg(feature = "toml")]
+        assert_eq!(StorageFormat::try_from("toml").unwrap(), StorageFormat::Toml);
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!(matches!(
+            "xml".parse::<StorageFormat>(),
+            Err(PersistenceError::UnknownStorageFormat(name)) if name == "xml",
+        ));
+        assert!(matches!(
+            "TOML".parse::<StorageFormat>(),
+            Err(PersistenceError::UnknownStorageFormat(name)) if name == "TOML",
+        ));
+
+        #[cfg(not(feature = "bincode"))]
+        assert!(matches!(
+            "bincode".parse::<StorageFormat>(),
+            Err(PersistenceError::DisabledStorageFormat(name)) if name == "bincode",
+        ));
+        #[cfg(not(feature = "pretty"))]
+        assert!(matches!(
+            "json-pretty".parse::<StorageFormat>(),
+            Err(PersistenceError::DisabledStorageFormat(name)) if name == "json-pretty",
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "ron", feature = "pretty"))]
+    fn test_display() {
+        for format in
+            [StorageFormat::Ron, StorageFormat::RonPretty, StorageFormat::RonPrettyWithStructNames]
+        {
+            assert_eq!(format.to_string(), format.as_str());
+            assert_eq!(format.to_string().parse::<StorageFormat>().unwrap(), format);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "pretty"))]
+    fn test_display_json_pretty_with() {
+        let tabs = StorageFormat::JsonPrettyWith(JsonIndent::Tabs);
+        let four_spaces = StorageFormat::JsonPrettyWith(JsonIndent::Spaces(4));
+
+        assert_eq!(tabs.to_string(), "json-pretty-with-tabs");
+        assert_eq!(four_spaces.to_string(), "json-pretty-with-4-spaces");
+
+        for format in [StorageFormat::JsonPretty, tabs, four_spaces] {
+            assert_eq!(format.to_string().parse::<StorageFormat>().unwrap(), format);
+        }
+
+        assert!(matches!(
+            "json-pretty-with-many-spaces".parse::<StorageFormat>(),
+            Err(PersistenceError::UnknownStorageFormat(name))
+                if name == "json-pretty-with-many-spaces",
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_reflect() {
+        use bevy::reflect::ReflectRef;
+
+        let format = StorageFormat::Toml;
+        match format.reflect_ref() {
+            ReflectRef::Enum(reflected) => assert_eq!(reflected.variant_name(), "Toml"),
+            _ => panic!("storage format is not reflected as an enum"),
+        }
+
+        let reflected = format.clone_value();
+        assert_eq!(StorageFormat::from_reflect(reflected.as_ref()), Some(StorageFormat::Toml));
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "path-to-error"))]
+    fn test_json_path_to_error() {
+        #[derive(Debug, Deserialize, Serialize)]
+        struct Audio {
+            volume: f64,
+        }
+
+        #[derive(Debug, Deserialize, Serialize)]
+        struct Settings {
+            audio: Audio,
+        }
+
+        let error = StorageFormat::Json
+            .deserialize::<Settings>("settings", br#"{ "audio": { "volume": "loud" } }"#)
+            .unwrap_err();
+
+        assert!(error.to_string().contains("audio.volume"));
+        assert_eq!(error.field_path(), Some("audio.volume"));
+        assert!(error.is_deserialization());
+        assert_eq!(error.format(), Some(StorageFormat::Json));
+    }
 }
 
 #[cfg(target_family = "wasm")]
@@ -263,6 +560,27 @@ mod wasm {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    #[cfg(feature = "bson")]
+    fn test_bson() -> anyhow::Result<()> {
+        let format = StorageFormat::Bson;
+        let resource = KeyBindings::default();
+
+        let actual_serialized_resource = format.serialize("key bindings", &resource).unwrap();
+        let expected_serialized_resource = bson::to_vec(&resource)?;
+
+        assert_eq!(actual_serialized_resource, expected_serialized_resource);
+
+        let actual_deserialized_resource =
+            format.deserialize::<KeyBindings>("key bindings", &actual_serialized_resource).unwrap();
+        let expected_deserialized_resource =
+            bson::from_slice::<KeyBindings>(&expected_serialized_resource)?;
+
+        assert_eq!(expected_deserialized_resource, actual_deserialized_resource);
+
+        Ok(())
+    }
+
     #[wasm_bindgen_test]
     #[cfg(feature = "ini")]
     fn test_ini() -> anyhow::Result<()> {
@@ -332,6 +650,30 @@ mod wasm {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    #[cfg(feature = "pickle")]
+    fn test_pickle() -> anyhow::Result<()> {
+        let format = StorageFormat::Pickle;
+        let resource = KeyBindings::default();
+
+        let actual_serialized_resource = format.serialize("key bindings", &resource).unwrap();
+        let expected_serialized_resource =
+            serde_pickle::to_vec(&resource, serde_pickle::SerOptions::new())?;
+
+        assert_eq!(actual_serialized_resource, expected_serialized_resource);
+
+        let actual_deserialized_resource =
+            format.deserialize::<KeyBindings>("key bindings", &actual_serialized_resource).unwrap();
+        let expected_deserialized_resource = serde_pickle::from_slice::<KeyBindings>(
+            &expected_serialized_resource,
+            serde_pickle::DeOptions::new(),
+        )?;
+
+        assert_eq!(expected_deserialized_resource, actual_deserialized_resource);
+
+        Ok(())
+    }
+
     #[wasm_bindgen_test]
     #[cfg(feature = "ron")]
     fn test_ron() -> anyhow::Result<()> {
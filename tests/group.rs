@@ -0,0 +1,239 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+
+    #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Resource, Serialize)]
+    pub struct Statistics {
+        pub deaths: u32,
+    }
+
+    #[test]
+    fn register() {
+        let mut group = PersistentGroup::new();
+
+        assert!(group.is_empty());
+
+        group.register::<KeyBindings>().register::<Statistics>().register::<KeyBindings>();
+
+        assert_eq!(group.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn persist_all() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let statistics_path = tempdir.path().join("statistics").join("statistics.toml");
+
+        let mut world = World::new();
+        world.insert_resource(
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&key_bindings_path)
+                .default(KeyBindings::default())
+                .build()?,
+        );
+        world.insert_resource(
+            Persistent::<Statistics>::builder()
+                .name("statistics")
+                .format(StorageFormat::Toml)
+                .path(&statistics_path)
+                .default(Statistics::default())
+                .build()?,
+        );
+
+        let mut group = PersistentGroup::new();
+        group.register::<KeyBindings>().register::<Statistics>();
+
+        std::fs::remove_dir_all(statistics_path.parent().unwrap())?;
+
+        let new_key_bindings = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        **world.resource_mut::<Persistent<KeyBindings>>() = new_key_bindings.clone();
+        world.resource_mut::<Persistent<Statistics>>().deaths = 3;
+
+        let results = group.persist_all(&world);
+        assert_eq!(results.len(), 2);
+
+        let (key_bindings_name, key_bindings_result) = &results[0];
+        assert_eq!(key_bindings_name, "key bindings");
+        assert!(key_bindings_result.is_ok());
+
+        let (statistics_name, statistics_result) = &results[1];
+        assert_eq!(statistics_name, "statistics");
+        assert!(statistics_result.is_err());
+
+        let expected_content = toml::to_string(&new_key_bindings)?;
+        let actual_content = std::fs::read_to_string(&key_bindings_path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+        assert!(!statistics_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn unload_all() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let statistics_path = tempdir.path().join("statistics.toml");
+
+        let mut world = World::new();
+        world.insert_resource(
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&key_bindings_path)
+                .default(KeyBindings::default())
+                .build()?,
+        );
+        world.insert_resource(
+            Persistent::<Statistics>::builder()
+                .name("statistics")
+                .format(StorageFormat::Toml)
+                .path(&statistics_path)
+                .default(Statistics::default())
+                .build()?,
+        );
+
+        let mut group = PersistentGroup::new();
+        group.register::<KeyBindings>().register::<Statistics>();
+
+        let new_key_bindings = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        **world.resource_mut::<Persistent<KeyBindings>>() = new_key_bindings.clone();
+
+        let new_statistics = Statistics { deaths: 3 };
+        **world.resource_mut::<Persistent<Statistics>>() = new_statistics.clone();
+
+        let results = group.unload_all(&mut world);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        assert!(world.resource::<Persistent<KeyBindings>>().is_unloaded());
+        assert!(world.resource::<Persistent<Statistics>>().is_unloaded());
+
+        let expected_key_bindings_content = toml::to_string(&new_key_bindings)?;
+        let actual_key_bindings_content = std::fs::read_to_string(&key_bindings_path)?;
+
+        assert_eq!(expected_key_bindings_content.trim(), actual_key_bindings_content.trim());
+
+        let expected_statistics_content = toml::to_string(&new_statistics)?;
+        let actual_statistics_content = std::fs::read_to_string(&statistics_path)?;
+
+        assert_eq!(expected_statistics_content.trim(), actual_statistics_content.trim());
+
+        let results = group.unload_all(&mut world);
+        assert!(results.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn reload_all() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let statistics_path = tempdir.path().join("statistics.toml");
+
+        let mut world = World::new();
+        world.insert_resource(
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&key_bindings_path)
+                .default(KeyBindings::default())
+                .build()?,
+        );
+        world.insert_resource(
+            Persistent::<Statistics>::builder()
+                .name("statistics")
+                .format(StorageFormat::Toml)
+                .path(&statistics_path)
+                .default(Statistics::default())
+                .loaded(false)
+                .build()?,
+        );
+
+        let mut group = PersistentGroup::new();
+        group.register::<KeyBindings>().register::<Statistics>();
+
+        let new_key_bindings = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        std::fs::write(&key_bindings_path, toml::to_string(&new_key_bindings)?)?;
+
+        let results = group.reload_all(&mut world);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        assert_eq!(world.resource::<Persistent<KeyBindings>>().get(), &new_key_bindings);
+        assert!(world.resource::<Persistent<Statistics>>().is_loaded());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn persistence_failed_event() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let statistics_path = tempdir.path().join("statistics").join("statistics.toml");
+
+        let mut world = World::new();
+        world.init_resource::<Events<PersistenceFailed>>();
+        world.insert_resource(
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&key_bindings_path)
+                .default(KeyBindings::default())
+                .build()?,
+        );
+        world.insert_resource(
+            Persistent::<Statistics>::builder()
+                .name("statistics")
+                .format(StorageFormat::Toml)
+                .path(&statistics_path)
+                .default(Statistics::default())
+                .build()?,
+        );
+
+        let mut group = PersistentGroup::new();
+        group.register::<KeyBindings>().register::<Statistics>();
+        world.insert_resource(group);
+
+        std::fs::remove_dir_all(statistics_path.parent().unwrap())?;
+
+        PersistentGroup::persist_all_system(&mut world);
+
+        let events = world
+            .resource::<Events<PersistenceFailed>>()
+            .iter_current_update_events()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+
+        let event = &events[0];
+        assert_eq!(event.name, "statistics");
+        assert_eq!(event.storage, Storage::Filesystem { path: statistics_path.clone() });
+        assert_eq!(event.operation, Operation::Persist);
+        assert!(!event.error.is_empty());
+
+        PersistentGroup::reload_all_system(&mut world);
+
+        let events = world
+            .resource::<Events<PersistenceFailed>>()
+            .iter_current_update_events()
+            .filter(|event| event.operation == Operation::Reload)
+            .count();
+        assert_eq!(events, 1);
+
+        Ok(())
+    }
+}
@@ -385,6 +385,3051 @@ mod native {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn quiet() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let (resource, logs) = capture_logs(|| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .quiet(true)
+                .build()
+        });
+        let mut resource = resource?;
+
+        assert!(resource.is_quiet());
+        assert!(!logs.contains("saved default key bindings"));
+
+        let (result, logs) = capture_logs(|| resource.persist());
+        result?;
+
+        assert!(!logs.contains("saved new key bindings"));
+
+        std::fs::write(&path, "invalid keybindings")?;
+
+        let (result, logs) = capture_logs(|| resource.reload());
+
+        assert!(result.is_err());
+        assert!(logs.contains("failed to parse key bindings as TOML"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn not_quiet() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let (resource, logs) = capture_logs(|| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .build()
+        });
+        let resource = resource?;
+
+        assert!(!resource.is_quiet());
+        assert!(logs.contains("saved default key bindings"));
+
+        let (result, logs) = capture_logs(|| resource.persist());
+        result?;
+
+        let length = std::fs::metadata(&path)?.len();
+        assert!(logs.contains(&format!(
+            "saved new key bindings to {} ({} bytes)",
+            resource.storage(),
+            length
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "toml", feature = "tracing"))]
+    fn tracing_fields() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let (resource, logs) = capture_logs(|| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .build()
+        });
+        let resource = resource?;
+
+        assert!(logs.contains("saved default key bindings"));
+        assert!(logs.contains("resource.name=key bindings"));
+        assert!(logs.contains(&format!("storage={}", resource.storage())));
+        assert!(logs.contains("format=toml"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn migrate_from_fallback() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let legacy_path = tempdir.path().join("legacy").join("key-bindings.toml");
+        let path = tempdir.path().join("new").join("key-bindings.toml");
+
+        let existing_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        let existing_content = toml::to_string(&existing_resource)?;
+
+        std::fs::create_dir_all(legacy_path.parent().unwrap())?;
+        std::fs::write(&legacy_path, &existing_content)?;
+
+        assert!(legacy_path.exists());
+        assert!(!path.exists());
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .fallback_path(&legacy_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert!(path.exists());
+
+        let expected_resource = existing_resource;
+        let actual_resource = resource.get();
+
+        assert_eq!(actual_resource, &expected_resource);
+
+        let expected_content = existing_content;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn ignore_fallback_when_occupied() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let legacy_path = tempdir.path().join("legacy-key-bindings.toml");
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let legacy_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        std::fs::write(&legacy_path, toml::to_string(&legacy_resource)?)?;
+
+        let existing_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::KeyX };
+        std::fs::write(&path, toml::to_string(&existing_resource)?)?;
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .fallback_path(&legacy_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert_eq!(resource.get(), &existing_resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn reload_if_changed() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let name = "key bindings";
+        let format = StorageFormat::Toml;
+        let storage = Storage::Filesystem { path: path.clone() };
+        let loaded = true;
+        let default = KeyBindings::default();
+        let revertible = false;
+        let revert_to_default_on_deserialization_errors = false;
+
+        let mut resource = Persistent::new(
+            name,
+            format,
+            storage,
+            loaded,
+            default,
+            revertible,
+            revert_to_default_on_deserialization_errors,
+        )?;
+
+        assert!(!resource.reload_if_changed()?);
+
+        let new_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        std::fs::write(&path, toml::to_string(&new_resource)?)?;
+
+        let modified = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+        std::fs::File::options().write(true).open(&path)?.set_modified(modified)?;
+
+        assert!(resource.reload_if_changed()?);
+        assert_eq!(resource.get(), &new_resource);
+
+        assert!(!resource.reload_if_changed()?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn do_not_create_if_missing() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings").join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .create_if_missing(false)
+            .build()?;
+
+        assert!(!path.exists());
+        assert!(!resource.creates_if_missing());
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        resource.crouch = KeyCode::ControlLeft;
+        resource.persist()?;
+
+        assert!(path.exists());
+
+        let expected_content = toml::to_string(resource.get())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn do_not_create_if_missing_when_not_loaded() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .loaded(false)
+            .default(KeyBindings::default())
+            .create_if_missing(false)
+            .build()?;
+
+        assert!(!path.exists());
+        assert!(!resource.is_loaded());
+        assert_eq!(resource.default(), Some(&KeyBindings::default()));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn clone_resource() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let name = "key bindings";
+        let format = StorageFormat::Toml;
+        let storage = Storage::Filesystem { path: path.clone() };
+        let loaded = true;
+        let default = KeyBindings::default();
+        let revertible = false;
+        let revert_to_default_on_deserialization_errors = false;
+
+        let mut resource = Persistent::new(
+            name,
+            format,
+            storage,
+            loaded,
+            default,
+            revertible,
+            revert_to_default_on_deserialization_errors,
+        )?;
+
+        let snapshot = resource.clone_resource()?;
+
+        assert_eq!(&snapshot, resource.get());
+
+        resource.crouch = KeyCode::ControlLeft;
+
+        assert_eq!(snapshot, KeyBindings::default());
+        assert_ne!(&snapshot, resource.get());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn deny_unknown_fields_json() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.json");
+
+        std::fs::write(&path, r#"{ "jump": "Space", "crouch": "KeyC", "jmup": "KeyJ" }"#)?;
+
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Json)
+                .path(&path)
+                .default(KeyBindings::default())
+        };
+
+        let resource = builder().build()?;
+        assert!(!resource.denies_unknown_fields());
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        let error = builder().deny_unknown_fields(true).build().unwrap_err();
+        assert!(error.is_deserialization());
+        assert!(matches!(
+            &error,
+            PersistenceError::UnknownFields(fields) if fields == &["jmup".to_owned()],
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn deny_unknown_fields_toml() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        std::fs::write(&path, "jump = \"Space\"\ncrouch = \"KeyC\"\ncrunch = \"KeyX\"\n")?;
+
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+        };
+
+        let resource = builder().build()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        let error = builder().deny_unknown_fields(true).build().unwrap_err();
+        assert!(matches!(
+            &error,
+            PersistenceError::UnknownFields(fields) if fields == &["crunch".to_owned()],
+        ));
+
+        std::fs::write(&path, toml::to_string(&KeyBindings::default())?)?;
+
+        let resource = builder().deny_unknown_fields(true).build()?;
+        assert!(resource.denies_unknown_fields());
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn persist_to() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let export_path = tempdir.path().join("exports").join("key-bindings.json");
+
+        let name = "key bindings";
+        let format = StorageFormat::Toml;
+        let storage = Storage::Filesystem { path: path.clone() };
+        let loaded = true;
+        let default = KeyBindings::default();
+        let revertible = false;
+        let revert_to_default_on_deserialization_errors = false;
+
+        let mut resource = Persistent::new(
+            name,
+            format,
+            storage.clone(),
+            loaded,
+            default,
+            revertible,
+            revert_to_default_on_deserialization_errors,
+        )?;
+
+        resource.crouch = KeyCode::ControlLeft;
+
+        let export_storage = Storage::Filesystem { path: export_path.clone() };
+        resource.persist_to(&export_storage, StorageFormat::Json)?;
+
+        assert_eq!(resource.format(), format);
+        assert_eq!(resource.storage(), &storage);
+
+        let expected_export_content = serde_json::to_string(resource.get())?;
+        let actual_export_content = std::fs::read_to_string(&export_path)?;
+
+        assert_eq!(expected_export_content.trim(), actual_export_content.trim());
+
+        let expected_content = toml::to_string(&KeyBindings::default())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn persist_as() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.crouch = KeyCode::ControlLeft;
+        resource.persist_as(StorageFormat::Json)?;
+
+        assert_eq!(resource.format(), StorageFormat::Toml);
+        assert_eq!(
+            serde_json::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            *resource.get(),
+        );
+
+        resource.persist()?;
+
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            *resource.get(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn load_from() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let import_path = tempdir.path().join("key-bindings.json");
+
+        let name = "key bindings";
+        let format = StorageFormat::Toml;
+        let storage = Storage::Filesystem { path: path.clone() };
+        let loaded = true;
+        let default = KeyBindings::default();
+        let revertible = false;
+        let revert_to_default_on_deserialization_errors = false;
+
+        let mut resource = Persistent::new(
+            name,
+            format,
+            storage.clone(),
+            loaded,
+            default,
+            revertible,
+            revert_to_default_on_deserialization_errors,
+        )?;
+
+        let imported = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        std::fs::write(&import_path, serde_json::to_string(&imported)?)?;
+
+        let import_storage = Storage::Filesystem { path: import_path };
+        resource.load_from(&import_storage, StorageFormat::Json)?;
+
+        assert_eq!(resource.format(), format);
+        assert_eq!(resource.storage(), &storage);
+        assert_eq!(resource.get(), &imported);
+
+        let expected_content = toml::to_string(&imported)?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn set_storage() -> anyhow::Result<()> {
+        let old_tempdir = tempfile::tempdir()?;
+        let new_tempdir = tempfile::tempdir()?;
+
+        let old_path = old_tempdir.path().join("key-bindings.toml");
+        let new_path = new_tempdir.path().join("saves").join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&old_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert!(old_path.exists());
+        assert!(!new_path.exists());
+
+        let new_storage = Storage::Filesystem { path: new_path.clone() };
+        resource.set_storage(new_storage.clone(), true)?;
+
+        assert!(!old_path.exists());
+        assert!(new_path.exists());
+        assert_eq!(resource.storage(), &new_storage);
+
+        resource.update(|key_bindings| key_bindings.crouch = KeyCode::ControlLeft)?;
+
+        assert!(!old_path.exists());
+
+        let expected_content = toml::to_string(resource.get())?;
+        let actual_content = std::fs::read_to_string(&new_path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn set_storage_keep_old() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let old_path = tempdir.path().join("key-bindings.toml");
+        let new_path = tempdir.path().join("key-bindings-copy.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&old_path)
+            .default(KeyBindings::default())
+            .loaded(false)
+            .build()?;
+
+        resource.set_storage(Storage::Filesystem { path: new_path.clone() }, false)?;
+
+        assert!(old_path.exists());
+        assert!(new_path.exists());
+        assert!(resource.is_unloaded());
+
+        assert_eq!(std::fs::read_to_string(&old_path)?, std::fs::read_to_string(&new_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn get_or_load_empty_storage() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .loaded(false)
+            .revertible(true)
+            .create_if_missing(false)
+            .build()?;
+
+        assert!(!path.exists());
+        assert!(resource.is_unloaded());
+
+        assert_eq!(resource.get_or_load()?, &KeyBindings::default());
+        assert!(resource.is_loaded());
+
+        let expected_content = toml::to_string(&KeyBindings::default())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn get_or_load_empty_storage_with_retained_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .loaded(false)
+            .retain_default(true)
+            .build()?;
+
+        assert!(!resource.is_revertible());
+        assert!(resource.is_unloaded());
+
+        assert_eq!(resource.get_or_load()?, &KeyBindings::default());
+        assert!(resource.is_loaded());
+
+        let expected_content = toml::to_string(&KeyBindings::default())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn get_or_load_populated_storage() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let existing_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        std::fs::write(&path, toml::to_string(&existing_resource)?)?;
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .loaded(false)
+            .build()?;
+
+        assert!(resource.is_unloaded());
+
+        assert_eq!(resource.get_or_load()?, &existing_resource);
+        assert!(resource.is_loaded());
+
+        resource.crouch = KeyCode::KeyC;
+        assert_eq!(resource.get_or_load()?.crouch, KeyCode::KeyC);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn snapshot_to() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let snapshot_path = tempdir.path().join("snapshots").join("key-bindings.toml");
+
+        let content = "# hand edited\njump = \"Space\"\ncrouch = \"ControlLeft\"\n";
+        std::fs::write(&path, content)?;
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.crouch = KeyCode::KeyC;
+        resource.snapshot_to(&Storage::Filesystem { path: snapshot_path.clone() })?;
+
+        assert_eq!(std::fs::read_to_string(&snapshot_path)?, content);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn reload_or_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .revertible(true)
+            .build()?;
+
+        resource.set(KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft })?;
+
+        std::fs::write(&path, "this is not toml {")?;
+
+        resource.reload_or_default()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        let expected_content = toml::to_string(&KeyBindings::default())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        std::fs::write(&path, "this is not toml {")?;
+
+        resource.unload_without_persisting();
+        resource.reload_or_default()?;
+
+        assert!(resource.is_loaded());
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn reload_or_default_without_retained_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let new_key_bindings = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        resource.set(new_key_bindings.clone())?;
+
+        std::fs::write(&path, "this is not toml {")?;
+
+        let error = resource.reload_or_default().err().unwrap();
+        assert!(error.is_deserialization());
+        match error {
+            PersistenceError::RecoveryFailed { primary, during_recovery } => {
+                assert!(matches!(*primary, PersistenceError::TomlDeserialization(_)));
+                assert!(matches!(*during_recovery, PersistenceError::DefaultNotRetained));
+            },
+            error => panic!("unexpected error {:?}", error),
+        }
+
+        assert_eq!(resource.get(), &new_key_bindings);
+        assert_eq!(std::fs::read_to_string(&path)?, "this is not toml {");
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ron")]
+    fn ron_options() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct AudioSettings {
+            volume: Option<u8>,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("audio-settings.ron");
+
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+
+        let mut resource = Persistent::<AudioSettings>::builder()
+            .name("audio settings")
+            .format(StorageFormat::Ron)
+            .path(&path)
+            .default(AudioSettings::default())
+            .ron_options(options)
+            .build()?;
+
+        resource.set(AudioSettings { volume: Some(5) })?;
+
+        let content = std::fs::read_to_string(&path)?;
+
+        assert!(content.contains("volume:5"));
+        assert!(!content.contains("Some"));
+
+        resource.reload()?;
+        assert_eq!(resource.get(), &AudioSettings { volume: Some(5) });
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn change_format() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let toml_path = tempdir.path().join("key-bindings.toml");
+        let json_path = tempdir.path().join("key-bindings.json");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&toml_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let new_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        resource.change_format(StorageFormat::Json)?;
+
+        assert_eq!(resource.format(), StorageFormat::Json);
+        assert_eq!(resource.storage(), &Storage::Filesystem { path: json_path.clone() });
+        assert_eq!(resource.get(), &new_resource);
+
+        assert!(!toml_path.exists());
+        assert!(json_path.exists());
+
+        let content = std::fs::read_to_string(&json_path)?;
+        assert_eq!(serde_json::from_str::<KeyBindings>(&content)?, new_resource);
+
+        resource.reload()?;
+        assert_eq!(resource.get(), &new_resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn change_format_hooks() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let toml_path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&toml_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let persisted = Arc::new(Mutex::new(Vec::new()));
+        {
+            let persisted = persisted.clone();
+            resource.on_persisted(move |key_bindings: &KeyBindings| {
+                persisted.lock().unwrap().push(key_bindings.clone());
+            });
+        }
+
+        // changes which are not persisted are not converted
+        resource.jump = KeyCode::KeyJ;
+        resource.change_format(StorageFormat::Json)?;
+
+        assert_eq!(*persisted.lock().unwrap(), [KeyBindings::default()]);
+        assert_eq!(resource.jump, KeyCode::KeyJ);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn change_format_empty_storage() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let toml_path = tempdir.path().join("key-bindings.toml");
+        let json_path = tempdir.path().join("key-bindings.json");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&toml_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        std::fs::remove_file(&toml_path)?;
+
+        resource.jump = KeyCode::KeyJ;
+        resource.change_format(StorageFormat::Json)?;
+
+        assert_eq!(resource.format(), StorageFormat::Json);
+        assert!(!toml_path.exists());
+
+        let content = std::fs::read_to_string(&json_path)?;
+        assert_eq!(serde_json::from_str::<KeyBindings>(&content)?, *resource.get());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn change_format_failure() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let toml_path = tempdir.path().join("key-bindings.toml");
+        let json_path = tempdir.path().join("key-bindings.json");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&toml_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        // a directory in place of the new file makes converting fail
+        std::fs::create_dir(&json_path)?;
+
+        assert!(resource.change_format(StorageFormat::Json).is_err());
+
+        assert_eq!(resource.format(), StorageFormat::Toml);
+        assert_eq!(resource.storage(), &Storage::Filesystem { path: toml_path.clone() });
+        assert!(toml_path.exists());
+
+        resource.reload()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn hooks() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let existing_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::ControlLeft };
+        std::fs::write(&path, toml::to_string(&existing_resource)?)?;
+
+        let loads = Arc::new(Mutex::new(0));
+        let saves = Arc::new(Mutex::new(Vec::new()));
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .on_load({
+                let loads = loads.clone();
+                move |key_bindings| {
+                    *loads.lock().unwrap() += 1;
+                    key_bindings.jump = KeyCode::KeyJ;
+                }
+            })
+            .on_save({
+                let saves = saves.clone();
+                move |key_bindings| saves.lock().unwrap().push(key_bindings.clone())
+            })
+            .build()?;
+
+        assert_eq!(*loads.lock().unwrap(), 1);
+        assert_eq!(
+            resource.get(),
+            &KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft }
+        );
+
+        resource.reload()?;
+
+        assert_eq!(*loads.lock().unwrap(), 2);
+        assert_eq!(resource.jump, KeyCode::KeyJ);
+
+        resource.update(|key_bindings| key_bindings.crouch = KeyCode::KeyC)?;
+
+        assert_eq!(
+            *saves.lock().unwrap(),
+            vec![KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::KeyC }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn get_cloned() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.crouch = KeyCode::ControlLeft;
+
+        assert_eq!(&resource.get_cloned(), resource.get());
+        assert_eq!(resource.try_get_cloned().as_ref(), resource.try_get());
+
+        resource.unload_without_persisting();
+        assert_eq!(resource.try_get_cloned(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn write_to() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.crouch = KeyCode::ControlLeft;
+
+        let mut buffer = Vec::new();
+        resource.write_to(&mut buffer)?;
+
+        assert_eq!(buffer, toml::to_string(resource.get())?.into_bytes());
+
+        let expected_content = toml::to_string(&KeyBindings::default())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn read_from() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let imported = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        let content = toml::to_string(&imported)?;
+
+        resource.read_from(&mut std::io::Cursor::new(content.as_bytes()))?;
+
+        assert_eq!(resource.get(), &imported);
+        assert_eq!(std::fs::read_to_string(&path)?.trim(), content.trim());
+
+        let result = resource.read_from(&mut "this is not toml {".as_bytes());
+
+        assert!(result.is_err());
+        assert_eq!(resource.get(), &imported);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn validator() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let invalid_resource = KeyBindings { jump: KeyCode::Space, crouch: KeyCode::Space };
+        std::fs::write(&path, toml::to_string(&invalid_resource)?)?;
+
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .validator(|key_bindings| {
+                    if key_bindings.jump == key_bindings.crouch {
+                        Err("jump and crouch are bound to the same key".to_owned())
+                    } else {
+                        Ok(())
+                    }
+                })
+        };
+
+        let error = builder().build().unwrap_err();
+        assert!(matches!(error, PersistenceError::Validation(_)));
+        assert!(error.is_serde());
+        assert!(error.is_deserialization());
+
+        let resource =
+            builder().revertible(true).revert_to_default_on_deserialization_errors(true).build()?;
+
+        assert_eq!(resource.get(), &KeyBindings::default());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            KeyBindings::default(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn append() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Analytics {
+            events: Vec<String>,
+        }
+
+        impl Merge for Analytics {
+            fn merge(&mut self, record: Analytics) {
+                self.events.extend(record.events);
+            }
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("analytics.json");
+
+        let mut resource = Persistent::<Analytics>::builder()
+            .name("analytics")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Analytics::default())
+            .append(true)
+            .build()?;
+
+        for event in ["started", "jumped", "quit"] {
+            resource.set(Analytics { events: vec![event.to_owned()] })?;
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        assert_eq!(content.lines().filter(|line| !line.is_empty()).count(), 4);
+
+        resource.reload()?;
+
+        assert_eq!(
+            resource.get(),
+            &Analytics { events: vec!["started".into(), "jumped".into(), "quit".into()] },
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn append_after_reload() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Analytics {
+            events: Vec<String>,
+        }
+
+        impl Merge for Analytics {
+            fn merge(&mut self, record: Analytics) {
+                self.events.extend(record.events);
+            }
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("analytics.json");
+
+        let mut resource = Persistent::<Analytics>::builder()
+            .name("analytics")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Analytics::default())
+            .append(true)
+            .build()?;
+
+        for event in ["started", "jumped"] {
+            resource.set(Analytics { events: vec![event.to_owned()] })?;
+        }
+        resource.reload()?;
+
+        resource.update(|analytics| analytics.events.push("quit".to_owned()))?;
+
+        let content = std::fs::read_to_string(&path)?;
+        assert_eq!(content.lines().filter(|line| !line.is_empty()).count(), 1);
+
+        resource.set(Analytics { events: vec!["restarted".to_owned()] })?;
+
+        let content = std::fs::read_to_string(&path)?;
+        assert_eq!(content.lines().filter(|line| !line.is_empty()).count(), 2);
+
+        resource.reload()?;
+
+        assert_eq!(
+            resource.get(),
+            &Analytics {
+                events: vec!["started".into(), "jumped".into(), "quit".into(), "restarted".into(),],
+            },
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn touch_in_append_mode() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Analytics {
+            events: Vec<String>,
+        }
+
+        impl Merge for Analytics {
+            fn merge(&mut self, record: Analytics) {
+                self.events.extend(record.events);
+            }
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("analytics.json");
+
+        let mut resource = Persistent::<Analytics>::builder()
+            .name("analytics")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Analytics::default())
+            .append(true)
+            .build()?;
+
+        for event in ["started", "jumped"] {
+            resource.set(Analytics { events: vec![event.to_owned()] })?;
+        }
+        resource.touch()?;
+
+        let content = std::fs::read_to_string(&path)?;
+        assert_eq!(content.lines().filter(|line| !line.is_empty()).count(), 1);
+
+        resource.reload()?;
+        assert_eq!(resource.get(), &Analytics { events: vec!["started".into(), "jumped".into()] });
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    #[should_panic(expected = "append mode is not supported for toml storage format")]
+    fn append_unsupported_format() {
+        #[derive(Default, Deserialize, Resource, Serialize)]
+        struct Analytics {
+            events: Vec<String>,
+        }
+
+        impl Merge for Analytics {
+            fn merge(&mut self, record: Analytics) {
+                self.events.extend(record.events);
+            }
+        }
+
+        Persistent::<Analytics>::builder()
+            .name("analytics")
+            .format(StorageFormat::Toml)
+            .path("analytics.toml")
+            .default(Analytics::default())
+            .append(true)
+            .build()
+            .ok();
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn fsync() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .fsync(true)
+            .build()?;
+
+        assert!(resource.fsyncs());
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, new_resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn comparison() -> anyhow::Result<()> {
+        use std::hash::BuildHasher;
+
+        let tempdir = tempfile::tempdir()?;
+
+        let build = |file: &str| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(tempdir.path().join(file))
+                .default(KeyBindings::default())
+                .build()
+        };
+
+        let mut first = build("first.toml")?;
+        let mut second = build("second.toml")?;
+
+        assert_eq!(first, second);
+
+        let hasher = std::collections::hash_map::RandomState::new();
+        assert_eq!(hasher.hash_one(&first), hasher.hash_one(&second));
+        assert_eq!(hasher.hash_one(&first), hasher.hash_one(KeyBindings::default()));
+
+        second.update(|key_bindings| key_bindings.jump = KeyCode::KeyJ)?;
+        assert_ne!(first, second);
+
+        first.unload_without_persisting();
+        assert_ne!(first, second);
+
+        second.unload_without_persisting();
+        assert_eq!(first, second);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn as_ref() -> anyhow::Result<()> {
+        use std::borrow::Borrow;
+
+        fn jump_key(key_bindings: impl AsRef<KeyBindings>) -> KeyCode {
+            key_bindings.as_ref().jump
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let borrowed: &KeyBindings = resource.borrow();
+        assert_eq!(borrowed, &KeyBindings::default());
+
+        assert_eq!(jump_key(&resource), KeyBindings::default().jump);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn preview() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.jump = KeyCode::KeyJ;
+
+        let preview = resource.preview()?;
+        assert_ne!(std::fs::read(&path)?, preview);
+
+        resource.persist()?;
+        assert_eq!(std::fs::read(&path)?, preview);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn set_name() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.set_name("key bindings of alice");
+        assert_eq!(resource.name(), "key bindings of alice");
+
+        let (result, logs) = capture_logs(|| resource.persist());
+        result?;
+
+        assert!(logs.contains("saved new key bindings of alice"));
+
+        std::fs::write(&path, "invalid keybindings")?;
+
+        let (result, logs) = capture_logs(|| resource.reload());
+
+        assert!(result.is_err());
+        assert!(logs.contains("failed to parse key bindings of alice as TOML"));
+
+        assert_eq!(resource.storage(), &Storage::Filesystem { path });
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn diff() -> anyhow::Result<()> {
+        #[derive(Default, Deserialize, Resource, Serialize)]
+        struct Settings {
+            audio: AudioSettings,
+            difficulty: u8,
+        }
+
+        #[derive(Default, Deserialize, Serialize)]
+        struct AudioSettings {
+            volume: u8,
+            muted: bool,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings.json");
+
+        let mut resource = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Settings::default())
+            .build()?;
+
+        assert!(resource.diff()?.is_empty());
+
+        resource.audio.volume = 50;
+        assert_eq!(resource.diff()?, vec!["/audio/volume".to_owned()]);
+
+        resource.persist()?;
+        assert!(resource.diff()?.is_empty());
+
+        resource.difficulty = 3;
+        resource.audio.muted = true;
+        assert_eq!(resource.diff()?, vec!["/audio/muted".to_owned(), "/difficulty".to_owned()]);
+
+        resource.reload()?;
+        assert!(resource.diff()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn to_builder() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let sibling_path = tempdir.path().join("sibling-key-bindings.toml");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .revertible(true)
+            .revert_to_default_on_deserialization_errors(true)
+            .quiet(true)
+            .persist_on_drop(true)
+            .build()?;
+
+        let mut sibling = resource.to_builder().path(&sibling_path).build()?;
+        assert!(!sibling.persists_on_drop());
+
+        assert!(sibling_path.exists());
+
+        assert_eq!(sibling.name(), resource.name());
+        assert_eq!(sibling.format(), resource.format());
+        assert_eq!(sibling.storage(), &Storage::Filesystem { path: sibling_path.clone() });
+        assert_eq!(sibling.is_revertible(), resource.is_revertible());
+        assert_eq!(sibling.is_quiet(), resource.is_quiet());
+        assert_eq!(sibling.get(), resource.get());
+
+        std::fs::write(&sibling_path, "invalid key bindings")?;
+
+        sibling.reload()?;
+        assert_eq!(sibling.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn check_free_space() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .check_free_space(true)
+            .build()?;
+
+        assert!(resource.checks_free_space());
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, new_resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn unload_with() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.jump = KeyCode::KeyJ;
+        resource.unload_with(false)?;
+
+        assert!(resource.is_unloaded());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?.jump,
+            KeyCode::Space
+        );
+
+        resource.reload()?;
+        resource.jump = KeyCode::KeyJ;
+        resource.unload_with(true)?;
+
+        assert!(resource.is_unloaded());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?.jump,
+            KeyCode::KeyJ
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn unload_with_persist_failure() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        // make writes fail by replacing the file with a directory
+        std::fs::remove_file(&path)?;
+        std::fs::create_dir(&path)?;
+
+        resource.jump = KeyCode::KeyJ;
+
+        assert!(resource.unload_with(true).is_err());
+        assert!(resource.is_loaded());
+        assert_eq!(resource.jump, KeyCode::KeyJ);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn load_bytes() -> anyhow::Result<()> {
+        const BASELINE: &[u8] = b"jump = \"KeyJ\"\ncrouch = \"ControlLeft\"\n";
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.load_bytes(BASELINE)?;
+
+        let expected_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        assert_eq!(resource.get(), &expected_resource);
+
+        let expected_content = toml::to_string(&expected_resource)?;
+        assert_eq!(std::fs::read_to_string(&path)?.trim(), expected_content.trim());
+
+        let result = resource.load_bytes(b"this is not toml {");
+
+        assert!(result.is_err());
+        assert_eq!(resource.get(), &expected_resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn recovery_failed() -> anyhow::Result<()> {
+        #[derive(Deserialize, Resource, Serialize)]
+        struct Scores {
+            map: std::collections::HashMap<(u32, u32), u32>,
+        }
+
+        impl Default for Scores {
+            fn default() -> Scores {
+                // tuple keys cannot be serialized as JSON
+                Scores { map: std::collections::HashMap::from([((1, 2), 3)]) }
+            }
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("scores.json");
+
+        let builder = || {
+            Persistent::<Scores>::builder()
+                .name("scores")
+                .format(StorageFormat::Json)
+                .path(&path)
+                .default(Scores::default())
+                .revertible(true)
+                .revert_to_default_on_deserialization_errors(true)
+        };
+
+        let assert_recovery_failed = |error: PersistenceError| {
+            assert!(error.is_deserialization());
+            match error {
+                PersistenceError::RecoveryFailed { primary, during_recovery } => {
+                    assert!(matches!(*primary, PersistenceError::JsonDeserialization(_)));
+                    assert!(matches!(*during_recovery, PersistenceError::JsonSerialization(_)));
+                },
+                error => panic!("unexpected error {:?}", error),
+            }
+        };
+
+        std::fs::write(&path, "invalid scores")?;
+        assert_recovery_failed(builder().build().err().unwrap());
+
+        std::fs::write(&path, r#"{"map":{}}"#)?;
+        let mut resource = builder().build()?;
+
+        std::fs::write(&path, "invalid scores")?;
+        assert_recovery_failed(resource.reload().unwrap_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn info() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Reflect, Resource, Serialize)]
+        struct Statistics {
+            deaths: u32,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("statistics.toml");
+
+        let mut resource = Persistent::<Statistics>::builder()
+            .name("statistics")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(Statistics::default())
+            .build()?;
+
+        let info = resource.info();
+        assert_eq!(
+            info,
+            PersistentInfo {
+                name: "statistics".to_owned(),
+                format: StorageFormat::Toml,
+                storage: Storage::Filesystem { path: path.clone() },
+                fallback_storage: None,
+                loaded: true,
+                revertible: false,
+            },
+        );
+        assert_eq!(info.path::<String>("name").ok(), Some(&"statistics".to_owned()));
+
+        *resource.try_get_reflect_mut().unwrap().path_mut::<u32>("deaths").unwrap() = 3;
+        assert_eq!(resource.try_get_reflect().unwrap().path::<u32>("deaths").ok(), Some(&3));
+        assert_eq!(resource.deaths, 3);
+
+        resource.unload_without_persisting();
+        assert!(resource.try_get_reflect().is_none());
+        assert!(!resource.info().loaded);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn export_pretty() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.bin");
+        let export_path = tempdir.path().join("bug-report").join("key-bindings.json");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Bincode)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let result = resource.export_pretty(&export_path);
+
+        #[cfg(all(feature = "json", feature = "pretty"))]
+        {
+            result?;
+
+            let expected_content = serde_json::to_string_pretty(resource.get())?;
+            let actual_content = std::fs::read_to_string(&export_path)?;
+
+            assert_eq!(expected_content.trim(), actual_content.trim());
+        }
+        #[cfg(not(all(feature = "json", feature = "pretty")))]
+        {
+            assert!(matches!(
+                result,
+                Err(PersistenceError::DisabledStorageFormat(name)) if name == "json-pretty",
+            ));
+            assert!(!export_path.exists());
+        }
+
+        assert_eq!(resource.format(), StorageFormat::Bincode);
+        assert_eq!(std::fs::read(&path)?, bincode::serialize(resource.get())?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn create_dirs() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let directory = tempdir.path().join("config").join("input");
+        let path = directory.join("key-bindings.toml");
+
+        let builder = |create_dirs| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .create_dirs(create_dirs)
+        };
+
+        let result = builder(false).build();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::MissingDirectory(missing)) if missing == directory,
+        ));
+        assert!(!tempdir.path().join("config").exists());
+
+        let resource = builder(true).build()?;
+        assert!(resource.creates_dirs());
+        assert!(path.exists());
+
+        std::fs::remove_file(&path)?;
+
+        let resource = builder(false).build()?;
+        assert!(!resource.creates_dirs());
+        assert!(path.exists());
+
+        std::fs::remove_dir_all(&directory)?;
+
+        let result = resource.persist();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::MissingDirectory(missing)) if missing == directory,
+        ));
+        assert!(!directory.exists());
+
+        let snapshot =
+            Storage::Filesystem { path: tempdir.path().join("snapshots").join("1.toml") };
+        assert!(matches!(
+            resource.snapshot_to(&snapshot),
+            Err(PersistenceError::MissingDirectory(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn patch_json() -> anyhow::Result<()> {
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Audio {
+            volume: f64,
+            muted: bool,
+        }
+
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Settings {
+            audio: Audio,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings.toml");
+
+        let mut resource = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(Settings::default())
+            .validator(|settings: &Settings| {
+                if (0.0..=1.0).contains(&settings.audio.volume) {
+                    Ok(())
+                } else {
+                    Err("volume is out of range".to_owned())
+                }
+            })
+            .build()?;
+
+        resource.patch_json("/audio/volume", serde_json::json!(0.5))?;
+
+        let expected_resource = Settings { audio: Audio { volume: 0.5, muted: false } };
+        assert_eq!(resource.get(), &expected_resource);
+
+        let expected_content = toml::to_string(&expected_resource)?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        assert!(matches!(
+            resource.patch_json("/audio/volume", serde_json::json!(2.0)),
+            Err(PersistenceError::Validation(_)),
+        ));
+        assert!(matches!(
+            resource.patch_json("/audio/volume", serde_json::json!("loud")),
+            Err(PersistenceError::JsonDeserialization(_)),
+        ));
+        assert!(matches!(
+            resource.patch_json("/video/brightness", serde_json::json!(1.0)),
+            Err(PersistenceError::InvalidJsonPointer(pointer)) if pointer == "/video/brightness",
+        ));
+
+        assert_eq!(resource.get(), &expected_resource);
+        assert_eq!(std::fs::read_to_string(&path)?.trim(), expected_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn get_or_insert_with() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .loaded(false)
+            .revertible(false)
+            .create_if_missing(false)
+            .build()?;
+
+        assert!(!path.exists());
+        assert!(resource.is_unloaded());
+        assert!(!resource.is_revertible());
+
+        let inserted_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        assert_eq!(resource.get_or_insert_with(|| inserted_resource.clone())?, &inserted_resource);
+        assert!(resource.is_loaded());
+
+        let expected_content = toml::to_string(&inserted_resource)?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        resource.unload_without_persisting();
+        assert_eq!(
+            resource.get_or_insert_with(|| panic!("storage is not empty"))?,
+            &inserted_resource,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn line_ending() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let builder = |file_name: &str, line_ending| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(tempdir.path().join(file_name))
+                .default(KeyBindings::default())
+                .line_ending(line_ending)
+        };
+
+        let resource = builder("lf.toml", LineEnding::Lf).build()?;
+        assert_eq!(resource.line_ending(), Some(LineEnding::Lf));
+
+        let content = std::fs::read_to_string(tempdir.path().join("lf.toml"))?;
+        assert!(content.contains('\n'));
+        assert!(!content.contains('\r'));
+
+        let mut resource = builder("crlf.toml", LineEnding::CrLf).build()?;
+        assert_eq!(resource.line_ending(), Some(LineEnding::CrLf));
+
+        let content = std::fs::read_to_string(tempdir.path().join("crlf.toml"))?;
+        assert_eq!(content.matches('\n').count(), content.matches("\r\n").count());
+        assert_eq!(content.replace("\r\n", "\n"), toml::to_string(&KeyBindings::default())?);
+
+        resource.reload()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        builder("native.toml", LineEnding::Native).build()?;
+
+        let content = std::fs::read_to_string(tempdir.path().join("native.toml"))?;
+        assert_eq!(content.contains("\r\n"), cfg!(windows));
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(tempdir.path().join("default.toml"))
+            .default(KeyBindings::default())
+            .build()?;
+        assert_eq!(resource.line_ending(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn on_persisted() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let persisted = Arc::new(Mutex::new(Vec::new()));
+        {
+            let persisted = persisted.clone();
+            resource.on_persisted(move |key_bindings: &KeyBindings| {
+                persisted.lock().unwrap().push(key_bindings.clone());
+            });
+        }
+        let receiver = resource.persisted_receiver();
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.jump = KeyCode::KeyJ;
+        resource.crouch = KeyCode::ControlLeft;
+        resource.persist()?;
+
+        assert_eq!(*persisted.lock().unwrap(), std::slice::from_ref(&new_resource));
+        assert_eq!(receiver.try_recv()?, new_resource);
+
+        // a directory in place of the file makes saving fail
+        std::fs::remove_file(&path)?;
+        std::fs::create_dir(&path)?;
+
+        assert!(resource.persist().is_err());
+
+        assert_eq!(persisted.lock().unwrap().len(), 1);
+        assert!(receiver.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml-edit")]
+    fn toml_config() -> anyhow::Result<()> {
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Audio {
+            volume: f64,
+            muted: bool,
+        }
+
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Settings {
+            audio: Audio,
+            aliases: std::collections::HashMap<String, String>,
+        }
+
+        let settings = || {
+            let aliases = ["w", "a", "s", "d", "q", "e"]
+                .into_iter()
+                .map(|key| (key.to_owned(), key.to_uppercase()))
+                .collect();
+            Settings { audio: Audio { volume: 0.5, muted: false }, aliases }
+        };
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings.toml");
+
+        let builder = |default| {
+            Persistent::<Settings>::builder()
+                .name("settings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(default)
+                .toml_config(TomlConfig { inline_table_max_keys: 2, sort_keys: true })
+        };
+
+        let resource = builder(settings()).build()?;
+        let content = std::fs::read(&path)?;
+
+        assert!(
+            String::from_utf8(content.clone())?.contains("audio = { muted = false, volume = 0.5 }"),
+        );
+
+        for _ in 0..3 {
+            // each map has a different iteration order
+            std::fs::remove_file(&path)?;
+            builder(settings()).build()?;
+            assert_eq!(std::fs::read(&path)?, content);
+
+            resource.persist()?;
+            assert_eq!(std::fs::read(&path)?, content);
+        }
+
+        let mut resource = builder(Settings::default()).build()?;
+        assert_eq!(resource.get(), &settings());
+
+        resource.reload()?;
+        assert_eq!(resource.get(), &settings());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn warn_if_extension_mismatch() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let builder = |file_name: &str| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(tempdir.path().join(file_name))
+                .default(KeyBindings::default())
+        };
+
+        let mismatched = builder("key-bindings.json").build()?;
+        let (mismatch, logs) = capture_logs(|| mismatched.warn_if_extension_mismatch());
+
+        assert!(mismatch);
+        assert!(logs.contains("WARN"));
+        assert!(logs.contains("doesn't match the conventional extension of toml"));
+
+        let matched = builder("key-bindings.TOML").build()?;
+        let (mismatch, logs) = capture_logs(|| matched.warn_if_extension_mismatch());
+
+        assert!(!mismatch);
+        assert!(!logs.contains("doesn't match"));
+
+        let extensionless = builder("key-bindings").build()?;
+        assert!(!extensionless.warn_if_extension_mismatch());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn persist_on_drop() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let builder = |persist_on_drop| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .persist_on_drop(persist_on_drop)
+        };
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        {
+            let mut resource = builder(true).build()?;
+            assert!(resource.persists_on_drop());
+
+            resource.jump = KeyCode::KeyJ;
+            resource.crouch = KeyCode::ControlLeft;
+        }
+
+        let expected_content = toml::to_string(&new_resource)?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        {
+            let mut resource = builder(false).build()?;
+            assert!(!resource.persists_on_drop());
+
+            resource.jump = KeyCode::Space;
+        }
+        assert_eq!(std::fs::read_to_string(&path)?.trim(), expected_content.trim());
+
+        {
+            let mut resource = builder(true).build()?;
+            resource.jump = KeyCode::Space;
+            resource.unload_without_persisting();
+        }
+        assert_eq!(std::fs::read_to_string(&path)?.trim(), expected_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn flush() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.crouch = KeyCode::ControlLeft;
+        resource.flush()?;
+
+        let expected_content = toml::to_string(resource.get())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn max_read_bytes() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+        };
+
+        let resource = builder().build()?;
+        assert_eq!(resource.max_read_bytes(), None);
+
+        let size = std::fs::metadata(&path)?.len();
+        std::fs::write(
+            &path,
+            format!("{}\n{}", "#".repeat(1024), std::fs::read_to_string(&path)?),
+        )?;
+
+        let result = builder().max_read_bytes(size).build();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::TooLarge { size: actual, limit }) if actual == size + 1025 && limit == size,
+        ));
+
+        let result = builder().format(StorageFormat::Auto).max_read_bytes(size).build();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::TooLarge { size: actual, limit }) if actual == size + 1025 && limit == size,
+        ));
+
+        let resource = builder().max_read_bytes(size + 1025).build()?;
+        assert_eq!(resource.max_read_bytes(), Some(size + 1025));
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn touch() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .loaded(false)
+            .build()?;
+
+        std::fs::write(&path, "  crouch='KeyX'\n\n\njump   =    \"Space\"   # jump\n")?;
+
+        resource.touch()?;
+
+        let expected = KeyBindings { crouch: KeyCode::KeyX, ..KeyBindings::default() };
+        assert!(resource.is_loaded());
+        assert_eq!(resource.get(), &expected);
+
+        let canonical = StorageFormat::Toml.serialize("key bindings", &expected)?;
+        assert_eq!(std::fs::read(&path)?, canonical);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn header() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let header = "Generated by MyGame\n\nEdits may be overwritten!";
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .header(header)
+        };
+
+        let mut resource = builder().build()?;
+        assert_eq!(resource.header(), Some(header));
+
+        resource.update(|key_bindings| key_bindings.crouch = KeyCode::KeyX)?;
+
+        let expected = KeyBindings { crouch: KeyCode::KeyX, ..KeyBindings::default() };
+        let content = std::fs::read_to_string(&path)?;
+        assert_eq!(
+            content,
+            format!(
+                "# Generated by MyGame\n#\n# Edits may be overwritten!\n{}",
+                std::str::from_utf8(&StorageFormat::Toml.serialize("key bindings", &expected)?)?,
+            ),
+        );
+
+        let resource = builder().build()?;
+        assert_eq!(resource.get(), &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn header_json() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.json");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(KeyBindings::default())
+            .header("Generated by MyGame")
+            .build()?;
+
+        assert_eq!(
+            std::fs::read(&path)?,
+            StorageFormat::Json.serialize("key bindings", resource.get())?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn relative_to_exe() -> anyhow::Result<()> {
+        let executable_directory = std::env::current_exe()?.parent().unwrap().to_owned();
+
+        let tempdir = tempfile::tempdir_in(&executable_directory)?;
+        let relative_path =
+            PathBuf::from(tempdir.path().file_name().unwrap()).join("key-bindings.toml");
+        assert!(relative_path.is_relative());
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&relative_path)
+            .default(KeyBindings::default())
+            .relative_to_exe(true)
+            .build()?;
+
+        let expected_path = executable_directory.join(&relative_path);
+        assert!(expected_path.exists());
+        assert_eq!(resource.storage(), &Storage::Filesystem { path: expected_path });
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn replace() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let new_key_bindings = KeyBindings { jump: KeyCode::KeyW, crouch: KeyCode::KeyS };
+        let previous_key_bindings = resource.replace(new_key_bindings.clone())?;
+
+        assert_eq!(previous_key_bindings, Some(KeyBindings::default()));
+        assert_eq!(resource.get(), &new_key_bindings);
+        assert_eq!(
+            std::fs::read(&path)?,
+            StorageFormat::Toml.serialize("key bindings", &new_key_bindings)?,
+        );
+
+        resource.unload_without_persisting();
+
+        let previous_key_bindings = resource.replace(KeyBindings::default())?;
+        assert_eq!(previous_key_bindings, None);
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn replace_failure() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        // make writes fail by replacing the file with a directory
+        std::fs::remove_file(&path)?;
+        std::fs::create_dir(&path)?;
+
+        let new_key_bindings = KeyBindings { jump: KeyCode::KeyW, crouch: KeyCode::KeyS };
+
+        assert!(resource.replace(new_key_bindings).is_err());
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn auto() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let json_path = tempdir.path().join("key-bindings.json");
+        let json_key_bindings = KeyBindings { jump: KeyCode::KeyJ, ..KeyBindings::default() };
+        std::fs::write(&json_path, serde_json::to_string(&json_key_bindings)?)?;
+
+        let toml_path = tempdir.path().join("key-bindings.toml");
+        let toml_key_bindings = KeyBindings { crouch: KeyCode::KeyX, ..KeyBindings::default() };
+        std::fs::write(&toml_path, toml::to_string(&toml_key_bindings)?)?;
+
+        let builder = |path: &PathBuf| {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Auto)
+                .path(path)
+                .default(KeyBindings::default())
+        };
+
+        let resource = builder(&json_path).build()?;
+        assert_eq!(resource.format(), StorageFormat::Json);
+        assert_eq!(resource.get(), &json_key_bindings);
+
+        let resource = builder(&toml_path).build()?;
+        assert_eq!(resource.format(), StorageFormat::Toml);
+        assert_eq!(resource.get(), &toml_key_bindings);
+
+        let missing_path = tempdir.path().join("key-bindings");
+        let error = builder(&missing_path).build().unwrap_err();
+        assert!(matches!(error, PersistenceError::AmbiguousStorageFormat));
+        assert!(!missing_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn is_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .revertible(true)
+            .build()?;
+        assert_eq!(resource.is_default(), Some(true));
+
+        resource.update(|key_bindings| key_bindings.jump = KeyCode::KeyJ)?;
+        assert_eq!(resource.is_default(), Some(false));
+
+        resource.revert_to_default()?;
+        assert_eq!(resource.is_default(), Some(true));
+
+        resource.unload()?;
+        assert_eq!(resource.is_default(), None);
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+        assert_eq!(resource.is_default(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn merge_bytes() -> anyhow::Result<()> {
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Audio {
+            volume: f64,
+            muted: bool,
+        }
+
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Config {
+            audio: Audio,
+            servers: Vec<String>,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("config.json");
+
+        let local = Config {
+            audio: Audio { volume: 0.8, muted: true },
+            servers: vec!["eu".to_owned(), "us".to_owned()],
+        };
+        std::fs::write(&path, serde_json::to_string(&local)?)?;
+
+        let mut resource = Persistent::<Config>::builder()
+            .name("config")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Config::default())
+            .build()?;
+
+        resource.merge_bytes(br#"{ "audio": { "volume": 0.5 }, "servers": ["asia"] }"#)?;
+
+        let expected_resource =
+            Config { audio: Audio { volume: 0.5, muted: true }, servers: vec!["asia".to_owned()] };
+        assert_eq!(resource.get(), &expected_resource);
+
+        let expected_content = serde_json::to_string(&expected_resource)?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content, actual_content);
+
+        assert!(matches!(
+            resource.merge_bytes(br#"{ "audio": { "volume": "loud" } }"#),
+            Err(PersistenceError::JsonDeserialization(_)),
+        ));
+        assert_eq!(resource.get(), &expected_resource);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "toml", feature = "testing"))]
+    fn from_str() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let resource = Persistent::<KeyBindings>::from_str(
+            "key bindings",
+            StorageFormat::Toml,
+            Storage::Filesystem { path: path.clone() },
+            "jump = \"KeyJ\"\ncrouch = \"KeyC\"\n",
+        )?;
+        assert_eq!(resource.get(), &KeyBindings { jump: KeyCode::KeyJ, ..KeyBindings::default() });
+
+        let resource = Persistent::<KeyBindings>::from_str(
+            "key bindings",
+            StorageFormat::Toml,
+            Storage::Filesystem { path: path.clone() },
+            "invalid key bindings",
+        )?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        let expected_content = toml::to_string(&KeyBindings::default())?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ini")]
+    fn ini_nested_structs() -> anyhow::Result<()> {
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Audio {
+            volume: f64,
+            muted: bool,
+        }
+
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Config {
+            nickname: String,
+            audio: Audio,
+        }
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("config.ini");
+
+        let builder = || {
+            Persistent::<Config>::builder()
+                .name("config")
+                .format(StorageFormat::Ini)
+                .path(&path)
+                .default(Config::default())
+        };
+
+        let error = builder().build().err().unwrap();
+        assert!(error.is_serialization());
+        assert_eq!(error.format(), Some(StorageFormat::Ini));
+        assert_eq!(
+            error.to_string(),
+            "INI only supports flat resources but field audio is serialized as a struct",
+        );
+        assert!(!path.exists());
+
+        let error = builder().ini_options(IniOptions { check_shape: false }).build().err().unwrap();
+        assert!(matches!(error, PersistenceError::IniSerialization(_)));
+        assert!(!path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "toml"))]
+    fn follow_symlinks() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let target = tempdir.path().join("target.toml");
+        let link = tempdir.path().join("key-bindings.toml");
+
+        let original = toml::to_string(&KeyBindings::default())?;
+        std::fs::write(&target, &original)?;
+        std::os::unix::fs::symlink(&target, &link)?;
+
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&link)
+                .default(KeyBindings::default())
+        };
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+
+        let mut resource = builder().follow_symlinks(false).build()?;
+        assert!(!resource.follows_symlinks());
+
+        let error = resource.set(new_resource.clone()).unwrap_err();
+        assert!(matches!(error, PersistenceError::Symlink(_)));
+        assert_eq!(std::fs::read_to_string(&target)?, original);
+
+        let mut resource = builder().build()?;
+        assert!(resource.follows_symlinks());
+
+        resource.set(new_resource.clone())?;
+        assert!(std::fs::symlink_metadata(&link)?.file_type().is_symlink());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&target)?)?,
+            new_resource,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn from_config() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        let mut config =
+            PersistentConfig::new("key bindings", StorageFormat::Toml, storage.clone());
+        assert!(config.loaded);
+        assert!(!config.revertible);
+        assert!(!config.revert_to_default_on_deserialization_errors);
+
+        config.revertible = true;
+        config.revert_to_default_on_deserialization_errors = true;
+
+        let from_config = Persistent::from_config(config, KeyBindings::default())?;
+        let from_new = Persistent::new(
+            "key bindings",
+            StorageFormat::Toml,
+            storage,
+            true,
+            KeyBindings::default(),
+            true,
+            true,
+        )?;
+
+        assert_eq!(from_config.info(), from_new.info());
+        assert_eq!(from_config.get(), from_new.get());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn skip_redundant_writes() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .skip_redundant_writes(true)
+            .build()?;
+
+        assert!(resource.skips_redundant_writes());
+
+        let persisted = Arc::new(Mutex::new(Vec::new()));
+        {
+            let persisted = persisted.clone();
+            resource.on_persisted(move |key_bindings: &KeyBindings| {
+                persisted.lock().unwrap().push(key_bindings.clone());
+            });
+        }
+
+        let modified = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(86400);
+        std::fs::File::options().write(true).open(&path)?.set_modified(modified)?;
+
+        resource.jump = KeyCode::KeyJ;
+        resource.jump = KeyCode::Space;
+        resource.persist()?;
+
+        assert_eq!(resource.get(), &KeyBindings::default());
+        assert_eq!(std::fs::metadata(&path)?.modified()?, modified);
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            KeyBindings::default()
+        );
+        assert!(persisted.lock().unwrap().is_empty());
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::KeyC };
+        resource.set(new_resource.clone())?;
+        assert_ne!(std::fs::metadata(&path)?.modified()?, modified);
+        assert_eq!(*persisted.lock().unwrap(), vec![new_resource]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn serialize_and_deserialize() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        resource.jump = KeyCode::KeyJ;
+
+        let serialized = serde_json::to_vec(&resource)?;
+        assert_eq!(serialized, serde_json::to_vec(resource.get())?);
+
+        let deserialized =
+            serde_json::from_slice::<UnconfiguredPersistent<KeyBindings>>(&serialized)?;
+        assert_eq!(deserialized.get(), resource.get());
+
+        let other_path = tempdir.path().join("other-key-bindings.toml");
+        let config = PersistentConfig::new(
+            "other key bindings",
+            StorageFormat::Toml,
+            Storage::Filesystem { path: other_path.clone() },
+        );
+
+        let configured = deserialized.configure(config)?;
+        assert_eq!(configured.name(), "other key bindings");
+        assert_eq!(configured.get(), resource.get());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&other_path)?)?,
+            *resource.get(),
+        );
+
+        resource.unload_without_persisting();
+        assert!(serde_json::to_vec(&resource).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn undo() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .history(2)
+            .build()?;
+
+        assert_eq!(resource.history_limit(), 2);
+
+        let first = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::KeyC };
+        let second = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+
+        resource.set(first.clone())?;
+        resource.set(second.clone())?;
+
+        resource.undo()?;
+        assert_eq!(resource.get(), &first);
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, first);
+
+        resource.undo()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            KeyBindings::default(),
+        );
+
+        resource.undo()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn undo_after_revert_to_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .revertible(true)
+            .history(3)
+            .build()?;
+
+        let first = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::KeyC };
+        let second = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+
+        resource.set(first.clone())?;
+        resource.set(second.clone())?;
+
+        resource.revert_to_default()?;
+        assert_eq!(resource.get(), &KeyBindings::default());
+
+        resource.undo()?;
+        assert_eq!(resource.get(), &second);
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, second);
+
+        resource.undo()?;
+        assert_eq!(resource.get(), &first);
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, first);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn retain_default() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let builder = || {
+            Persistent::<KeyBindings>::builder()
+                .name("key bindings")
+                .format(StorageFormat::Toml)
+                .path(&path)
+                .default(KeyBindings::default())
+                .revertible(false)
+        };
+
+        let resource = builder().build()?;
+        assert!(!resource.retains_default());
+        assert_eq!(resource.default(), None);
+
+        let mut resource = builder().retain_default(true).build()?;
+        assert!(resource.retains_default());
+        assert!(!resource.is_revertible());
+        assert_eq!(resource.default(), Some(&KeyBindings::default()));
+
+        resource.update(|key_bindings| key_bindings.jump = KeyCode::KeyJ)?;
+        assert_eq!(resource.default().map(|default| default.jump), Some(KeyCode::Space));
+        assert_eq!(resource.is_default(), Some(false));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn format_as_string() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        assert_eq!(resource.format_as_string()?, std::fs::read_to_string(&path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn format_as_string_binary() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.bin");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Bincode)
+            .path(&path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let error = resource.format_as_string().unwrap_err();
+        assert!(matches!(error, PersistenceError::BinaryStorageFormat(StorageFormat::Bincode)));
+        assert!(!error.is_serde());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn persist_with_timeout() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let directory = tempdir.path().join("settings");
+        let path = directory.join("key-bindings.toml");
+
+        std::fs::create_dir(&directory)?;
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .create_dirs(false)
+            .build()?;
+
+        resource.jump = KeyCode::KeyJ;
+        resource.persist_with_timeout(std::time::Duration::from_secs(10))?;
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            *resource.get(),
+        );
+
+        assert_eq!(std::fs::read_dir(&directory)?.count(), 1);
+
+        // missing directories are checked before writing on a separate thread
+        std::fs::remove_dir_all(&directory)?;
+
+        let error = resource.persist_with_timeout(std::time::Duration::from_secs(10)).unwrap_err();
+        assert!(
+            matches!(error, PersistenceError::MissingDirectory(missing) if missing == directory)
+        );
+        assert!(!directory.exists());
+
+        // writing on a separate thread cannot finish without waiting for it,
+        // so the write is abandoned but it still finishes in the background
+        std::fs::create_dir(&directory)?;
+
+        resource.crouch = KeyCode::ControlLeft;
+        let error = resource.persist_with_timeout(std::time::Duration::ZERO).unwrap_err();
+        assert!(matches!(error, PersistenceError::Timeout(timeout) if timeout.is_zero()));
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| toml::from_str::<KeyBindings>(&content).ok())
+            .as_ref()
+            != Some(resource.get())
+        {
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        assert_eq!(std::fs::read_dir(&directory)?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn temp_dir() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let temp_dir = tempfile::tempdir()?;
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .temp_dir(Some(temp_dir.path().to_path_buf()))
+            .default(KeyBindings::default())
+            .build()?;
+        assert_eq!(resource.temp_dir(), Some(temp_dir.path()));
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, new_resource);
+        assert_eq!(std::fs::read_dir(tempdir.path())?.count(), 1);
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "toml", target_os = "linux"))]
+    fn temp_dir_on_another_filesystem() -> anyhow::Result<()> {
+        use std::os::unix::fs::MetadataExt;
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let temp_dir = match tempfile::tempdir_in("/dev/shm") {
+            Ok(temp_dir) => temp_dir,
+            Err(_) => return Ok(()),
+        };
+        if std::fs::metadata(temp_dir.path())?.dev() == std::fs::metadata(tempdir.path())?.dev() {
+            return Ok(());
+        }
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .temp_dir(Some(temp_dir.path().to_path_buf()))
+            .default(KeyBindings::default())
+            .build()?;
+
+        // file is replaced rather than truncated in place, so its hard links keep the old content
+        let link = tempdir.path().join("key-bindings.link.toml");
+        std::fs::hard_link(&path, &link)?;
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        assert_eq!(toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?, new_resource);
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&link)?)?,
+            KeyBindings::default(),
+        );
+        assert_eq!(std::fs::read_dir(tempdir.path())?.count(), 2);
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn revert_to_default_returning() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .revertible(true)
+            .build()?;
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        let previous = resource.revert_to_default_returning()?;
+        assert_eq!(previous, Some(new_resource));
+
+        assert_eq!(resource.get(), &KeyBindings::default());
+        assert_eq!(
+            toml::from_str::<KeyBindings>(&std::fs::read_to_string(&path)?)?,
+            KeyBindings::default(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn yaml_alias_limit() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Document(std::collections::BTreeMap<String, serde_yaml::Value>);
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("document.yaml");
+
+        let mut bomb =
+            String::from("a: &a [\"lol\", \"lol\", \"lol\", \"lol\", \"lol\", \"lol\"]\n");
+        for (previous, current) in ["a", "b", "c", "d", "e", "f", "g", "h"]
+            .iter()
+            .zip(["b", "c", "d", "e", "f", "g", "h", "i"])
+        {
+            let aliases = vec![format!("*{}", previous); 6].join(", ");
+            bomb.push_str(&format!("{}: &{} [{}]\n", current, current, aliases));
+        }
+        std::fs::write(&path, &bomb)?;
+
+        let builder = || {
+            Persistent::<Document>::builder()
+                .name("document")
+                .format(StorageFormat::Yaml)
+                .path(&path)
+                .default(Document::default())
+        };
+
+        let error = builder().yaml_alias_limit(16).build().unwrap_err();
+        assert!(matches!(
+            error,
+            PersistenceError::YamlAliasLimitExceeded { aliases: 48, limit: 16 }
+        ));
+        assert!(error.is_deserialization());
+        assert_eq!(error.format(), Some(StorageFormat::Yaml));
+
+        std::fs::write(
+            &path,
+            "defaults: &defaults [1, 2] # *not-an-alias\ncopy: *defaults\ntext: '*not-an-alias'\n",
+        )?;
+
+        let resource = builder().yaml_alias_limit(1).build()?;
+        assert_eq!(resource.0["copy"], resource.0["defaults"]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn yaml_alias_limit_flow_mappings_and_block_scalars() -> anyhow::Result<()> {
+        #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+        struct Document(std::collections::BTreeMap<String, serde_yaml::Value>);
+
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("document.yaml");
+
+        let builder = || {
+            Persistent::<Document>::builder()
+                .name("document")
+                .format(StorageFormat::Yaml)
+                .path(&path)
+                .default(Document::default())
+        };
+
+        std::fs::write(
+            &path,
+            "defaults: &defaults {volume: 1, muted: false}\n\
+             copies: {first: *defaults, second: *defaults}\n\
+             list: [*defaults,*defaults]\n",
+        )?;
+
+        let error = builder().yaml_alias_limit(3).build().unwrap_err();
+        assert!(matches!(error, PersistenceError::YamlAliasLimitExceeded { aliases: 4, limit: 3 }));
+
+        let resource = builder().yaml_alias_limit(4).build()?;
+        assert_eq!(resource.0["copies"]["second"], resource.0["defaults"]);
+        assert_eq!(resource.0["list"][1], resource.0["defaults"]);
+
+        // words starting with asterisks in block scalars are counted as aliases
+        std::fs::write(&path, "literal: |\n  *bold* text\nfolded: >\n  more *bold* text\n")?;
+
+        let error = builder().yaml_alias_limit(1).build().unwrap_err();
+        assert!(matches!(error, PersistenceError::YamlAliasLimitExceeded { aliases: 2, limit: 1 }));
+
+        let resource = builder().yaml_alias_limit(2).build()?;
+        assert_eq!(resource.0["literal"], serde_yaml::Value::from("*bold* text\n"));
+
+        Ok(())
+    }
 }
 
 #[cfg(target_family = "wasm")]
@@ -1181,6 +4226,48 @@ mod wasm {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    #[cfg(feature = "toml")]
+    fn cache() -> anyhow::Result<()> {
+        LocalStorage::clear();
+
+        let loads = Arc::new(Mutex::new(0));
+
+        let mut resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path("local/key-bindings.toml")
+            .default(KeyBindings::default())
+            .on_load({
+                let loads = loads.clone();
+                move |_| *loads.lock().unwrap() += 1
+            })
+            .cache(true)
+            .build()?;
+
+        assert!(resource.is_cached());
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+        resource.set(new_resource.clone())?;
+
+        let loads_before_reloading = *loads.lock().unwrap();
+
+        resource.reload()?;
+        resource.reload()?;
+
+        assert_eq!(*loads.lock().unwrap(), loads_before_reloading);
+        assert_eq!(resource.get(), &new_resource);
+
+        let external_resource = KeyBindings { jump: KeyCode::KeyK, crouch: KeyCode::KeyC };
+        resource.storage().write_raw(toml::to_string(&external_resource)?.as_bytes())?;
+
+        resource.reload()?;
+
+        assert_eq!(*loads.lock().unwrap(), loads_before_reloading + 1);
+        assert_eq!(resource.get(), &external_resource);
+
+        Ok(())
+    }
 
     wasm_bindgen_test_configure!(run_in_browser);
 }
@@ -0,0 +1,75 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+    #[cfg(feature = "protobuf")]
+    use prost::Message;
+
+    // the same as what prost-build generates for:
+    //
+    // message Profile {
+    //   string nickname = 1;
+    //   uint32 level = 2;
+    //   repeated string achievements = 3;
+    // }
+    #[cfg(feature = "protobuf")]
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Profile {
+        #[prost(string, tag = "1")]
+        pub nickname: String,
+        #[prost(uint32, tag = "2")]
+        pub level: u32,
+        #[prost(string, repeated, tag = "3")]
+        pub achievements: Vec<String>,
+    }
+
+    #[test]
+    #[cfg(feature = "protobuf")]
+    fn protobuf() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("profile.pb");
+
+        let builder = || {
+            Persistent::<Protobuf<Profile>>::builder()
+                .name("profile")
+                .format(StorageFormat::Protobuf)
+                .path(&path)
+                .default(Protobuf(Profile::default()))
+        };
+
+        let mut resource = builder().build()?;
+        assert_eq!(resource.get(), &Protobuf(Profile::default()));
+
+        let profile = Profile {
+            nickname: "alice".to_owned(),
+            level: 42,
+            achievements: vec!["first blood".to_owned(), "pacifist".to_owned()],
+        };
+        resource.set(Protobuf(profile.clone()))?;
+
+        assert_eq!(std::fs::read(&path)?, profile.encode_to_vec());
+        assert_eq!(Profile::decode(std::fs::read(&path)?.as_slice())?, profile);
+
+        let resource = builder().build()?;
+        assert_eq!(resource.get().clone().into_inner(), profile);
+        assert_eq!(resource.level, 42);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "protobuf", feature = "json"))]
+    fn protobuf_in_other_formats() -> anyhow::Result<()> {
+        let profile = Protobuf(Profile { nickname: "bob".to_owned(), level: 7, ..default() });
+
+        let serialized = StorageFormat::Json.serialize("profile", &profile)?;
+        let deserialized =
+            StorageFormat::Json.deserialize::<Protobuf<Profile>>("profile", &serialized)?;
+
+        assert_eq!(deserialized, profile);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+
+    /// A handle to a loaded texture, which cannot be serialized.
+    #[derive(Debug)]
+    pub struct TextureHandle(pub Arc<Vec<u8>>);
+
+    #[derive(Debug)]
+    pub struct Avatar {
+        pub nickname: String,
+        pub texture: Option<TextureHandle>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    pub struct AvatarProxy {
+        pub nickname: String,
+    }
+
+    impl Proxy for Avatar {
+        type Proxy = AvatarProxy;
+
+        fn to_proxy(&self) -> AvatarProxy {
+            AvatarProxy { nickname: self.nickname.clone() }
+        }
+
+        fn from_proxy(proxy: AvatarProxy) -> Avatar {
+            Avatar { nickname: proxy.nickname, texture: None }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn proxied() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("avatar.toml");
+
+        let mut resource = Persistent::<Proxied<Avatar>>::builder()
+            .name("avatar")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(Proxied(Avatar { nickname: "alice".to_owned(), texture: None }))
+            .build()?;
+
+        resource.nickname = "bob".to_owned();
+        resource.texture = Some(TextureHandle(Arc::new(vec![0xff; 4])));
+        assert_eq!(resource.texture.as_ref().map(|texture| texture.0.len()), Some(4));
+        resource.persist()?;
+
+        let expected_content = toml::to_string(&AvatarProxy { nickname: "bob".to_owned() })?;
+        let actual_content = std::fs::read_to_string(&path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+
+        resource.reload()?;
+
+        assert_eq!(resource.nickname, "bob");
+        assert!(resource.texture.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+    use std::time::Duration;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Resource, Serialize)]
+    pub struct Settings {
+        #[serde(with = "bevy_persistent::serde_helpers::duration_seconds")]
+        pub autosave_interval: Duration,
+        #[serde(with = "bevy_persistent::serde_helpers::duration_human")]
+        pub session_timeout: Duration,
+        #[serde(with = "bevy_persistent::serde_helpers::byte_size")]
+        pub cache_size: u64,
+    }
+
+    impl Default for Settings {
+        fn default() -> Settings {
+            Settings {
+                autosave_interval: Duration::from_millis(1500),
+                session_timeout: Duration::from_secs(5405),
+                cache_size: 512 * 1024,
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn json() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings.json");
+
+        let persistent = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Settings::default())
+            .build()?;
+
+        let content = std::fs::read_to_string(&path)?;
+        let value = serde_json::from_str::<serde_json::Value>(&content)?;
+
+        assert_eq!(value["autosave_interval"], 1.5);
+        assert_eq!(value["session_timeout"], "1h 30m 5s");
+        assert_eq!(value["cache_size"], "512 KiB");
+
+        let reloaded = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Json)
+            .path(&path)
+            .default(Settings { autosave_interval: Duration::ZERO, ..Settings::default() })
+            .build()?;
+
+        assert_eq!(reloaded.get(), persistent.get());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn toml() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings.toml");
+
+        std::fs::write(
+            &path,
+            "autosave_interval = 90.0\nsession_timeout = \"2m30s 250ms\"\ncache_size = \"1.5 MB\"\n",
+        )?;
+
+        let persistent = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(Settings::default())
+            .build()?;
+
+        assert_eq!(persistent.autosave_interval, Duration::from_secs(90));
+        assert_eq!(persistent.session_timeout, Duration::from_millis(150_250));
+        assert_eq!(persistent.cache_size, 1_500_000);
+
+        persistent.persist()?;
+
+        let content = std::fs::read_to_string(&path)?;
+        assert!(content.contains("session_timeout = \"2m 30s 250ms\""));
+        assert!(content.contains("cache_size = \"1500000 B\""));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn invalid() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("settings.toml");
+
+        std::fs::write(
+            &path,
+            "autosave_interval = 1.0\nsession_timeout = \"5 fortnights\"\ncache_size = \"1 KiB\"\n",
+        )?;
+
+        let result = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Toml)
+            .path(&path)
+            .default(Settings::default())
+            .build();
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn path() {
+        let slots = SaveSlots::<KeyBindings>::new("key bindings", StorageFormat::Toml, "saves");
+
+        assert_eq!(slots.path(3), PathBuf::from("saves").join("slot-3.toml"));
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn occupied() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let directory = tempdir.path().join("saves");
+
+        let slots = SaveSlots::<KeyBindings>::new("key bindings", StorageFormat::Toml, &directory);
+
+        assert!(slots.occupied()?.is_empty());
+
+        let first = slots.builder(0).default(KeyBindings::default()).build()?;
+        let third = slots.builder(2).default(KeyBindings::default()).build()?;
+
+        assert_eq!(first.name(), "key bindings slot 0");
+        assert_eq!(third.name(), "key bindings slot 2");
+
+        std::fs::write(directory.join("notes.txt"), "")?;
+        std::fs::write(directory.join("slot-x.toml"), "")?;
+        std::fs::write(directory.join("slot-1.json"), "")?;
+
+        assert_eq!(slots.occupied()?, vec![0, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn load_all() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let directory = tempdir.path().join("saves");
+
+        let slots = SaveSlots::<KeyBindings>::new("key bindings", StorageFormat::Toml, &directory);
+
+        assert!(slots.load_all()?.is_empty());
+
+        let new_resource = KeyBindings { jump: KeyCode::KeyJ, crouch: KeyCode::ControlLeft };
+
+        slots.builder(0).default(KeyBindings::default()).build()?;
+        slots.builder(3).default(new_resource.clone()).build()?;
+        std::fs::write(slots.path(1), "jump = ")?;
+
+        let loaded = slots.load_all()?;
+        assert_eq!(loaded.keys().copied().collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(loaded[&0], KeyBindings::default());
+        assert_eq!(loaded[&3], new_resource);
+
+        let (loaded, errors) = slots.load_all_with_errors()?;
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(errors.keys().copied().collect::<Vec<_>>(), vec![1]);
+        assert!(errors[&1].is_deserialization());
+
+        Ok(())
+    }
+}
@@ -42,6 +42,119 @@ mod native {
         Ok(())
     }
 
+    #[test]
+    fn filesystem_modified() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        assert!(storage.modified().is_none());
+
+        std::fs::write(&path, "".as_bytes()).unwrap();
+
+        assert_eq!(storage.modified(), Some(std::fs::metadata(&path)?.modified()?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn filesystem_remove() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        std::fs::write(&path, "".as_bytes()).unwrap();
+        assert!(storage.occupied());
+
+        storage.remove()?;
+        assert!(!storage.occupied());
+
+        storage.remove()?;
+        assert!(!storage.occupied());
+
+        Ok(())
+    }
+
+    #[test]
+    fn filesystem_copy_to() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let source_path = tempdir.path().join("key-bindings.toml");
+        let destination_path = tempdir.path().join("snapshots").join("key-bindings.toml");
+
+        let content = "# hand edited\njump = \"Space\"\ncrouch = \"KeyC\"\n";
+        std::fs::write(&source_path, content)?;
+
+        let source = Storage::Filesystem { path: source_path.clone() };
+        let destination = Storage::Filesystem { path: destination_path.clone() };
+
+        source.copy_to(&destination)?;
+
+        assert_eq!(std::fs::read(&source_path)?, std::fs::read(&destination_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn filesystem_raw() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("saves").join("world.bin");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        assert!(storage.read_raw().is_err());
+
+        let bytes = vec![0x00, 0xff, b'\n', 0xc3, 0x28, b'\r', 0x7f];
+        storage.write_raw(&bytes)?;
+
+        assert_eq!(std::fs::read(&path)?, bytes);
+        assert_eq!(storage.read_raw()?, bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn filesystem_read_tail() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("telemetry.log");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        assert!(storage.read_tail(16).is_err());
+
+        {
+            use std::io::Write;
+
+            let mut file = std::fs::File::create(&path)?;
+            for index in 0..100_000 {
+                writeln!(file, "record {}", index)?;
+            }
+        }
+
+        assert_eq!(storage.read_tail(13)?, b"record 99999\n");
+        assert_eq!(storage.read_tail(0)?, b"");
+
+        let length = std::fs::metadata(&path)?.len() as usize;
+        assert_eq!(storage.read_tail(length * 2)?, std::fs::read(&path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn filesystem_write() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.toml");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        let length = storage.write("key bindings", StorageFormat::Toml, &KeyBindings::default())?;
+
+        assert_eq!(length, std::fs::metadata(&path)?.len() as usize);
+        assert_eq!(
+            length,
+            StorageFormat::Toml.serialize("key bindings", &KeyBindings::default())?.len(),
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn filesystem_display() -> anyhow::Result<()> {
         let tempdir = tempfile::tempdir()?;
@@ -52,6 +165,103 @@ mod native {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))),
+    ))]
+    fn reveal_command() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("saves").join("world.bin");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        let command = storage.reveal_command()?;
+        let arguments = command.get_args().collect::<Vec<_>>();
+
+        #[cfg(target_os = "windows")]
+        {
+            let mut argument = std::ffi::OsString::from("/select,");
+            argument.push(&path);
+
+            assert_eq!(command.get_program(), "explorer");
+            assert_eq!(arguments, [argument.as_os_str()]);
+        }
+        #[cfg(target_os = "macos")]
+        {
+            assert_eq!(command.get_program(), "open");
+            assert_eq!(arguments, [std::ffi::OsStr::new("-R"), path.as_os_str()]);
+        }
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        {
+            assert_eq!(command.get_program(), "xdg-open");
+            assert_eq!(arguments, [path.parent().unwrap().as_os_str()]);
+
+            let storage = Storage::Filesystem { path: PathBuf::from("world.bin") };
+            let command = storage.reveal_command()?;
+            assert_eq!(command.get_args().collect::<Vec<_>>(), [std::ffi::OsStr::new(".")]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "macos",
+        all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))),
+    )))]
+    fn reveal_command_unsupported() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("saves").join("world.bin");
+        let storage = Storage::Filesystem { path };
+
+        assert!(matches!(storage.reveal_command(), Err(PersistenceError::FileManagerUnavailable)));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn filesystem_write_large() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("large.json");
+        let storage = Storage::Filesystem { path: path.clone() };
+
+        let resource = (0..1_000_000).collect::<Vec<u32>>();
+        let length = storage.write("large", StorageFormat::Json, &resource)?;
+
+        assert_eq!(length, std::fs::metadata(&path)?.len() as usize);
+        assert_eq!(storage.read::<Vec<u32>>("large", StorageFormat::Json)?, resource);
+
+        let entries = std::fs::read_dir(tempdir.path())?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(entries.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn filesystem_write_streamed_with_options() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let path = tempdir.path().join("key-bindings.yaml");
+
+        let resource = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Yaml)
+            .path(&path)
+            .default(KeyBindings::default())
+            .line_ending(LineEnding::CrLf)
+            .header("Key bindings\n\nEdit with care.")
+            .build()?;
+
+        let content = std::fs::read(&path)?;
+        assert!(content.starts_with(b"# Key bindings\r\n#\r\n# Edit with care.\r\n"));
+        assert_eq!(content, resource.preview()?);
+
+        Ok(())
+    }
 }
 
 #[cfg(target_family = "wasm")]
@@ -93,6 +303,87 @@ mod wasm {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    #[cfg(feature = "ron")]
+    fn local_storage_quota_exceeded() -> anyhow::Result<()> {
+        LocalStorage::clear();
+
+        let key = "huge.ron";
+        let storage = Storage::LocalStorage { key: key.to_owned() };
+
+        let huge = "a".repeat(6 * 1024 * 1024);
+        let result = storage.write("huge", StorageFormat::Ron, &huge);
+
+        assert!(matches!(result, Err(PersistenceError::QuotaExceeded { .. })));
+        assert!(!storage.occupied());
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn local_storage_quota_exceeded_is_quota() -> anyhow::Result<()> {
+        LocalStorage::clear();
+
+        let storage = Storage::LocalStorage { key: "huge.txt".to_owned() };
+
+        // raw writes don't estimate the quota, so the browser storage actually overflows
+        let huge = "a".repeat(6 * 1024 * 1024);
+        let error = storage.write_raw(huge.as_bytes()).unwrap_err();
+
+        assert!(matches!(
+            error,
+            PersistenceError::Browser(gloo_storage::errors::StorageError::JsError(_)),
+        ));
+        assert!(error.is_quota());
+        assert!(!error.is_serde());
+        assert!(!storage.occupied());
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    fn local_storage_raw() -> anyhow::Result<()> {
+        LocalStorage::clear();
+
+        let key = "key-bindings.toml";
+        let storage = Storage::LocalStorage { key: key.to_owned() };
+
+        assert!(storage.read_raw().is_err());
+
+        let content = "# hand edited\njump = \"Space\"\ncrouch = \"KeyC\"\n";
+        storage.write_raw(content.as_bytes())?;
+
+        assert_eq!(LocalStorage::raw().get_item(key).unwrap().as_deref(), Some(content));
+        assert_eq!(storage.read_raw()?, content.as_bytes());
+
+        // invalid UTF-8 is stored as an array of bytes like binary storage formats
+        let bytes = [0x00, 0xff, 0xc3, 0x28];
+        storage.write_raw(&bytes)?;
+
+        assert_eq!(LocalStorage::raw().get_item(key).unwrap().as_deref(), Some("[0,255,195,40]"));
+        assert_eq!(storage.read_raw()?, bytes);
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn local_storage_write() -> anyhow::Result<()> {
+        LocalStorage::clear();
+
+        let resource = KeyBindings::default();
+
+        let storage = Storage::LocalStorage { key: "key-bindings.toml".to_owned() };
+        let length = storage.write("key bindings", StorageFormat::Toml, &resource)?;
+        assert_eq!(length, StorageFormat::Toml.serialize("key bindings", &resource)?.len());
+
+        let storage = Storage::LocalStorage { key: "key-bindings.json".to_owned() };
+        let length = storage.write("key bindings", StorageFormat::Json, &resource)?;
+        assert_eq!(length, StorageFormat::Json.serialize("key bindings", &resource)?.len());
+
+        Ok(())
+    }
+
     #[wasm_bindgen_test]
     fn local_storage_display() -> anyhow::Result<()> {
         let key = "key-bindings.toml";
@@ -133,6 +424,49 @@ mod wasm {
         Ok(())
     }
 
+    #[wasm_bindgen_test]
+    fn session_storage_raw() -> anyhow::Result<()> {
+        SessionStorage::clear();
+
+        let key = "key-bindings.toml";
+        let storage = Storage::SessionStorage { key: key.to_owned() };
+
+        assert!(storage.read_raw().is_err());
+
+        let content = "# hand edited\njump = \"Space\"\ncrouch = \"KeyC\"\n";
+        storage.write_raw(content.as_bytes())?;
+
+        assert_eq!(SessionStorage::raw().get_item(key).unwrap().as_deref(), Some(content));
+        assert_eq!(storage.read_raw()?, content.as_bytes());
+
+        // invalid UTF-8 is stored as an array of bytes like binary storage formats
+        let bytes = [0x00, 0xff, 0xc3, 0x28];
+        storage.write_raw(&bytes)?;
+
+        assert_eq!(SessionStorage::raw().get_item(key).unwrap().as_deref(), Some("[0,255,195,40]"));
+        assert_eq!(storage.read_raw()?, bytes);
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn session_storage_write() -> anyhow::Result<()> {
+        SessionStorage::clear();
+
+        let resource = KeyBindings::default();
+
+        let storage = Storage::SessionStorage { key: "key-bindings.toml".to_owned() };
+        let length = storage.write("key bindings", StorageFormat::Toml, &resource)?;
+        assert_eq!(length, StorageFormat::Toml.serialize("key bindings", &resource)?.len());
+
+        let storage = Storage::SessionStorage { key: "key-bindings.json".to_owned() };
+        let length = storage.write("key bindings", StorageFormat::Json, &resource)?;
+        assert_eq!(length, StorageFormat::Json.serialize("key bindings", &resource)?.len());
+
+        Ok(())
+    }
+
     #[wasm_bindgen_test]
     fn session_storage_display() -> anyhow::Result<()> {
         let key = "key-bindings.toml";
@@ -0,0 +1,171 @@
+mod common;
+use common::*;
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Default, Deserialize, PartialEq, Resource, Serialize)]
+    pub struct Scores {
+        pub scores: HashMap<(u32, u32), u32>,
+    }
+
+    fn entries(directory: &std::path::Path) -> anyhow::Result<Vec<String>> {
+        let mut entries = std::fs::read_dir(directory)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn commit() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let scores_path = tempdir.path().join("scores.json");
+
+        let mut key_bindings = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&key_bindings_path)
+            .default(KeyBindings::default())
+            .build()?;
+        let mut scores = Persistent::<Scores>::builder()
+            .name("scores")
+            .format(StorageFormat::Json)
+            .path(&scores_path)
+            .default(Scores::default())
+            .build()?;
+
+        key_bindings.jump = KeyCode::KeyJ;
+        scores.scores.clear();
+
+        let mut transaction = Transaction::new();
+        transaction.add(&key_bindings).add(&scores);
+        assert_eq!(transaction.len(), 2);
+
+        transaction.commit()?;
+
+        let expected_content = toml::to_string(key_bindings.get())?;
+        let actual_content = std::fs::read_to_string(&key_bindings_path)?;
+
+        assert_eq!(expected_content.trim(), actual_content.trim());
+        assert_eq!(entries(tempdir.path())?, ["key-bindings.toml", "scores.json"]);
+
+        // committed resources are treated as persisted, so they are not reloaded needlessly
+        assert!(!key_bindings.reload_if_changed()?);
+        assert!(!scores.reload_if_changed()?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn commit_serialization_failure() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let scores_path = tempdir.path().join("scores.json");
+
+        let mut key_bindings = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&key_bindings_path)
+            .default(KeyBindings::default())
+            .build()?;
+        let mut scores = Persistent::<Scores>::builder()
+            .name("scores")
+            .format(StorageFormat::Json)
+            .path(&scores_path)
+            .default(Scores::default())
+            .build()?;
+
+        let key_bindings_content = std::fs::read(&key_bindings_path)?;
+        let scores_content = std::fs::read(&scores_path)?;
+
+        key_bindings.jump = KeyCode::KeyJ;
+        // tuple keys cannot be serialized as JSON
+        scores.scores.insert((1, 2), 3);
+
+        let mut transaction = Transaction::new();
+        transaction.add(&key_bindings).add(&scores);
+
+        let error = transaction.commit().unwrap_err();
+        assert!(error.is_serialization());
+
+        assert_eq!(std::fs::read(&key_bindings_path)?, key_bindings_content);
+        assert_eq!(std::fs::read(&scores_path)?, scores_content);
+        assert_eq!(entries(tempdir.path())?, ["key-bindings.toml", "scores.json"]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn commit_write_failure() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let key_bindings_path = tempdir.path().join("key-bindings.toml");
+        let blocker_path = tempdir.path().join("blocker");
+
+        let mut key_bindings = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&key_bindings_path)
+            .default(KeyBindings::default())
+            .build()?;
+
+        let key_bindings_content = std::fs::read(&key_bindings_path)?;
+        std::fs::write(&blocker_path, "not a directory")?;
+
+        key_bindings.jump = KeyCode::KeyJ;
+
+        let mut transaction = Transaction::new();
+        transaction.add(&key_bindings).add_bytes(
+            Storage::Filesystem { path: blocker_path.join("statistics.toml") },
+            StorageFormat::Toml,
+            b"deaths = 3\n".to_vec(),
+        );
+
+        assert!(transaction.commit().is_err());
+
+        assert_eq!(std::fs::read(&key_bindings_path)?, key_bindings_content);
+        assert_eq!(entries(tempdir.path())?, ["blocker", "key-bindings.toml"]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn commit_missing_directory() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let directory = tempdir.path().join("config");
+        let key_bindings_path = directory.join("key-bindings.toml");
+
+        std::fs::create_dir(&directory)?;
+        let key_bindings = Persistent::<KeyBindings>::builder()
+            .name("key bindings")
+            .format(StorageFormat::Toml)
+            .path(&key_bindings_path)
+            .default(KeyBindings::default())
+            .create_dirs(false)
+            .build()?;
+        std::fs::remove_dir_all(&directory)?;
+
+        let mut transaction = Transaction::new();
+        transaction.add(&key_bindings);
+
+        let result = transaction.commit();
+        assert!(matches!(
+            result,
+            Err(PersistenceError::MissingDirectory(missing)) if missing == directory,
+        ));
+        assert!(!directory.exists());
+
+        Ok(())
+    }
+}